@@ -132,6 +132,59 @@ export type DefaultPolicy = {
         }
       ];
       args: [];
+    },
+    {
+      name: 'removeDevice';
+      discriminator: [42, 19, 175, 5, 67, 100, 238, 14];
+      accounts: [
+        {
+          name: 'walletDevice';
+          signer: true;
+        },
+        {
+          name: 'removedWalletDevice';
+        },
+        {
+          name: 'policy';
+          pda: {
+            seeds: [
+              {
+                kind: 'const';
+                value: [112, 111, 108, 105, 99, 121];
+              },
+              {
+                kind: 'account';
+                path: 'walletDevice';
+              }
+            ];
+          };
+        },
+        {
+          name: 'removedPolicy';
+          writable: true;
+          pda: {
+            seeds: [
+              {
+                kind: 'const';
+                value: [112, 111, 108, 105, 99, 121];
+              },
+              {
+                kind: 'account';
+                path: 'removedWalletDevice';
+              }
+            ];
+          };
+        },
+        {
+          name: 'rentRefund';
+          writable: true;
+        },
+        {
+          name: 'lazorkit';
+          address: 'J6Big9w1VNeRZgDWH5qmNz2Nd6XFq5QeZbqC8caqSE5W';
+        }
+      ];
+      args: [];
     }
   ];
   accounts: [
@@ -157,6 +210,24 @@ export type DefaultPolicy = {
     }
   ];
   types: [
+    {
+      name: 'deviceKind';
+      docs: ['Key type a wallet_device authenticates with'];
+      type: {
+        kind: 'enum';
+        variants: [
+          {
+            name: 'passkey';
+          },
+          {
+            name: 'ed25519';
+          },
+          {
+            name: 'secp256k1';
+          }
+        ];
+      };
+    },
     {
       name: 'policy';
       type: {
@@ -176,7 +247,7 @@ export type DefaultPolicy = {
     {
       name: 'walletDevice';
       docs: [
-        'Account that stores a wallet_device (passkey) used to authenticate to a smart wallet'
+        'Account that stores a wallet_device (passkey or keypair) used to authenticate to a smart wallet'
       ];
       type: {
         kind: 'struct';
@@ -184,7 +255,8 @@ export type DefaultPolicy = {
           {
             name: 'passkeyPubkey';
             docs: [
-              'The public key of the passkey for this wallet_device that can authorize transactions'
+              'The public key of this wallet_device that can authorize transactions. Ed25519 keys occupy',
+              'the first 32 bytes and Ethereum addresses the first 20 bytes; the rest is zero.'
             ];
             type: {
               array: ['u8', 33];
@@ -204,6 +276,64 @@ export type DefaultPolicy = {
             name: 'bump';
             docs: ['Bump seed for PDA derivation'];
             type: 'u8';
+          },
+          {
+            name: 'kind';
+            docs: ['Key type of `passkey_pubkey`'];
+            type: {
+              defined: {
+                name: 'deviceKind';
+              };
+            };
+          },
+          {
+            name: 'rpIdHash';
+            docs: [
+              'sha256 of the relying party ID this passkey was created for (None = use config)'
+            ];
+            type: {
+              option: {
+                array: ['u8', 32];
+              };
+            };
+          },
+          {
+            name: 'signCount';
+            docs: [
+              'Last WebAuthn signature counter seen for this passkey (0 = authenticator has no counter)'
+            ];
+            type: 'u32';
+          },
+          {
+            name: 'backupEligible';
+            docs: [
+              'WebAuthn backup eligibility (BE) flag; fixed for the lifetime of a credential'
+            ];
+            type: 'bool';
+          },
+          {
+            name: 'backupState';
+            docs: ['WebAuthn backup state (BS) flag as of the last assertion'];
+            type: 'bool';
+          },
+          {
+            name: 'aaguid';
+            docs: [
+              'Authenticator model reported in the registration authenticatorData, if supplied'
+            ];
+            type: {
+              option: {
+                array: ['u8', 16];
+              };
+            };
+          },
+          {
+            name: 'backupEligibilityRecorded';
+            docs: [
+              'Whether `backup_eligible` has been recorded. Migrated legacy passkeys record it from their',
+              'first assertion.'
+            ];
+            type: 'bool';
           }
         ];
       };
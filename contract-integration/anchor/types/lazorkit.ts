@@ -17,9 +17,11 @@ export type Lazorkit = {
   ];
   instructions: [
     {
-      name: 'createSmartWallet';
-      docs: ['Create a new smart wallet with passkey authentication'];
-      discriminator: [129, 39, 235, 18, 132, 68, 203, 19];
+      name: 'cancelIntent';
+      docs: [
+        'Cancel a signed intent before a relayer executes it, authorized by a wallet device'
+      ];
+      discriminator: [67, 73, 238, 244, 208, 89, 225, 59];
       accounts: [
         {
           name: 'payer';
@@ -27,37 +29,18 @@ export type Lazorkit = {
           signer: true;
         },
         {
-          name: 'policyProgramRegistry';
-          docs: ['Policy program registry'];
+          name: 'config';
           pda: {
             seeds: [
               {
                 kind: 'const';
-                value: [
-                  112,
-                  111,
-                  108,
-                  105,
-                  99,
-                  121,
-                  95,
-                  114,
-                  101,
-                  103,
-                  105,
-                  115,
-                  116,
-                  114,
-                  121
-                ];
+                value: [99, 111, 110, 102, 105, 103];
               }
             ];
           };
         },
         {
           name: 'smartWallet';
-          docs: ['The smart wallet PDA being created with random ID'];
-          writable: true;
           pda: {
             seeds: [
               {
@@ -78,15 +61,16 @@ export type Lazorkit = {
                 ];
               },
               {
-                kind: 'arg';
-                path: 'args.wallet_id';
+                kind: 'account';
+                path: 'smart_wallet_data.id';
+                account: 'smartWallet';
               }
             ];
           };
+          relations: ['nonceLane'];
         },
         {
           name: 'smartWalletData';
-          docs: ['Smart wallet data'];
           writable: true;
           pda: {
             seeds: [
@@ -121,26 +105,31 @@ export type Lazorkit = {
         },
         {
           name: 'walletDevice';
-          docs: ['Wallet device for the passkey'];
+          writable: true;
+        },
+        {
+          name: 'intentReceipt';
+          docs: ['Replay registry entry of the intent being cancelled'];
           writable: true;
           pda: {
             seeds: [
               {
                 kind: 'const';
                 value: [
-                  119,
-                  97,
-                  108,
-                  108,
+                  105,
+                  110,
+                  116,
                   101,
+                  110,
                   116,
                   95,
-                  100,
+                  114,
                   101,
-                  118,
-                  105,
                   99,
-                  101
+                  101,
+                  105,
+                  112,
+                  116
                 ];
               },
               {
@@ -149,30 +138,31 @@ export type Lazorkit = {
               },
               {
                 kind: 'arg';
-                path: 'args.passkey_pubkey.to_hashed_bytes(smart_wallet';
-              }
-            ];
-          };
-        },
-        {
-          name: 'config';
-          docs: ['Program configuration'];
-          pda: {
-            seeds: [
-              {
-                kind: 'const';
-                value: [99, 111, 110, 102, 105, 103];
+                path: 'args.intent_hash';
               }
             ];
           };
         },
         {
-          name: 'defaultPolicyProgram';
-          docs: ['Default policy program for the smart wallet'];
+          name: 'ixSysvar';
+          address: 'Sysvar1nstructions1111111111111111111111111';
         },
         {
           name: 'systemProgram';
           address: '11111111111111111111111111111111';
+        },
+        {
+          name: 'nonceLane';
+          docs: [
+            "Nonce lane the challenge is sequenced on (None = the wallet's primary nonce)"
+          ];
+          writable: true;
+          optional: true;
+        },
+        {
+          name: 'slotHashes';
+          optional: true;
+          address: 'SysvarS1otHashes111111111111111111111111111';
         }
       ];
       args: [
@@ -180,15 +170,18 @@ export type Lazorkit = {
           name: 'args';
           type: {
             defined: {
-              name: 'createSmartWalletArgs';
+              name: 'cancelIntentArgs';
             };
           };
         }
       ];
     },
     {
-      name: 'createTransactionSession';
-      discriminator: [63, 173, 215, 71, 47, 219, 207, 197];
+      name: 'claimPasskeyIndex';
+      docs: [
+        'Claim the passkey index of a device registered before unique passkeys were enforced'
+      ];
+      discriminator: [132, 11, 88, 253, 26, 31, 91, 224];
       accounts: [
         {
           name: 'payer';
@@ -208,7 +201,6 @@ export type Lazorkit = {
         },
         {
           name: 'smartWallet';
-          writable: true;
           pda: {
             seeds: [
               {
@@ -235,10 +227,10 @@ export type Lazorkit = {
               }
             ];
           };
+          relations: ['walletDevice'];
         },
         {
           name: 'smartWalletData';
-          writable: true;
           pda: {
             seeds: [
               {
@@ -272,108 +264,173 @@ export type Lazorkit = {
         },
         {
           name: 'walletDevice';
+          docs: ['Registered passkey device claiming its index'];
+        },
+        {
+          name: 'passkeyIndex';
+          writable: true;
+        },
+        {
+          name: 'ixSysvar';
+          address: 'Sysvar1nstructions1111111111111111111111111';
+        },
+        {
+          name: 'systemProgram';
+          address: '11111111111111111111111111111111';
+        }
+      ];
+      args: [
+        {
+          name: 'passkeyProof';
+          type: {
+            defined: {
+              name: 'deviceAssertion';
+            };
+          };
+        }
+      ];
+    },
+    {
+      name: 'closeIntentReceipt';
+      docs: [
+        'Close an intent receipt after the intent expired, refunding its rent'
+      ];
+      discriminator: [253, 125, 14, 220, 130, 153, 94, 233];
+      accounts: [
+        {
+          name: 'intentReceipt';
+          writable: true;
+        },
+        {
+          name: 'rentRefund';
+          writable: true;
+        }
+      ];
+      args: [];
+    },
+    {
+      name: 'createDeviceRegistry';
+      docs: [
+        'Create the device registry of a wallet created before device registries existed; wallets',
+        'without a tracked device count set it from the device-approved list'
+      ];
+      discriminator: [131, 80, 204, 27, 54, 238, 200, 41];
+      accounts: [
+        {
+          name: 'payer';
+          writable: true;
+          signer: true;
+        },
+        {
+          name: 'config';
+          pda: {
+            seeds: [
+              {
+                kind: 'const';
+                value: [99, 111, 110, 102, 105, 103];
+              }
+            ];
+          };
+        },
+        {
+          name: 'smartWallet';
           pda: {
             seeds: [
               {
                 kind: 'const';
                 value: [
+                  115,
+                  109,
+                  97,
+                  114,
+                  116,
+                  95,
                   119,
                   97,
                   108,
                   108,
                   101,
-                  116,
-                  95,
-                  100,
-                  101,
-                  118,
-                  105,
-                  99,
-                  101
+                  116
                 ];
               },
               {
                 kind: 'account';
-                path: 'smartWallet';
-              },
-              {
-                kind: 'arg';
-                path: 'args.passkey_pubkey.to_hashed_bytes(smart_wallet';
+                path: 'smart_wallet_data.id';
+                account: 'smartWallet';
               }
             ];
           };
         },
         {
-          name: 'policyProgramRegistry';
+          name: 'smartWalletData';
+          writable: true;
           pda: {
             seeds: [
               {
                 kind: 'const';
                 value: [
-                  112,
-                  111,
-                  108,
-                  105,
-                  99,
-                  121,
-                  95,
+                  115,
+                  109,
+                  97,
                   114,
+                  116,
+                  95,
+                  119,
+                  97,
+                  108,
+                  108,
                   101,
-                  103,
-                  105,
-                  115,
                   116,
-                  114,
-                  121
+                  95,
+                  100,
+                  97,
+                  116,
+                  97
                 ];
+              },
+              {
+                kind: 'account';
+                path: 'smartWallet';
               }
             ];
           };
         },
         {
-          name: 'policyProgram';
+          name: 'walletDevice';
           docs: [
-            'Policy program for optional policy enforcement at session creation'
+            'Device approving the device list of a wallet without a tracked device count'
           ];
+          writable: true;
+          optional: true;
         },
         {
-          name: 'transactionSession';
-          docs: ['New transaction session account (rent payer: payer)'];
+          name: 'deviceRegistry';
           writable: true;
           pda: {
             seeds: [
               {
                 kind: 'const';
                 value: [
-                  116,
-                  114,
-                  97,
-                  110,
-                  115,
-                  97,
-                  99,
-                  116,
+                  100,
+                  101,
+                  118,
                   105,
-                  111,
-                  110,
+                  99,
+                  101,
                   95,
-                  115,
+                  114,
                   101,
-                  115,
-                  115,
+                  103,
                   105,
-                  111,
-                  110
+                  115,
+                  116,
+                  114,
+                  121
                 ];
               },
               {
                 kind: 'account';
                 path: 'smartWallet';
-              },
-              {
-                kind: 'account';
-                path: 'smart_wallet_data.last_nonce';
-                account: 'smartWallet';
               }
             ];
           };
@@ -382,6 +439,11 @@ export type Lazorkit = {
           name: 'ixSysvar';
           address: 'Sysvar1nstructions1111111111111111111111111';
         },
+        {
+          name: 'slotHashes';
+          optional: true;
+          address: 'SysvarS1otHashes111111111111111111111111111';
+        },
         {
           name: 'systemProgram';
           address: '11111111111111111111111111111111';
@@ -392,15 +454,18 @@ export type Lazorkit = {
           name: 'args';
           type: {
             defined: {
-              name: 'createSessionArgs';
+              name: 'createDeviceRegistryArgs';
             };
           };
         }
       ];
     },
     {
-      name: 'executeSessionTransaction';
-      discriminator: [38, 182, 163, 196, 170, 170, 115, 226];
+      name: 'createNonceLane';
+      docs: [
+        'Open an additional nonce lane so operations can be pending in parallel'
+      ];
+      discriminator: [224, 130, 239, 77, 203, 9, 83, 177];
       accounts: [
         {
           name: 'payer';
@@ -420,7 +485,6 @@ export type Lazorkit = {
         },
         {
           name: 'smartWallet';
-          writable: true;
           pda: {
             seeds: [
               {
@@ -450,7 +514,6 @@ export type Lazorkit = {
         },
         {
           name: 'smartWalletData';
-          writable: true;
           pda: {
             seeds: [
               {
@@ -483,38 +546,78 @@ export type Lazorkit = {
           };
         },
         {
-          name: 'cpiProgram';
-        },
-        {
-          name: 'transactionSession';
-          docs: [
-            'Transaction session to execute. Closed on success to refund rent.'
-          ];
-          writable: true;
-        },
-        {
-          name: 'sessionRefund';
+          name: 'nonceLane';
           writable: true;
-        }
-      ];
-      args: [
-        {
-          name: 'cpiData';
-          type: 'bytes';
+          pda: {
+            seeds: [
+              {
+                kind: 'const';
+                value: [110, 111, 110, 99, 101, 95, 108, 97, 110, 101];
+              },
+              {
+                kind: 'account';
+                path: 'smartWallet';
+              },
+              {
+                kind: 'arg';
+                path: 'lane';
+              }
+            ];
+          };
+        },
+        {
+          name: 'systemProgram';
+          address: '11111111111111111111111111111111';
+        }
+      ];
+      args: [
+        {
+          name: 'lane';
+          type: 'u64';
         }
       ];
     },
     {
-      name: 'executeTransaction';
-      discriminator: [231, 173, 49, 91, 235, 24, 68, 19];
+      name: 'createSmartWallet';
+      docs: ['Create a new smart wallet with passkey authentication'];
+      discriminator: [129, 39, 235, 18, 132, 68, 203, 19];
       accounts: [
         {
           name: 'payer';
           writable: true;
           signer: true;
         },
+        {
+          name: 'policyProgramRegistry';
+          docs: ['Policy program registry'];
+          pda: {
+            seeds: [
+              {
+                kind: 'const';
+                value: [
+                  112,
+                  111,
+                  108,
+                  105,
+                  99,
+                  121,
+                  95,
+                  114,
+                  101,
+                  103,
+                  105,
+                  115,
+                  116,
+                  114,
+                  121
+                ];
+              }
+            ];
+          };
+        },
         {
           name: 'smartWallet';
+          docs: ['The smart wallet PDA being created with random ID'];
           writable: true;
           pda: {
             seeds: [
@@ -536,15 +639,15 @@ export type Lazorkit = {
                 ];
               },
               {
-                kind: 'account';
-                path: 'smart_wallet_data.id';
-                account: 'smartWallet';
+                kind: 'arg';
+                path: 'args.wallet_id';
               }
             ];
           };
         },
         {
           name: 'smartWalletData';
+          docs: ['Smart wallet data'];
           writable: true;
           pda: {
             seeds: [
@@ -579,20 +682,54 @@ export type Lazorkit = {
         },
         {
           name: 'walletDevice';
+          docs: ['Wallet device for the passkey or keypair'];
+          writable: true;
+          pda: {
+            seeds: [
+              {
+                kind: 'const';
+                value: [
+                  119,
+                  97,
+                  108,
+                  108,
+                  101,
+                  116,
+                  95,
+                  100,
+                  101,
+                  118,
+                  105,
+                  99,
+                  101
+                ];
+              },
+              {
+                kind: 'account';
+                path: 'smartWallet';
+              },
+              {
+                kind: 'arg';
+                path: 'args.device_seed_hash(smart_wallet';
+              }
+            ];
+          };
         },
         {
-          name: 'policyProgramRegistry';
+          name: 'deviceRegistry';
+          docs: ["Index of the wallet's devices"];
+          writable: true;
           pda: {
             seeds: [
               {
                 kind: 'const';
                 value: [
-                  112,
-                  111,
-                  108,
+                  100,
+                  101,
+                  118,
                   105,
                   99,
-                  121,
+                  101,
                   95,
                   114,
                   101,
@@ -603,18 +740,27 @@ export type Lazorkit = {
                   114,
                   121
                 ];
+              },
+              {
+                kind: 'account';
+                path: 'smartWallet';
               }
             ];
           };
         },
         {
-          name: 'policyProgram';
+          name: 'credentialLookup';
+          writable: true;
+          optional: true;
         },
         {
-          name: 'cpiProgram';
+          name: 'passkeyIndex';
+          writable: true;
+          optional: true;
         },
         {
           name: 'config';
+          docs: ['Program configuration'];
           pda: {
             seeds: [
               {
@@ -624,9 +770,17 @@ export type Lazorkit = {
             ];
           };
         },
+        {
+          name: 'defaultPolicyProgram';
+          docs: ['Default policy program for the smart wallet'];
+        },
         {
           name: 'ixSysvar';
           address: 'Sysvar1nstructions1111111111111111111111111';
+        },
+        {
+          name: 'systemProgram';
+          address: '11111111111111111111111111111111';
         }
       ];
       args: [
@@ -634,86 +788,15 @@ export type Lazorkit = {
           name: 'args';
           type: {
             defined: {
-              name: 'executeTransactionArgs';
+              name: 'createSmartWalletArgs';
             };
           };
         }
       ];
     },
     {
-      name: 'initialize';
-      docs: ['Initialize the program by creating the sequence tracker'];
-      discriminator: [175, 175, 109, 31, 13, 152, 155, 237];
-      accounts: [
-        {
-          name: 'signer';
-          docs: [
-            'The signer of the transaction, who will be the initial authority.'
-          ];
-          writable: true;
-          signer: true;
-        },
-        {
-          name: 'config';
-          docs: ["The program's configuration account."];
-          writable: true;
-          pda: {
-            seeds: [
-              {
-                kind: 'const';
-                value: [99, 111, 110, 102, 105, 103];
-              }
-            ];
-          };
-        },
-        {
-          name: 'policyProgramRegistry';
-          docs: [
-            'The registry of policy programs that can be used with smart wallets.'
-          ];
-          writable: true;
-          pda: {
-            seeds: [
-              {
-                kind: 'const';
-                value: [
-                  112,
-                  111,
-                  108,
-                  105,
-                  99,
-                  121,
-                  95,
-                  114,
-                  101,
-                  103,
-                  105,
-                  115,
-                  116,
-                  114,
-                  121
-                ];
-              }
-            ];
-          };
-        },
-        {
-          name: 'defaultPolicyProgram';
-          docs: [
-            'The default policy program to be used for new smart wallets.'
-          ];
-        },
-        {
-          name: 'systemProgram';
-          docs: ['The system program.'];
-          address: '11111111111111111111111111111111';
-        }
-      ];
-      args: [];
-    },
-    {
-      name: 'invokePolicy';
-      discriminator: [233, 117, 13, 198, 43, 169, 77, 87];
+      name: 'createTransactionSession';
+      discriminator: [63, 173, 215, 71, 47, 219, 207, 197];
       accounts: [
         {
           name: 'payer';
@@ -797,9 +880,38 @@ export type Lazorkit = {
         },
         {
           name: 'walletDevice';
-        },
-        {
-          name: 'policyProgram';
+          writable: true;
+          pda: {
+            seeds: [
+              {
+                kind: 'const';
+                value: [
+                  119,
+                  97,
+                  108,
+                  108,
+                  101,
+                  116,
+                  95,
+                  100,
+                  101,
+                  118,
+                  105,
+                  99,
+                  101
+                ];
+              },
+              {
+                kind: 'account';
+                path: 'smartWallet';
+              },
+              {
+                kind: 'account';
+                path: 'wallet_device.kind.seed_hash(& args.passkey_pubkey,\nsmart_wallet';
+                account: 'walletDevice';
+              }
+            ];
+          };
         },
         {
           name: 'policyProgramRegistry';
@@ -829,35 +941,86 @@ export type Lazorkit = {
           };
         },
         {
-          name: 'ixSysvar';
-          address: 'Sysvar1nstructions1111111111111111111111111';
+          name: 'policyProgram';
+          docs: [
+            'Policy program for optional policy enforcement at session creation'
+          ];
         },
         {
-          name: 'systemProgram';
-          address: '11111111111111111111111111111111';
-        }
-      ];
-      args: [
-        {
-          name: 'args';
-          type: {
-            defined: {
-              name: 'invokePolicyArgs';
-            };
-          };
-        }
-      ];
-    },
-    {
-      name: 'registerPolicyProgram';
-      docs: ['Add a program to the policy program registry'];
-      discriminator: [15, 54, 85, 112, 89, 180, 121, 13];
+          name: 'transactionSession';
+          docs: ['New transaction session account (rent payer: payer)'];
+          writable: true;
+          pda: {
+            seeds: [
+              {
+                kind: 'const';
+                value: [
+                  116,
+                  114,
+                  97,
+                  110,
+                  115,
+                  97,
+                  99,
+                  116,
+                  105,
+                  111,
+                  110,
+                  95,
+                  115,
+                  101,
+                  115,
+                  115,
+                  105,
+                  111,
+                  110
+                ];
+              },
+              {
+                kind: 'account';
+                path: 'smartWallet';
+              },
+              {
+                kind: 'account';
+                path: 'smart_wallet_data.last_nonce';
+                account: 'smartWallet';
+              }
+            ];
+          };
+        },
+        {
+          name: 'ixSysvar';
+          address: 'Sysvar1nstructions1111111111111111111111111';
+        },
+        {
+          name: 'systemProgram';
+          address: '11111111111111111111111111111111';
+        },
+        {
+          name: 'slotHashes';
+          optional: true;
+          address: 'SysvarS1otHashes111111111111111111111111111';
+        }
+      ];
+      args: [
+        {
+          name: 'args';
+          type: {
+            defined: {
+              name: 'createSessionArgs';
+            };
+          };
+        }
+      ];
+    },
+    {
+      name: 'executeSessionTransaction';
+      discriminator: [38, 182, 163, 196, 170, 170, 115, 226];
       accounts: [
         {
-          name: 'authority';
+          name: 'payer';
           writable: true;
           signer: true;
-          relations: ['config'];
         },
         {
           name: 'config';
@@ -871,97 +1034,100 @@ export type Lazorkit = {
           };
         },
         {
-          name: 'policyProgramRegistry';
+          name: 'smartWallet';
           writable: true;
           pda: {
             seeds: [
               {
                 kind: 'const';
                 value: [
-                  112,
-                  111,
-                  108,
-                  105,
-                  99,
-                  121,
-                  95,
-                  114,
-                  101,
-                  103,
-                  105,
                   115,
-                  116,
+                  109,
+                  97,
                   114,
-                  121
+                  116,
+                  95,
+                  119,
+                  97,
+                  108,
+                  108,
+                  101,
+                  116
                 ];
+              },
+              {
+                kind: 'account';
+                path: 'smart_wallet_data.id';
+                account: 'smartWallet';
               }
             ];
           };
-        }
-      ];
-      args: [];
-    },
-    {
-      name: 'updateConfig';
-      docs: ['Update the program configuration'];
-      discriminator: [29, 158, 252, 191, 10, 83, 219, 99];
-      accounts: [
-        {
-          name: 'authority';
-          docs: ['The current authority of the program.'];
-          writable: true;
-          signer: true;
-          relations: ['config'];
         },
         {
-          name: 'config';
-          docs: ["The program's configuration account."];
+          name: 'smartWalletData';
           writable: true;
           pda: {
             seeds: [
               {
                 kind: 'const';
-                value: [99, 111, 110, 102, 105, 103];
+                value: [
+                  115,
+                  109,
+                  97,
+                  114,
+                  116,
+                  95,
+                  119,
+                  97,
+                  108,
+                  108,
+                  101,
+                  116,
+                  95,
+                  100,
+                  97,
+                  116,
+                  97
+                ];
+              },
+              {
+                kind: 'account';
+                path: 'smartWallet';
               }
             ];
           };
+        },
+        {
+          name: 'cpiProgram';
+        },
+        {
+          name: 'transactionSession';
+          docs: [
+            'Transaction session to execute. Closed on success to refund rent.'
+          ];
+          writable: true;
+        },
+        {
+          name: 'sessionRefund';
+          writable: true;
         }
       ];
       args: [
         {
-          name: 'param';
-          type: {
-            defined: {
-              name: 'updateConfigType';
-            };
-          };
-        },
-        {
-          name: 'value';
-          type: 'u64';
+          name: 'cpiData';
+          type: 'bytes';
         }
       ];
     },
     {
-      name: 'updatePolicy';
-      discriminator: [212, 245, 246, 7, 163, 151, 18, 57];
+      name: 'executeTransaction';
+      discriminator: [231, 173, 49, 91, 235, 24, 68, 19];
       accounts: [
         {
           name: 'payer';
           writable: true;
           signer: true;
         },
-        {
-          name: 'config';
-          pda: {
-            seeds: [
-              {
-                kind: 'const';
-                value: [99, 111, 110, 102, 105, 103];
-              }
-            ];
-          };
-        },
         {
           name: 'smartWallet';
           writable: true;
@@ -991,6 +1157,7 @@ export type Lazorkit = {
               }
             ];
           };
+          relations: ['nonceLane'];
         },
         {
           name: 'smartWalletData';
@@ -1028,12 +1195,7 @@ export type Lazorkit = {
         },
         {
           name: 'walletDevice';
-        },
-        {
-          name: 'oldPolicyProgram';
-        },
-        {
-          name: 'newPolicyProgram';
+          writable: true;
         },
         {
           name: 'policyProgramRegistry';
@@ -1062,14 +1224,82 @@ export type Lazorkit = {
             ];
           };
         },
+        {
+          name: 'policyProgram';
+        },
+        {
+          name: 'cpiProgram';
+        },
+        {
+          name: 'config';
+          pda: {
+            seeds: [
+              {
+                kind: 'const';
+                value: [99, 111, 110, 102, 105, 103];
+              }
+            ];
+          };
+        },
         {
           name: 'ixSysvar';
-          docs: ['CHECK'];
           address: 'Sysvar1nstructions1111111111111111111111111';
         },
         {
           name: 'systemProgram';
           address: '11111111111111111111111111111111';
+        },
+        {
+          name: 'intentReceipt';
+          docs: [
+            'Replay registry entry of the signed intent being executed (required with `intent_hash`)'
+          ];
+          writable: true;
+          optional: true;
+          pda: {
+            seeds: [
+              {
+                kind: 'const';
+                value: [
+                  105,
+                  110,
+                  116,
+                  101,
+                  110,
+                  116,
+                  95,
+                  114,
+                  101,
+                  99,
+                  101,
+                  105,
+                  112,
+                  116
+                ];
+              },
+              {
+                kind: 'account';
+                path: 'smartWallet';
+              },
+              {
+                kind: 'arg';
+                path: 'args.intent_hash';
+              }
+            ];
+          };
+        },
+        {
+          name: 'nonceLane';
+          docs: [
+            "Nonce lane the challenge is sequenced on (None = the wallet's primary nonce)"
+          ];
+          writable: true;
+          optional: true;
+        },
+        {
+          name: 'slotHashes';
+          optional: true;
+          address: 'SysvarS1otHashes111111111111111111111111111';
         }
       ];
       args: [
@@ -1077,18 +1307,1173 @@ export type Lazorkit = {
           name: 'args';
           type: {
             defined: {
-              name: 'updatePolicyArgs';
+              name: 'executeTransactionArgs';
             };
           };
         }
       ];
-    }
-  ];
-  accounts: [
+    },
+    {
+      name: 'initialize';
+      docs: ['Initialize the program by creating the sequence tracker'];
+      discriminator: [175, 175, 109, 31, 13, 152, 155, 237];
+      accounts: [
+        {
+          name: 'signer';
+          docs: [
+            'The signer of the transaction, who will be the initial authority.'
+          ];
+          writable: true;
+          signer: true;
+        },
+        {
+          name: 'config';
+          docs: ["The program's configuration account."];
+          writable: true;
+          pda: {
+            seeds: [
+              {
+                kind: 'const';
+                value: [99, 111, 110, 102, 105, 103];
+              }
+            ];
+          };
+        },
+        {
+          name: 'policyProgramRegistry';
+          docs: [
+            'The registry of policy programs that can be used with smart wallets.'
+          ];
+          writable: true;
+          pda: {
+            seeds: [
+              {
+                kind: 'const';
+                value: [
+                  112,
+                  111,
+                  108,
+                  105,
+                  99,
+                  121,
+                  95,
+                  114,
+                  101,
+                  103,
+                  105,
+                  115,
+                  116,
+                  114,
+                  121
+                ];
+              }
+            ];
+          };
+        },
+        {
+          name: 'defaultPolicyProgram';
+          docs: [
+            'The default policy program to be used for new smart wallets.'
+          ];
+        },
+        {
+          name: 'systemProgram';
+          docs: ['The system program.'];
+          address: '11111111111111111111111111111111';
+        }
+      ];
+      args: [
+        {
+          name: 'deploymentTag';
+          type: 'u64';
+        }
+      ];
+    },
+    {
+      name: 'invokePolicy';
+      discriminator: [233, 117, 13, 198, 43, 169, 77, 87];
+      accounts: [
+        {
+          name: 'payer';
+          writable: true;
+          signer: true;
+        },
+        {
+          name: 'config';
+          pda: {
+            seeds: [
+              {
+                kind: 'const';
+                value: [99, 111, 110, 102, 105, 103];
+              }
+            ];
+          };
+        },
+        {
+          name: 'smartWallet';
+          writable: true;
+          pda: {
+            seeds: [
+              {
+                kind: 'const';
+                value: [
+                  115,
+                  109,
+                  97,
+                  114,
+                  116,
+                  95,
+                  119,
+                  97,
+                  108,
+                  108,
+                  101,
+                  116
+                ];
+              },
+              {
+                kind: 'account';
+                path: 'smart_wallet_data.id';
+                account: 'smartWallet';
+              }
+            ];
+          };
+          relations: ['nonceLane'];
+        },
+        {
+          name: 'smartWalletData';
+          writable: true;
+          pda: {
+            seeds: [
+              {
+                kind: 'const';
+                value: [
+                  115,
+                  109,
+                  97,
+                  114,
+                  116,
+                  95,
+                  119,
+                  97,
+                  108,
+                  108,
+                  101,
+                  116,
+                  95,
+                  100,
+                  97,
+                  116,
+                  97
+                ];
+              },
+              {
+                kind: 'account';
+                path: 'smartWallet';
+              }
+            ];
+          };
+        },
+        {
+          name: 'walletDevice';
+          writable: true;
+        },
+        {
+          name: 'deviceRegistry';
+          writable: true;
+          pda: {
+            seeds: [
+              {
+                kind: 'const';
+                value: [
+                  100,
+                  101,
+                  118,
+                  105,
+                  99,
+                  101,
+                  95,
+                  114,
+                  101,
+                  103,
+                  105,
+                  115,
+                  116,
+                  114,
+                  121
+                ];
+              },
+              {
+                kind: 'account';
+                path: 'smartWallet';
+              }
+            ];
+          };
+        },
+        {
+          name: 'credentialLookup';
+          writable: true;
+          optional: true;
+        },
+        {
+          name: 'passkeyIndex';
+          writable: true;
+          optional: true;
+        },
+        {
+          name: 'policyProgram';
+        },
+        {
+          name: 'policyProgramRegistry';
+          pda: {
+            seeds: [
+              {
+                kind: 'const';
+                value: [
+                  112,
+                  111,
+                  108,
+                  105,
+                  99,
+                  121,
+                  95,
+                  114,
+                  101,
+                  103,
+                  105,
+                  115,
+                  116,
+                  114,
+                  121
+                ];
+              }
+            ];
+          };
+        },
+        {
+          name: 'ixSysvar';
+          address: 'Sysvar1nstructions1111111111111111111111111';
+        },
+        {
+          name: 'systemProgram';
+          address: '11111111111111111111111111111111';
+        },
+        {
+          name: 'nonceLane';
+          docs: [
+            "Nonce lane the challenge is sequenced on (None = the wallet's primary nonce)"
+          ];
+          writable: true;
+          optional: true;
+        },
+        {
+          name: 'slotHashes';
+          optional: true;
+          address: 'SysvarS1otHashes111111111111111111111111111';
+        }
+      ];
+      args: [
+        {
+          name: 'args';
+          type: {
+            defined: {
+              name: 'invokePolicyArgs';
+            };
+          };
+        }
+      ];
+    },
+    {
+      name: 'migrateAccount';
+      docs: [
+        'Grow an account created before fields were appended to its layout'
+      ];
+      discriminator: [177, 228, 60, 125, 13, 116, 44, 84];
+      accounts: [
+        {
+          name: 'payer';
+          writable: true;
+          signer: true;
+        },
+        {
+          name: 'account';
+          writable: true;
+        },
+        {
+          name: 'systemProgram';
+          address: '11111111111111111111111111111111';
+        }
+      ];
+      args: [];
+    },
+    {
+      name: 'registerPolicyProgram';
+      docs: ['Add a program to the policy program registry'];
+      discriminator: [15, 54, 85, 112, 89, 180, 121, 13];
+      accounts: [
+        {
+          name: 'authority';
+          writable: true;
+          signer: true;
+          relations: ['config'];
+        },
+        {
+          name: 'config';
+          pda: {
+            seeds: [
+              {
+                kind: 'const';
+                value: [99, 111, 110, 102, 105, 103];
+              }
+            ];
+          };
+        },
+        {
+          name: 'policyProgramRegistry';
+          writable: true;
+          pda: {
+            seeds: [
+              {
+                kind: 'const';
+                value: [
+                  112,
+                  111,
+                  108,
+                  105,
+                  99,
+                  121,
+                  95,
+                  114,
+                  101,
+                  103,
+                  105,
+                  115,
+                  116,
+                  114,
+                  121
+                ];
+              }
+            ];
+          };
+        }
+      ];
+      args: [];
+    },
+    {
+      name: 'removeWalletDevice';
+      docs: [
+        'Remove a wallet device, authorized by another device of the same wallet. The policy',
+        'program is notified through its `remove_device` instruction.'
+      ];
+      discriminator: [206, 29, 62, 51, 201, 79, 8, 10];
+      accounts: [
+        {
+          name: 'payer';
+          writable: true;
+          signer: true;
+        },
+        {
+          name: 'config';
+          pda: {
+            seeds: [
+              {
+                kind: 'const';
+                value: [99, 111, 110, 102, 105, 103];
+              }
+            ];
+          };
+        },
+        {
+          name: 'smartWallet';
+          pda: {
+            seeds: [
+              {
+                kind: 'const';
+                value: [
+                  115,
+                  109,
+                  97,
+                  114,
+                  116,
+                  95,
+                  119,
+                  97,
+                  108,
+                  108,
+                  101,
+                  116
+                ];
+              },
+              {
+                kind: 'account';
+                path: 'smart_wallet_data.id';
+                account: 'smartWallet';
+              }
+            ];
+          };
+          relations: ['removedWalletDevice', 'nonceLane'];
+        },
+        {
+          name: 'smartWalletData';
+          writable: true;
+          pda: {
+            seeds: [
+              {
+                kind: 'const';
+                value: [
+                  115,
+                  109,
+                  97,
+                  114,
+                  116,
+                  95,
+                  119,
+                  97,
+                  108,
+                  108,
+                  101,
+                  116,
+                  95,
+                  100,
+                  97,
+                  116,
+                  97
+                ];
+              },
+              {
+                kind: 'account';
+                path: 'smartWallet';
+              }
+            ];
+          };
+        },
+        {
+          name: 'walletDevice';
+          docs: ['Device authorizing the removal'];
+          writable: true;
+        },
+        {
+          name: 'removedWalletDevice';
+          docs: ['Device being removed; its rent goes to `rent_refund`'];
+          writable: true;
+        },
+        {
+          name: 'deviceRegistry';
+          writable: true;
+          pda: {
+            seeds: [
+              {
+                kind: 'const';
+                value: [
+                  100,
+                  101,
+                  118,
+                  105,
+                  99,
+                  101,
+                  95,
+                  114,
+                  101,
+                  103,
+                  105,
+                  115,
+                  116,
+                  114,
+                  121
+                ];
+              },
+              {
+                kind: 'account';
+                path: 'smartWallet';
+              }
+            ];
+          };
+        },
+        {
+          name: 'credentialLookup';
+          writable: true;
+        },
+        {
+          name: 'passkeyIndex';
+          writable: true;
+        },
+        {
+          name: 'rentRefund';
+          writable: true;
+        },
+        {
+          name: 'policyProgram';
+        },
+        {
+          name: 'policyProgramRegistry';
+          pda: {
+            seeds: [
+              {
+                kind: 'const';
+                value: [
+                  112,
+                  111,
+                  108,
+                  105,
+                  99,
+                  121,
+                  95,
+                  114,
+                  101,
+                  103,
+                  105,
+                  115,
+                  116,
+                  114,
+                  121
+                ];
+              }
+            ];
+          };
+        },
+        {
+          name: 'ixSysvar';
+          address: 'Sysvar1nstructions1111111111111111111111111';
+        },
+        {
+          name: 'nonceLane';
+          docs: [
+            "Nonce lane the challenge is sequenced on (None = the wallet's primary nonce)"
+          ];
+          writable: true;
+          optional: true;
+        },
+        {
+          name: 'slotHashes';
+          optional: true;
+          address: 'SysvarS1otHashes111111111111111111111111111';
+        }
+      ];
+      args: [
+        {
+          name: 'args';
+          type: {
+            defined: {
+              name: 'removeWalletDeviceArgs';
+            };
+          };
+        }
+      ];
+    },
+    {
+      name: 'updateAllowedAaguids';
+      docs: [
+        'Replace the default list of authenticator AAGUIDs that may be enrolled'
+      ];
+      discriminator: [27, 13, 245, 210, 142, 221, 119, 47];
+      accounts: [
+        {
+          name: 'authority';
+          docs: ['The current authority of the program.'];
+          writable: true;
+          signer: true;
+          relations: ['config'];
+        },
+        {
+          name: 'config';
+          docs: ["The program's configuration account."];
+          writable: true;
+          pda: {
+            seeds: [
+              {
+                kind: 'const';
+                value: [99, 111, 110, 102, 105, 103];
+              }
+            ];
+          };
+        }
+      ];
+      args: [
+        {
+          name: 'aaguids';
+          type: {
+            vec: {
+              array: ['u8', 16];
+            };
+          };
+        }
+      ];
+    },
+    {
+      name: 'updateAllowedOrigins';
+      docs: ['Replace the default list of allowed WebAuthn origin hashes'];
+      discriminator: [90, 84, 74, 188, 173, 71, 119, 24];
+      accounts: [
+        {
+          name: 'authority';
+          docs: ['The current authority of the program.'];
+          writable: true;
+          signer: true;
+          relations: ['config'];
+        },
+        {
+          name: 'config';
+          docs: ["The program's configuration account."];
+          writable: true;
+          pda: {
+            seeds: [
+              {
+                kind: 'const';
+                value: [99, 111, 110, 102, 105, 103];
+              }
+            ];
+          };
+        }
+      ];
+      args: [
+        {
+          name: 'originHashes';
+          type: {
+            vec: {
+              array: ['u8', 32];
+            };
+          };
+        }
+      ];
+    },
+    {
+      name: 'updateConfig';
+      docs: ['Update the program configuration'];
+      discriminator: [29, 158, 252, 191, 10, 83, 219, 99];
+      accounts: [
+        {
+          name: 'authority';
+          docs: ['The current authority of the program.'];
+          writable: true;
+          signer: true;
+          relations: ['config'];
+        },
+        {
+          name: 'config';
+          docs: ["The program's configuration account."];
+          writable: true;
+          pda: {
+            seeds: [
+              {
+                kind: 'const';
+                value: [99, 111, 110, 102, 105, 103];
+              }
+            ];
+          };
+        }
+      ];
+      args: [
+        {
+          name: 'param';
+          type: {
+            defined: {
+              name: 'updateConfigType';
+            };
+          };
+        },
+        {
+          name: 'value';
+          type: 'u64';
+        }
+      ];
+    },
+    {
+      name: 'updatePolicy';
+      discriminator: [212, 245, 246, 7, 163, 151, 18, 57];
+      accounts: [
+        {
+          name: 'payer';
+          writable: true;
+          signer: true;
+        },
+        {
+          name: 'config';
+          pda: {
+            seeds: [
+              {
+                kind: 'const';
+                value: [99, 111, 110, 102, 105, 103];
+              }
+            ];
+          };
+        },
+        {
+          name: 'smartWallet';
+          writable: true;
+          pda: {
+            seeds: [
+              {
+                kind: 'const';
+                value: [
+                  115,
+                  109,
+                  97,
+                  114,
+                  116,
+                  95,
+                  119,
+                  97,
+                  108,
+                  108,
+                  101,
+                  116
+                ];
+              },
+              {
+                kind: 'account';
+                path: 'smart_wallet_data.id';
+                account: 'smartWallet';
+              }
+            ];
+          };
+          relations: ['nonceLane'];
+        },
+        {
+          name: 'smartWalletData';
+          writable: true;
+          pda: {
+            seeds: [
+              {
+                kind: 'const';
+                value: [
+                  115,
+                  109,
+                  97,
+                  114,
+                  116,
+                  95,
+                  119,
+                  97,
+                  108,
+                  108,
+                  101,
+                  116,
+                  95,
+                  100,
+                  97,
+                  116,
+                  97
+                ];
+              },
+              {
+                kind: 'account';
+                path: 'smartWallet';
+              }
+            ];
+          };
+        },
+        {
+          name: 'walletDevice';
+          writable: true;
+        },
+        {
+          name: 'deviceRegistry';
+          writable: true;
+          pda: {
+            seeds: [
+              {
+                kind: 'const';
+                value: [
+                  100,
+                  101,
+                  118,
+                  105,
+                  99,
+                  101,
+                  95,
+                  114,
+                  101,
+                  103,
+                  105,
+                  115,
+                  116,
+                  114,
+                  121
+                ];
+              },
+              {
+                kind: 'account';
+                path: 'smartWallet';
+              }
+            ];
+          };
+        },
+        {
+          name: 'credentialLookup';
+          writable: true;
+          optional: true;
+        },
+        {
+          name: 'passkeyIndex';
+          writable: true;
+          optional: true;
+        },
+        {
+          name: 'oldPolicyProgram';
+        },
+        {
+          name: 'newPolicyProgram';
+        },
+        {
+          name: 'policyProgramRegistry';
+          pda: {
+            seeds: [
+              {
+                kind: 'const';
+                value: [
+                  112,
+                  111,
+                  108,
+                  105,
+                  99,
+                  121,
+                  95,
+                  114,
+                  101,
+                  103,
+                  105,
+                  115,
+                  116,
+                  114,
+                  121
+                ];
+              }
+            ];
+          };
+        },
+        {
+          name: 'ixSysvar';
+          docs: ['CHECK'];
+          address: 'Sysvar1nstructions1111111111111111111111111';
+        },
+        {
+          name: 'systemProgram';
+          address: '11111111111111111111111111111111';
+        },
+        {
+          name: 'nonceLane';
+          docs: [
+            "Nonce lane the challenge is sequenced on (None = the wallet's primary nonce)"
+          ];
+          writable: true;
+          optional: true;
+        },
+        {
+          name: 'slotHashes';
+          optional: true;
+          address: 'SysvarS1otHashes111111111111111111111111111';
+        }
+      ];
+      args: [
+        {
+          name: 'args';
+          type: {
+            defined: {
+              name: 'updatePolicyArgs';
+            };
+          };
+        }
+      ];
+    },
+    {
+      name: 'updateRpIdHash';
+      docs: [
+        'Set the default relying party ID hash expected in authenticatorData'
+      ];
+      discriminator: [249, 160, 106, 39, 135, 68, 112, 46];
+      accounts: [
+        {
+          name: 'authority';
+          docs: ['The current authority of the program.'];
+          writable: true;
+          signer: true;
+          relations: ['config'];
+        },
+        {
+          name: 'config';
+          docs: ["The program's configuration account."];
+          writable: true;
+          pda: {
+            seeds: [
+              {
+                kind: 'const';
+                value: [99, 111, 110, 102, 105, 103];
+              }
+            ];
+          };
+        }
+      ];
+      args: [
+        {
+          name: 'rpIdHash';
+          type: {
+            option: {
+              array: ['u8', 32];
+            };
+          };
+        }
+      ];
+    },
+    {
+      name: 'updateWalletSettings';
+      docs: [
+        'Replace the per-wallet security settings, authorized by a wallet device'
+      ];
+      discriminator: [113, 43, 132, 136, 255, 14, 160, 146];
+      accounts: [
+        {
+          name: 'payer';
+          writable: true;
+          signer: true;
+        },
+        {
+          name: 'config';
+          pda: {
+            seeds: [
+              {
+                kind: 'const';
+                value: [99, 111, 110, 102, 105, 103];
+              }
+            ];
+          };
+        },
+        {
+          name: 'smartWallet';
+          pda: {
+            seeds: [
+              {
+                kind: 'const';
+                value: [
+                  115,
+                  109,
+                  97,
+                  114,
+                  116,
+                  95,
+                  119,
+                  97,
+                  108,
+                  108,
+                  101,
+                  116
+                ];
+              },
+              {
+                kind: 'account';
+                path: 'smart_wallet_data.id';
+                account: 'smartWallet';
+              }
+            ];
+          };
+          relations: ['nonceLane'];
+        },
+        {
+          name: 'smartWalletData';
+          writable: true;
+          pda: {
+            seeds: [
+              {
+                kind: 'const';
+                value: [
+                  115,
+                  109,
+                  97,
+                  114,
+                  116,
+                  95,
+                  119,
+                  97,
+                  108,
+                  108,
+                  101,
+                  116,
+                  95,
+                  100,
+                  97,
+                  116,
+                  97
+                ];
+              },
+              {
+                kind: 'account';
+                path: 'smartWallet';
+              }
+            ];
+          };
+        },
+        {
+          name: 'walletDevice';
+          writable: true;
+        },
+        {
+          name: 'ixSysvar';
+          address: 'Sysvar1nstructions1111111111111111111111111';
+        },
+        {
+          name: 'nonceLane';
+          docs: [
+            "Nonce lane the challenge is sequenced on (None = the wallet's primary nonce)"
+          ];
+          writable: true;
+          optional: true;
+        },
+        {
+          name: 'slotHashes';
+          optional: true;
+          address: 'SysvarS1otHashes111111111111111111111111111';
+        }
+      ];
+      args: [
+        {
+          name: 'args';
+          type: {
+            defined: {
+              name: 'updateWalletSettingsArgs';
+            };
+          };
+        }
+      ];
+    },
+    {
+      name: 'verifySignature';
+      docs: [
+        "Check that a wallet's devices, up to its threshold, approved an app-supplied message hash,",
+        'without consuming a nonce. The result is returned as a bool in the return data.'
+      ];
+      discriminator: [91, 139, 24, 69, 251, 162, 245, 112];
+      accounts: [
+        {
+          name: 'config';
+          pda: {
+            seeds: [
+              {
+                kind: 'const';
+                value: [99, 111, 110, 102, 105, 103];
+              }
+            ];
+          };
+        },
+        {
+          name: 'smartWallet';
+          pda: {
+            seeds: [
+              {
+                kind: 'const';
+                value: [
+                  115,
+                  109,
+                  97,
+                  114,
+                  116,
+                  95,
+                  119,
+                  97,
+                  108,
+                  108,
+                  101,
+                  116
+                ];
+              },
+              {
+                kind: 'account';
+                path: 'smart_wallet_data.id';
+                account: 'smartWallet';
+              }
+            ];
+          };
+        },
+        {
+          name: 'smartWalletData';
+          pda: {
+            seeds: [
+              {
+                kind: 'const';
+                value: [
+                  115,
+                  109,
+                  97,
+                  114,
+                  116,
+                  95,
+                  119,
+                  97,
+                  108,
+                  108,
+                  101,
+                  116,
+                  95,
+                  100,
+                  97,
+                  116,
+                  97
+                ];
+              },
+              {
+                kind: 'account';
+                path: 'smartWallet';
+              }
+            ];
+          };
+        },
+        {
+          name: 'walletDevice';
+        },
+        {
+          name: 'ixSysvar';
+          address: 'Sysvar1nstructions1111111111111111111111111';
+        },
+        {
+          name: 'slotHashes';
+          optional: true;
+          address: 'SysvarS1otHashes111111111111111111111111111';
+        },
+        {
+          name: 'payer';
+          docs: [
+            'Signer a payer-bound message is bound to; without it only unbound messages verify'
+          ];
+          signer: true;
+          optional: true;
+        }
+      ];
+      args: [
+        {
+          name: 'args';
+          type: {
+            defined: {
+              name: 'verifySignatureArgs';
+            };
+          };
+        }
+      ];
+      returns: 'bool';
+    }
+  ];
+  accounts: [
     {
       name: 'config';
       discriminator: [155, 12, 170, 224, 30, 250, 204, 130];
     },
+    {
+      name: 'credentialLookup';
+      discriminator: [83, 54, 58, 242, 36, 92, 56, 97];
+    },
+    {
+      name: 'deviceRegistry';
+      discriminator: [103, 245, 70, 187, 154, 60, 208, 216];
+    },
+    {
+      name: 'intentReceipt';
+      discriminator: [84, 252, 93, 100, 126, 80, 15, 134];
+    },
+    {
+      name: 'nonceLane';
+      discriminator: [216, 182, 231, 194, 99, 245, 227, 27];
+    },
+    {
+      name: 'passkeyIndex';
+      discriminator: [145, 98, 21, 42, 204, 12, 11, 201];
+    },
     {
       name: 'policyProgramRegistry';
       discriminator: [158, 67, 114, 157, 27, 153, 86, 72];
@@ -1621,6 +3006,346 @@ export type Lazorkit = {
       code: 6092;
       name: 'invalidRefundAmount';
       msg: 'Invalid refund amount';
+    },
+    {
+      code: 6093;
+      name: 'clientDataTypeMismatch';
+      msg: 'Client data type is not webauthn.get';
+    },
+    {
+      code: 6094;
+      name: 'clientDataOriginMissing';
+      msg: 'Origin field missing from client data JSON';
+    },
+    {
+      code: 6095;
+      name: 'clientDataOriginNotAllowed';
+      msg: 'Client data origin is not in the allowed origin list';
+    },
+    {
+      code: 6096;
+      name: 'tooManyAllowedOrigins';
+      msg: 'Too many allowed origins provided';
+    },
+    {
+      code: 6097;
+      name: 'authenticatorDataTooShort';
+      msg: 'Authenticator data is shorter than the 37-byte header';
+    },
+    {
+      code: 6098;
+      name: 'rpIdHashMismatch';
+      msg: 'Authenticator data rpIdHash does not match the expected relying party';
+    },
+    {
+      code: 6099;
+      name: 'userNotPresent';
+      msg: 'Authenticator data is missing the user presence flag';
+    },
+    {
+      code: 6100;
+      name: 'userNotVerified';
+      msg: 'Authenticator data is missing the user verification flag';
+    },
+    {
+      code: 6101;
+      name: 'signCountRegression';
+      msg: 'Authenticator signature counter did not increase (possible cloned authenticator)';
+    },
+    {
+      code: 6102;
+      name: 'signatureNotLowS';
+      msg: 'Secp256r1 signature is not low-S; normalize s to n - s before submitting';
+    },
+    {
+      code: 6103;
+      name: 'clientDataDuplicateKey';
+      msg: 'Client data JSON contains a duplicate key';
+    },
+    {
+      code: 6104;
+      name: 'clientDataInvalidEscape';
+      msg: 'Client data JSON contains a malformed escape sequence';
+    },
+    {
+      code: 6105;
+      name: 'messageOperationMismatch';
+      msg: 'Challenge was signed for a different operation';
+    },
+    {
+      code: 6106;
+      name: 'unsupportedMessageVersion';
+      msg: 'Challenge message version is not supported';
+    },
+    {
+      code: 6107;
+      name: 'domainSeparatorMismatch';
+      msg: 'Challenge domain separator does not match this program, deployment and wallet';
+    },
+    {
+      code: 6108;
+      name: 'ed25519InvalidInstruction';
+      msg: 'Ed25519 instruction has invalid data';
+    },
+    {
+      code: 6109;
+      name: 'ed25519DataMismatch';
+      msg: 'Ed25519 signature data validation failed';
+    },
+    {
+      code: 6110;
+      name: 'ed25519InstructionNotFound';
+      msg: 'Ed25519 instruction not found at specified index';
+    },
+    {
+      code: 6111;
+      name: 'ed25519SignerMissing';
+      msg: 'Ed25519 device key did not sign the transaction';
+    },
+    {
+      code: 6112;
+      name: 'invalidDeviceKeyFormat';
+      msg: 'Invalid device public key format';
+    },
+    {
+      code: 6113;
+      name: 'secp256k1InvalidInstruction';
+      msg: 'Secp256k1 instruction has invalid data';
+    },
+    {
+      code: 6114;
+      name: 'secp256k1DataMismatch';
+      msg: 'Secp256k1 signature data validation failed';
+    },
+    {
+      code: 6115;
+      name: 'secp256k1InstructionNotFound';
+      msg: 'Secp256k1 instruction not found at specified index';
+    },
+    {
+      code: 6116;
+      name: 'backupEligibilityMismatch';
+      msg: 'Authenticator backup eligibility (BE) flag does not match the registered device';
+    },
+    {
+      code: 6117;
+      name: 'invalidBackupFlags';
+      msg: 'Invalid backup flags: backup state requires a backup-eligible passkey';
+    },
+    {
+      code: 6118;
+      name: 'deviceBoundCredentialRequired';
+      msg: 'This operation requires a device-bound (non-synced) passkey';
+    },
+    {
+      code: 6119;
+      name: 'attestedCredentialDataMissing';
+      msg: 'Registration authenticator data has no attested credential data';
+    },
+    {
+      code: 6120;
+      name: 'attestationCredentialMismatch';
+      msg: 'Registration authenticator data belongs to a different credential';
+    },
+    {
+      code: 6121;
+      name: 'attestationFlagsMismatch';
+      msg: 'Registration authenticator data flags do not match the declared device flags';
+    },
+    {
+      code: 6122;
+      name: 'aaguidNotAllowed';
+      msg: 'Authenticator model (AAGUID) is not allowed for this wallet';
+    },
+    {
+      code: 6123;
+      name: 'tooManyAllowedAaguids';
+      msg: 'Too many allowed AAGUIDs provided';
+    },
+    {
+      code: 6124;
+      name: 'passkeyNotOnCurve';
+      msg: 'Passkey public key is not a point on the P-256 curve';
+    },
+    {
+      code: 6125;
+      name: 'payerMismatch';
+      msg: 'Challenge is bound to a different fee payer';
+    },
+    {
+      code: 6126;
+      name: 'challengeExpired';
+      msg: 'Challenge expired: its validity window has passed';
+    },
+    {
+      code: 6127;
+      name: 'challengeValidityTooLong';
+      msg: 'Challenge valid_until_slot is too far in the future';
+    },
+    {
+      code: 6128;
+      name: 'invalidTimestampDrift';
+      msg: 'Timestamp drift window is out of range';
+    },
+    {
+      code: 6129;
+      name: 'slotHashesSysvarMissing';
+      msg: 'Challenge commits to a slot hash but the SlotHashes sysvar was not provided';
+    },
+    {
+      code: 6130;
+      name: 'slotHashNotFound';
+      msg: 'Challenge slot hash is unknown or has expired from the SlotHashes sysvar';
+    },
+    {
+      code: 6131;
+      name: 'nonceLaneMismatch';
+      msg: 'Challenge is sequenced on a different nonce lane';
+    },
+    {
+      code: 6132;
+      name: 'invalidNonceLane';
+      msg: 'Nonce lane key is reserved';
+    },
+    {
+      code: 6133;
+      name: 'intentNotYetValid';
+      msg: 'Signed intent is not valid yet';
+    },
+    {
+      code: 6134;
+      name: 'intentWindowRequired';
+      msg: 'Signed intents must carry a validity window';
+    },
+    {
+      code: 6135;
+      name: 'intentAlreadyExecuted';
+      msg: 'Signed intent was already executed';
+    },
+    {
+      code: 6136;
+      name: 'intentCancelled';
+      msg: 'Signed intent was cancelled';
+    },
+    {
+      code: 6137;
+      name: 'intentReceiptMismatch';
+      msg: 'Intent receipt does not match the signed intent';
+    },
+    {
+      code: 6138;
+      name: 'intentReceiptNotExpired';
+      msg: 'Intent receipt cannot be closed before the intent expires';
+    },
+    {
+      code: 6139;
+      name: 'thresholdApprovalRequired';
+      msg: "Operation requires approval from the wallet's device threshold";
+    },
+    {
+      code: 6140;
+      name: 'thresholdNotMet';
+      msg: 'Not enough device approvals to meet the wallet threshold';
+    },
+    {
+      code: 6141;
+      name: 'duplicateDeviceApproval';
+      msg: 'The same device approved more than once';
+    },
+    {
+      code: 6142;
+      name: 'thresholdChallengeMismatch';
+      msg: 'Device approvals sign different challenges';
+    },
+    {
+      code: 6143;
+      name: 'invalidThreshold';
+      msg: 'Device approval threshold is out of range';
+    },
+    {
+      code: 6144;
+      name: 'signedMessageMismatch';
+      msg: 'Signed message hash does not match the message being verified';
+    },
+    {
+      code: 6145;
+      name: 'invalidRemoveDeviceDiscriminator';
+      msg: 'Invalid instruction discriminator for remove_device';
+    },
+    {
+      code: 6146;
+      name: 'lastWalletDevice';
+      msg: 'The last device of a wallet cannot be removed';
+    },
+    {
+      code: 6147;
+      name: 'selfDeviceRemoval';
+      msg: 'A device cannot authorize its own removal';
+    },
+    {
+      code: 6148;
+      name: 'credentialLookupMissing';
+      msg: 'Attested passkey devices must be registered with their credential lookup account';
+    },
+    {
+      code: 6149;
+      name: 'passkeyIndexMissing';
+      msg: 'Passkey devices must be registered with their passkey index while unique passkeys are enforced';
+    },
+    {
+      code: 6150;
+      name: 'allowedOriginsNotConfigured';
+      msg: 'No allowed WebAuthn origins are configured for this wallet or the program';
+    },
+    {
+      code: 6151;
+      name: 'rpIdHashNotConfigured';
+      msg: 'No relying party ID hash is configured for this device or the program';
+    },
+    {
+      code: 6152;
+      name: 'clientDataCrossOrigin';
+      msg: 'Client data reports a cross-origin assertion, which is not accepted';
+    },
+    {
+      code: 6153;
+      name: 'invalidCredentialPublicKey';
+      msg: 'Registration authenticator data has a malformed or non-ES256 credential public key';
+    },
+    {
+      code: 6154;
+      name: 'thresholdOperationNotSupported';
+      msg: 'Threshold approval can only be required for operations that accept co-signers';
+    },
+    {
+      code: 6155;
+      name: 'deviceCountNotTracked';
+      msg: 'The wallet does not track its device count yet; create its device registry first';
+    },
+    {
+      code: 6156;
+      name: 'deviceListApprovalRequired';
+      msg: 'Listing the devices of a wallet without a tracked device count needs every listed device';
+    },
+    {
+      code: 6157;
+      name: 'passkeyIndexProofMissing';
+      msg: 'Claiming a passkey index needs an assertion by that passkey';
+    },
+    {
+      code: 6158;
+      name: 'passkeyIndexProofMismatch';
+      msg: 'Passkey assertion does not sign the passkey index challenge';
+    },
+    {
+      code: 6159;
+      name: 'uniquePasskeysNotEnforced';
+      msg: 'Unique passkeys are not enforced by the config';
+    },
+    {
+      code: 6160;
+      name: 'invalidDeploymentTag';
+      msg: 'Deployment tag must be non-zero';
     }
   ];
   types: [
@@ -1631,25 +3356,65 @@ export type Lazorkit = {
         kind: 'struct';
         fields: [
           {
-            name: 'smartWallet';
-            type: 'pubkey';
+            name: 'smartWallet';
+            type: 'pubkey';
+          },
+          {
+            name: 'newWalletDevice';
+            type: 'pubkey';
+          },
+          {
+            name: 'passkeyHash';
+            type: {
+              array: ['u8', 32];
+            };
+          },
+          {
+            name: 'addedBy';
+            type: 'pubkey';
+          },
+          {
+            name: 'timestamp';
+            type: 'i64';
+          }
+        ];
+      };
+    },
+    {
+      name: 'cancelIntentArgs';
+      type: {
+        kind: 'struct';
+        fields: [
+          {
+            name: 'passkeyPubkey';
+            type: {
+              array: ['u8', 33];
+            };
+          },
+          {
+            name: 'signature';
+            type: 'bytes';
           },
           {
-            name: 'newWalletDevice';
-            type: 'pubkey';
+            name: 'clientDataJsonRaw';
+            type: 'bytes';
           },
           {
-            name: 'passkeyHash';
+            name: 'authenticatorDataRaw';
+            type: 'bytes';
+          },
+          {
+            name: 'verifyInstructionIndex';
+            type: 'u8';
+          },
+          {
+            name: 'intentHash';
             type: {
               array: ['u8', 32];
             };
           },
           {
-            name: 'addedBy';
-            type: 'pubkey';
-          },
-          {
-            name: 'timestamp';
+            name: 'validUntil';
             type: 'i64';
           }
         ];
@@ -1679,6 +3444,79 @@ export type Lazorkit = {
           {
             name: 'isPaused';
             type: 'bool';
+          },
+          {
+            name: 'allowedOriginHashes';
+            docs: [
+              'sha256 hashes of WebAuthn origins accepted for wallets without their own list. Passkey',
+              'assertions are rejected while neither list is set.'
+            ];
+            type: {
+              vec: {
+                array: ['u8', 32];
+              };
+            };
+          },
+          {
+            name: 'rpIdHash';
+            docs: [
+              'sha256 of the relying party ID expected for devices without their own. Passkey',
+              'assertions are rejected while neither is set.'
+            ];
+            type: {
+              option: {
+                array: ['u8', 32];
+              };
+            };
+          },
+          {
+            name: 'requireUserVerification';
+            docs: [
+              'Require the user verification (UV) flag on every assertion'
+            ];
+            type: 'bool';
+          },
+          {
+            name: 'minMessageVersion';
+            docs: [
+              'Oldest challenge format still accepted (0 = legacy un-enveloped challenges)'
+            ];
+            type: 'u8';
+          },
+          {
+            name: 'deploymentTag';
+            docs: [
+              'Deployment/cluster identifier mixed into every challenge domain separator (0 only on',
+              'configs migrated from before it existed)'
+            ];
+            type: 'u64';
+          },
+          {
+            name: 'allowedAaguids';
+            docs: [
+              'Authenticator models (AAGUIDs) that may be enrolled in wallets without their own list.',
+              'AAGUIDs are reported by the client without an attestation statement, so this is a filter',
+              'rather than a proof of the authenticator model.'
+            ];
+            type: {
+              vec: {
+                array: ['u8', 16];
+              };
+            };
+          },
+          {
+            name: 'maxWalletDevices';
+            docs: [
+              'Maximum number of devices per wallet (0 = `MAX_WALLET_DEVICES`)'
+            ];
+            type: 'u8';
+          },
+          {
+            name: 'uniquePasskeys';
+            docs: [
+              'Bind each newly registered passkey to a single wallet through its `PasskeyIndex`'
+            ];
+            type: 'bool';
           }
         ];
       };
@@ -1712,6 +3550,34 @@ export type Lazorkit = {
         ];
       };
     },
+    {
+      name: 'createDeviceRegistryArgs';
+      type: {
+        kind: 'struct';
+        fields: [
+          {
+            name: 'approval';
+            type: {
+              option: {
+                defined: {
+                  name: 'deviceAssertion';
+                };
+              };
+            };
+          },
+          {
+            name: 'coSigners';
+            type: {
+              vec: {
+                defined: {
+                  name: 'deviceAssertion';
+                };
+              };
+            };
+          }
+        ];
+      };
+    },
     {
       name: 'createSessionArgs';
       type: {
@@ -1732,50 +3598,286 @@ export type Lazorkit = {
             type: 'bytes';
           },
           {
-            name: 'authenticatorDataRaw';
-            type: 'bytes';
+            name: 'authenticatorDataRaw';
+            type: 'bytes';
+          },
+          {
+            name: 'verifyInstructionIndex';
+            type: 'u8';
+          },
+          {
+            name: 'policyData';
+            type: 'bytes';
+          },
+          {
+            name: 'expiresAt';
+            type: 'i64';
+          }
+        ];
+      };
+    },
+    {
+      name: 'createSmartWalletArgs';
+      type: {
+        kind: 'struct';
+        fields: [
+          {
+            name: 'deviceKind';
+            type: {
+              defined: {
+                name: 'deviceKind';
+              };
+            };
+          },
+          {
+            name: 'passkeyPubkey';
+            type: 'bytes';
+          },
+          {
+            name: 'credentialId';
+            type: 'bytes';
+          },
+          {
+            name: 'policyData';
+            type: 'bytes';
+          },
+          {
+            name: 'walletId';
+            type: 'u64';
+          },
+          {
+            name: 'isPayForUser';
+            type: 'bool';
+          },
+          {
+            name: 'allowedOriginHashes';
+            type: {
+              vec: {
+                array: ['u8', 32];
+              };
+            };
+          },
+          {
+            name: 'rpIdHash';
+            type: {
+              option: {
+                array: ['u8', 32];
+              };
+            };
+          },
+          {
+            name: 'requireUserVerification';
+            type: 'bool';
+          },
+          {
+            name: 'backupEligible';
+            type: 'bool';
+          },
+          {
+            name: 'backupState';
+            type: 'bool';
+          },
+          {
+            name: 'deviceBoundOperations';
+            type: 'u8';
+          },
+          {
+            name: 'allowedAaguids';
+            type: {
+              vec: {
+                array: ['u8', 16];
+              };
+            };
+          },
+          {
+            name: 'attestationAuthenticatorData';
+            type: {
+              option: 'bytes';
+            };
+          },
+          {
+            name: 'timestampDriftSeconds';
+            type: {
+              option: 'u32';
+            };
+          },
+          {
+            name: 'passkeyProof';
+            type: {
+              option: {
+                defined: {
+                  name: 'deviceAssertion';
+                };
+              };
+            };
+          }
+        ];
+      };
+    },
+    {
+      name: 'credentialLookup';
+      docs: [
+        'Reverse lookup from a passkey credential ID to the wallet_device registered with it, so a',
+        'discoverable-credential login can find its wallet on-chain. Seeded by `sha256(credential_id)`.',
+        '',
+        'Only registrations whose authenticatorData attests the credential ID and public key are',
+        'indexed, since an unattested credential ID is just a client-supplied label that anyone could',
+        'copy. The first attested registration of a credential keeps the lookup until its device is',
+        'removed; later ones are registered without it. Credential IDs are not secret, so clients must',
+        "still check the device's public key against the assertion they received."
+      ];
+      type: {
+        kind: 'struct';
+        fields: [
+          {
+            name: 'smartWallet';
+            docs: ['Smart wallet the credential is registered to'];
+            type: 'pubkey';
+          },
+          {
+            name: 'walletDevice';
+            docs: ["wallet_device PDA holding the credential's public key"];
+            type: 'pubkey';
+          },
+          {
+            name: 'bump';
+            docs: ['Bump seed for PDA derivation'];
+            type: 'u8';
+          }
+        ];
+      };
+    },
+    {
+      name: 'deviceAssertion';
+      docs: ['Signed assertion from one device in a threshold authorization'];
+      type: {
+        kind: 'struct';
+        fields: [
+          {
+            name: 'passkeyPubkey';
+            type: {
+              array: ['u8', 33];
+            };
+          },
+          {
+            name: 'signature';
+            type: 'bytes';
+          },
+          {
+            name: 'clientDataJsonRaw';
+            type: 'bytes';
+          },
+          {
+            name: 'authenticatorDataRaw';
+            type: 'bytes';
+          },
+          {
+            name: 'verifyInstructionIndex';
+            type: 'u8';
+          }
+        ];
+      };
+    },
+    {
+      name: 'deviceEntry';
+      docs: ['One wallet_device known to a [`DeviceRegistry`]'];
+      type: {
+        kind: 'struct';
+        fields: [
+          {
+            name: 'walletDevice';
+            docs: ['wallet_device PDA'];
+            type: 'pubkey';
+          },
+          {
+            name: 'credentialIdHash';
+            docs: [
+              "sha256 of the device's credential ID (of an empty ID for keypair devices)"
+            ];
+            type: {
+              array: ['u8', 32];
+            };
+          },
+          {
+            name: 'kind';
+            type: {
+              defined: {
+                name: 'deviceKind';
+              };
+            };
           },
           {
-            name: 'verifyInstructionIndex';
-            type: 'u8';
+            name: 'status';
+            type: {
+              defined: {
+                name: 'deviceStatus';
+              };
+            };
+          }
+        ];
+      };
+    },
+    {
+      name: 'deviceKind';
+      docs: ['Key type a wallet_device authenticates with'];
+      type: {
+        kind: 'enum';
+        variants: [
+          {
+            name: 'passkey';
           },
           {
-            name: 'policyData';
-            type: 'bytes';
+            name: 'ed25519';
           },
           {
-            name: 'expiresAt';
-            type: 'i64';
+            name: 'secp256k1';
           }
         ];
       };
     },
     {
-      name: 'createSmartWalletArgs';
+      name: 'deviceRegistry';
+      docs: [
+        'Per-wallet index of wallet_device accounts, so devices can be listed without an indexer.',
+        'Kept in sync by every path that adds or removes a device.'
+      ];
       type: {
         kind: 'struct';
         fields: [
           {
-            name: 'passkeyPubkey';
-            type: {
-              array: ['u8', 33];
-            };
+            name: 'smartWallet';
+            docs: ['Smart wallet the devices belong to'];
+            type: 'pubkey';
           },
           {
-            name: 'credentialId';
-            type: 'bytes';
+            name: 'bump';
+            docs: ['Bump seed for PDA derivation'];
+            type: 'u8';
           },
           {
-            name: 'policyData';
-            type: 'bytes';
-          },
+            name: 'devices';
+            type: {
+              vec: {
+                defined: {
+                  name: 'deviceEntry';
+                };
+              };
+            };
+          }
+        ];
+      };
+    },
+    {
+      name: 'deviceStatus';
+      docs: ['Whether a registry entry still refers to a live wallet_device'];
+      type: {
+        kind: 'enum';
+        variants: [
           {
-            name: 'walletId';
-            type: 'u64';
+            name: 'active';
           },
           {
-            name: 'isPayForUser';
-            type: 'bool';
+            name: 'removed';
           }
         ];
       };
@@ -1849,6 +3951,24 @@ export type Lazorkit = {
           {
             name: 'cpiData';
             type: 'bytes';
+          },
+          {
+            name: 'intentHash';
+            type: {
+              option: {
+                array: ['u8', 32];
+              };
+            };
+          },
+          {
+            name: 'coSigners';
+            type: {
+              vec: {
+                defined: {
+                  name: 'deviceAssertion';
+                };
+              };
+            };
           }
         ];
       };
@@ -1882,6 +4002,73 @@ export type Lazorkit = {
         ];
       };
     },
+    {
+      name: 'intentReceipt';
+      docs: [
+        'Replay registry entry for one signed intent, seeded by the intent hash.',
+        "Kept until the intent's validity window has passed, then anyone may close it."
+      ];
+      type: {
+        kind: 'struct';
+        fields: [
+          {
+            name: 'smartWallet';
+            docs: ['Smart wallet the intent belongs to'];
+            type: 'pubkey';
+          },
+          {
+            name: 'intentHash';
+            docs: ['sha256 of the signed intent challenge'];
+            type: {
+              array: ['u8', 32];
+            };
+          },
+          {
+            name: 'status';
+            type: {
+              defined: {
+                name: 'intentStatus';
+              };
+            };
+          },
+          {
+            name: 'expiresAt';
+            docs: [
+              'Unix timestamp after which the intent can no longer execute and the receipt may be closed'
+            ];
+            type: 'i64';
+          },
+          {
+            name: 'rentRefundTo';
+            docs: ['Where to refund rent when closing the receipt'];
+            type: 'pubkey';
+          },
+          {
+            name: 'bump';
+            docs: ['Bump seed for PDA derivation'];
+            type: 'u8';
+          }
+        ];
+      };
+    },
+    {
+      name: 'intentStatus';
+      docs: ['Lifecycle of a signed intent in the replay registry'];
+      type: {
+        kind: 'enum';
+        variants: [
+          {
+            name: 'unused';
+          },
+          {
+            name: 'executed';
+          },
+          {
+            name: 'cancelled';
+          }
+        ];
+      };
+    },
     {
       name: 'invokePolicyArgs';
       type: {
@@ -1922,6 +4109,16 @@ export type Lazorkit = {
                 };
               };
             };
+          },
+          {
+            name: 'coSigners';
+            type: {
+              vec: {
+                defined: {
+                  name: 'deviceAssertion';
+                };
+              };
+            };
           }
         ];
       };
@@ -1932,14 +4129,119 @@ export type Lazorkit = {
         kind: 'struct';
         fields: [
           {
-            name: 'passkeyPubkey';
+            name: 'deviceKind';
             type: {
-              array: ['u8', 33];
+              defined: {
+                name: 'deviceKind';
+              };
             };
           },
+          {
+            name: 'passkeyPubkey';
+            type: 'bytes';
+          },
           {
             name: 'credentialId';
             type: 'bytes';
+          },
+          {
+            name: 'rpIdHash';
+            type: {
+              option: {
+                array: ['u8', 32];
+              };
+            };
+          },
+          {
+            name: 'backupEligible';
+            type: 'bool';
+          },
+          {
+            name: 'backupState';
+            type: 'bool';
+          },
+          {
+            name: 'attestationAuthenticatorData';
+            type: {
+              option: 'bytes';
+            };
+          },
+          {
+            name: 'passkeyProof';
+            type: {
+              option: {
+                defined: {
+                  name: 'deviceAssertion';
+                };
+              };
+            };
+          }
+        ];
+      };
+    },
+    {
+      name: 'nonceLane';
+      docs: [
+        'Independent nonce sequence for one lane of a smart wallet, so operations approved on',
+        'different lanes can be pending at the same time without invalidating each other'
+      ];
+      type: {
+        kind: 'struct';
+        fields: [
+          {
+            name: 'smartWallet';
+            docs: ['Smart wallet this lane belongs to'];
+            type: 'pubkey';
+          },
+          {
+            name: 'lane';
+            docs: [
+              'Lane key chosen by the client; `PRIMARY_NONCE_LANE` and `INTENT_NONCE_LANE` are reserved'
+            ];
+            type: 'u64';
+          },
+          {
+            name: 'sequence';
+            docs: ['Next sequence number expected on this lane'];
+            type: 'u64';
+          },
+          {
+            name: 'bump';
+            docs: ['Bump seed for PDA derivation'];
+            type: 'u8';
+          }
+        ];
+      };
+    },
+    {
+      name: 'passkeyIndex';
+      docs: [
+        'Global claim on a passkey public key, seeded by `sha256(passkey_pubkey)`. While',
+        '`Config::unique_passkeys` is on, a passkey can only be registered to the wallet holding its',
+        'index, and only with an assertion by the passkey itself so that a key cannot be claimed by',
+        'someone who merely knows it.',
+        '',
+        'Passkeys registered while the mode was off have no index until they claim one with',
+        '`claim_passkey_index`. If such a passkey was registered to several wallets, the first to claim',
+        'keeps the index; the other registrations are not undone.'
+      ];
+      type: {
+        kind: 'struct';
+        fields: [
+          {
+            name: 'smartWallet';
+            docs: ['Smart wallet the passkey is bound to'];
+            type: 'pubkey';
+          },
+          {
+            name: 'walletDevice';
+            docs: ['wallet_device PDA holding the passkey'];
+            type: 'pubkey';
+          },
+          {
+            name: 'bump';
+            docs: ['Bump seed for PDA derivation'];
+            type: 'u8';
           }
         ];
       };
@@ -2032,29 +4334,73 @@ export type Lazorkit = {
             type: 'pubkey';
           },
           {
-            name: 'timestamp';
-            type: 'i64';
-          }
-        ];
-      };
-    },
-    {
-      name: 'programPausedStateChanged';
-      docs: ['Event emitted when program is paused/unpaused'];
-      type: {
-        kind: 'struct';
-        fields: [
-          {
-            name: 'authority';
-            type: 'pubkey';
+            name: 'timestamp';
+            type: 'i64';
+          }
+        ];
+      };
+    },
+    {
+      name: 'programPausedStateChanged';
+      docs: ['Event emitted when program is paused/unpaused'];
+      type: {
+        kind: 'struct';
+        fields: [
+          {
+            name: 'authority';
+            type: 'pubkey';
+          },
+          {
+            name: 'isPaused';
+            type: 'bool';
+          },
+          {
+            name: 'timestamp';
+            type: 'i64';
+          }
+        ];
+      };
+    },
+    {
+      name: 'removeWalletDeviceArgs';
+      type: {
+        kind: 'struct';
+        fields: [
+          {
+            name: 'passkeyPubkey';
+            type: {
+              array: ['u8', 33];
+            };
+          },
+          {
+            name: 'signature';
+            type: 'bytes';
+          },
+          {
+            name: 'clientDataJsonRaw';
+            type: 'bytes';
+          },
+          {
+            name: 'authenticatorDataRaw';
+            type: 'bytes';
+          },
+          {
+            name: 'verifyInstructionIndex';
+            type: 'u8';
           },
           {
-            name: 'isPaused';
-            type: 'bool';
+            name: 'policyData';
+            type: 'bytes';
           },
           {
-            name: 'timestamp';
-            type: 'i64';
+            name: 'coSigners';
+            type: {
+              vec: {
+                defined: {
+                  name: 'deviceAssertion';
+                };
+              };
+            };
           }
         ];
       };
@@ -2115,6 +4461,80 @@ export type Lazorkit = {
             name: 'bump';
             docs: ['Bump seed for PDA derivation'];
             type: 'u8';
+          },
+          {
+            name: 'allowedOriginHashes';
+            docs: [
+              'sha256 hashes of WebAuthn origins accepted for this wallet (empty = use config default)'
+            ];
+            type: {
+              vec: {
+                array: ['u8', 32];
+              };
+            };
+          },
+          {
+            name: 'requireUserVerification';
+            docs: [
+              'Require the user verification (UV) flag regardless of the config setting'
+            ];
+            type: 'bool';
+          },
+          {
+            name: 'deviceBoundOperations';
+            docs: [
+              'Bitmask of [`MessageOperation::mask`] values that only a device-bound passkey may approve;',
+              'operations without a bit are included whenever any bit is set',
+              '',
+              '[`MessageOperation::mask`]: crate::state::MessageOperation::mask'
+            ];
+            type: 'u8';
+          },
+          {
+            name: 'allowedAaguids';
+            docs: [
+              'Authenticator models (AAGUIDs) that may be enrolled (empty = use config default)'
+            ];
+            type: {
+              vec: {
+                array: ['u8', 16];
+              };
+            };
+          },
+          {
+            name: 'timestampDriftSeconds';
+            docs: [
+              'Drift window for challenge timestamps in seconds (None = program default)'
+            ];
+            type: {
+              option: 'u32';
+            };
+          },
+          {
+            name: 'threshold';
+            docs: [
+              'Number of distinct devices that must approve operations in `threshold_operations`'
+            ];
+            type: 'u8';
+          },
+          {
+            name: 'thresholdOperations';
+            docs: [
+              'Bitmask of [`MessageOperation::mask`] values that need `threshold` device approvals;',
+              'operations without a bit are included whenever any bit is set',
+              '',
+              '[`MessageOperation::mask`]: crate::state::MessageOperation::mask'
+            ];
+            type: 'u8';
+          },
+          {
+            name: 'deviceCount';
+            docs: [
+              'Number of wallet_device accounts registered to this wallet (0 = not tracked, for wallets',
+              'migrated from a layout without this field; such wallets cannot add or remove devices until',
+              'their devices are listed with `create_device_registry`)'
+            ];
+            type: 'u16';
           }
         ];
       };
@@ -2197,6 +4617,22 @@ export type Lazorkit = {
             name: 'authenticator';
             type: 'pubkey';
           },
+          {
+            name: 'deviceKind';
+            type: {
+              defined: {
+                name: 'deviceKind';
+              };
+            };
+          },
+          {
+            name: 'backupEligible';
+            type: 'bool';
+          },
+          {
+            name: 'backupState';
+            type: 'bool';
+          },
           {
             name: 'nonce';
             type: 'u64';
@@ -2293,6 +4729,21 @@ export type Lazorkit = {
           },
           {
             name: 'unpauseProgram';
+          },
+          {
+            name: 'requireUserVerification';
+          },
+          {
+            name: 'minMessageVersion';
+          },
+          {
+            name: 'deploymentTag';
+          },
+          {
+            name: 'maxWalletDevices';
+          },
+          {
+            name: 'uniquePasskeys';
           }
         ];
       };
@@ -2345,6 +4796,110 @@ export type Lazorkit = {
                 };
               };
             };
+          },
+          {
+            name: 'coSigners';
+            type: {
+              vec: {
+                defined: {
+                  name: 'deviceAssertion';
+                };
+              };
+            };
+          }
+        ];
+      };
+    },
+    {
+      name: 'updateWalletSettingsArgs';
+      type: {
+        kind: 'struct';
+        fields: [
+          {
+            name: 'passkeyPubkey';
+            type: {
+              array: ['u8', 33];
+            };
+          },
+          {
+            name: 'signature';
+            type: 'bytes';
+          },
+          {
+            name: 'clientDataJsonRaw';
+            type: 'bytes';
+          },
+          {
+            name: 'authenticatorDataRaw';
+            type: 'bytes';
+          },
+          {
+            name: 'verifyInstructionIndex';
+            type: 'u8';
+          },
+          {
+            name: 'settings';
+            type: {
+              defined: {
+                name: 'walletSettings';
+              };
+            };
+          },
+          {
+            name: 'coSigners';
+            type: {
+              vec: {
+                defined: {
+                  name: 'deviceAssertion';
+                };
+              };
+            };
+          }
+        ];
+      };
+    },
+    {
+      name: 'verifySignatureArgs';
+      type: {
+        kind: 'struct';
+        fields: [
+          {
+            name: 'passkeyPubkey';
+            type: {
+              array: ['u8', 33];
+            };
+          },
+          {
+            name: 'signature';
+            type: 'bytes';
+          },
+          {
+            name: 'clientDataJsonRaw';
+            type: 'bytes';
+          },
+          {
+            name: 'authenticatorDataRaw';
+            type: 'bytes';
+          },
+          {
+            name: 'verifyInstructionIndex';
+            type: 'u8';
+          },
+          {
+            name: 'messageHash';
+            type: {
+              array: ['u8', 32];
+            };
+          },
+          {
+            name: 'coSigners';
+            type: {
+              vec: {
+                defined: {
+                  name: 'deviceAssertion';
+                };
+              };
+            };
           }
         ];
       };
@@ -2352,7 +4907,7 @@ export type Lazorkit = {
     {
       name: 'walletDevice';
       docs: [
-        'Account that stores a wallet_device (passkey) used to authenticate to a smart wallet'
+        'Account that stores a wallet_device (passkey or keypair) used to authenticate to a smart wallet'
       ];
       type: {
         kind: 'struct';
@@ -2360,7 +4915,8 @@ export type Lazorkit = {
           {
             name: 'passkeyPubkey';
             docs: [
-              'The public key of the passkey for this wallet_device that can authorize transactions'
+              'The public key of this wallet_device that can authorize transactions. Ed25519 keys occupy',
+              'the first 32 bytes and Ethereum addresses the first 20 bytes; the rest is zero.'
             ];
             type: {
               array: ['u8', 33];
@@ -2380,6 +4936,113 @@ export type Lazorkit = {
             name: 'bump';
             docs: ['Bump seed for PDA derivation'];
             type: 'u8';
+          },
+          {
+            name: 'kind';
+            docs: ['Key type of `passkey_pubkey`'];
+            type: {
+              defined: {
+                name: 'deviceKind';
+              };
+            };
+          },
+          {
+            name: 'rpIdHash';
+            docs: [
+              'sha256 of the relying party ID this passkey was created for (None = use config)'
+            ];
+            type: {
+              option: {
+                array: ['u8', 32];
+              };
+            };
+          },
+          {
+            name: 'signCount';
+            docs: [
+              'Last WebAuthn signature counter seen for this passkey (0 = authenticator has no counter)'
+            ];
+            type: 'u32';
+          },
+          {
+            name: 'backupEligible';
+            docs: [
+              'WebAuthn backup eligibility (BE) flag; fixed for the lifetime of a credential'
+            ];
+            type: 'bool';
+          },
+          {
+            name: 'backupState';
+            docs: ['WebAuthn backup state (BS) flag as of the last assertion'];
+            type: 'bool';
+          },
+          {
+            name: 'aaguid';
+            docs: [
+              'Authenticator model reported in the registration authenticatorData, if supplied'
+            ];
+            type: {
+              option: {
+                array: ['u8', 16];
+              };
+            };
+          },
+          {
+            name: 'backupEligibilityRecorded';
+            docs: [
+              'Whether `backup_eligible` has been recorded. Migrated legacy passkeys record it from their',
+              'first assertion.'
+            ];
+            type: 'bool';
+          }
+        ];
+      };
+    },
+    {
+      name: 'walletSettings';
+      docs: [
+        'Per-wallet security settings, replaced as a whole by `update_wallet_settings`'
+      ];
+      type: {
+        kind: 'struct';
+        fields: [
+          {
+            name: 'allowedOriginHashes';
+            type: {
+              vec: {
+                array: ['u8', 32];
+              };
+            };
+          },
+          {
+            name: 'requireUserVerification';
+            type: 'bool';
+          },
+          {
+            name: 'deviceBoundOperations';
+            type: 'u8';
+          },
+          {
+            name: 'allowedAaguids';
+            type: {
+              vec: {
+                array: ['u8', 16];
+              };
+            };
+          },
+          {
+            name: 'timestampDriftSeconds';
+            type: {
+              option: 'u32';
+            };
+          },
+          {
+            name: 'threshold';
+            type: 'u8';
+          },
+          {
+            name: 'thresholdOperations';
+            type: 'u8';
           }
         ];
       };
@@ -78,4 +78,21 @@ export class DefaultPolicyClient {
       })
       .instruction();
   }
+
+  async buildRemoveDeviceIx(
+    walletDevice: PublicKey,
+    removedWalletDevice: PublicKey,
+    rentRefund: PublicKey
+  ): Promise<TransactionInstruction> {
+    return await this.program.methods
+      .removeDevice()
+      .accountsPartial({
+        walletDevice,
+        removedWalletDevice,
+        policy: this.policyPda(walletDevice),
+        removedPolicy: this.policyPda(removedWalletDevice),
+        rentRefund,
+      })
+      .instruction();
+  }
 }
@@ -18,6 +18,9 @@ import {
   deriveSmartWalletDataPda,
   deriveWalletDevicePda,
   deriveTransactionSessionPda,
+  deriveDeviceRegistryPda,
+  deriveCredentialLookupPda,
+  derivePasskeyIndexPda,
 } from '../pda/lazorkit';
 import { getRandomBytes, instructionToAccountMetas } from '../utils';
 import * as types from '../types';
@@ -27,6 +30,7 @@ import {
   buildInvokePolicyMessage,
   buildUpdatePolicyMessage,
   buildExecuteMessage,
+  computeDomainSeparator,
 } from '../messages';
 import { Buffer } from 'buffer';
 import {
@@ -110,6 +114,27 @@ export class LazorkitClient {
     return deriveWalletDevicePda(this.programId, smartWallet, passkey)[0];
   }
 
+  /**
+   * Derives the device registry PDA for a given smart wallet
+   */
+  deviceRegistryPda(smartWallet: PublicKey): PublicKey {
+    return deriveDeviceRegistryPda(this.programId, smartWallet);
+  }
+
+  /**
+   * Derives the credential lookup PDA for a passkey credential ID
+   */
+  credentialLookupPda(credentialId: Buffer): PublicKey {
+    return deriveCredentialLookupPda(this.programId, credentialId);
+  }
+
+  /**
+   * Derives the global passkey index PDA for a compressed passkey public key
+   */
+  passkeyIndexPda(passkey: number[]): PublicKey {
+    return derivePasskeyIndexPda(this.programId, passkey);
+  }
+
   /**
    * Derives a transaction session PDA for a given smart wallet and nonce
    */
@@ -154,6 +179,35 @@ export class LazorkitClient {
     return await this.program.account.walletDevice.fetch(walletDevice);
   }
 
+  /**
+   * Fetches the device registry of a smart wallet
+   */
+  async getDeviceRegistryData(smartWallet: PublicKey) {
+    return await this.program.account.deviceRegistry.fetch(
+      this.deviceRegistryPda(smartWallet)
+    );
+  }
+
+  /**
+   * Finds the smart wallet and device a discoverable passkey credential is registered to.
+   * The device's public key must still be checked against the assertion.
+   */
+  async getSmartWalletByCredentialId(credentialId: Buffer): Promise<{
+    smartWallet: PublicKey | null;
+    walletDevice: PublicKey | null;
+  }> {
+    const lookup = await this.program.account.credentialLookup.fetchNullable(
+      this.credentialLookupPda(credentialId)
+    );
+    if (!lookup) {
+      return { walletDevice: null, smartWallet: null };
+    }
+    return {
+      walletDevice: lookup.walletDevice,
+      smartWallet: lookup.smartWallet,
+    };
+  }
+
   /**
    * Finds a smart wallet by passkey public key
    */
@@ -193,13 +247,119 @@ export class LazorkitClient {
   // ============================================================================
 
   /**
-   * Builds the initialize program instruction
+   * Writable wallet_device accounts of threshold co-signers
+   */
+  private coSignerAccountMetas(
+    smartWallet: PublicKey,
+    coSigners: types.DeviceAssertion[]
+  ): AccountMeta[] {
+    return coSigners.map((coSigner) => ({
+      pubkey: this.walletDevicePda(smartWallet, coSigner.passkeyPubkey),
+      isWritable: true,
+      isSigner: false,
+    }));
+  }
+
+  /**
+   * Instruction args registering a new passkey device; its passkey index proof is verified by
+   * the instruction at `proofInstructionIndex` (see `passkeyProofInstructions`)
+   */
+  private newWalletDeviceArgs(
+    device: types.NewPasskeyDevice | null | undefined,
+    proofInstructionIndex: number
+  ): types.NewWalletDeviceArgs | null {
+    if (!device) {
+      return null;
+    }
+    return {
+      deviceKind: { passkey: {} },
+      passkeyPubkey: Buffer.from(device.passkeyPubkey),
+      credentialId: Buffer.from(device.credentialIdBase64, 'base64'),
+      rpIdHash: null,
+      backupEligible: device.backupEligible === true,
+      backupState: device.backupState === true,
+      attestationAuthenticatorData: null,
+      passkeyProof: this.passkeyProofArgs(
+        device.passkeyProof,
+        proofInstructionIndex
+      ),
+    };
+  }
+
+  /**
+   * Borsh-encoded args of a new passkey device, as covered by the signed policy data hash.
+   * Must match the args sent by `invokePolicyWithAuth` / `updatePolicyWithAuth`.
+   */
+  private encodeNewWalletDeviceArgs(
+    device: types.NewPasskeyDevice | null | undefined
+  ): Buffer | null {
+    const args = this.newWalletDeviceArgs(device, 1);
+    if (!args) {
+      return null;
+    }
+    return this.program.coder.types.encode('newWalletDeviceArgs', args);
+  }
+
+  /**
+   * Assertion args of a passkey index proof
+   */
+  private passkeyProofArgs(
+    proof: types.PasskeySignature | null | undefined,
+    verifyInstructionIndex: number
+  ): types.DeviceAssertion | null {
+    if (!proof) {
+      return null;
+    }
+    return {
+      ...convertPasskeySignatureToInstructionArgs(proof),
+      verifyInstructionIndex,
+    };
+  }
+
+  /**
+   * Secp256r1 verify instruction of a passkey index proof, if any
+   */
+  private passkeyProofInstructions(
+    proof: types.PasskeySignature | null | undefined
+  ): TransactionInstruction[] {
+    return proof ? [buildPasskeyVerificationInstruction(proof)] : [];
+  }
+
+  /**
+   * Credential lookup account of a device being registered (keypair devices have none)
+   */
+  private newCredentialLookup(
+    device: { deviceKind: object; credentialId: Buffer } | null
+  ): PublicKey | null {
+    if (!device || !('passkey' in device.deviceKind)) {
+      return null;
+    }
+    return this.credentialLookupPda(device.credentialId);
+  }
+
+  /**
+   * Passkey index account of a device being registered (keypair devices have none). Only
+   * claimed on-chain while the config enforces unique passkeys.
+   */
+  private newPasskeyIndex(
+    device: { deviceKind: object; passkeyPubkey: Buffer } | null
+  ): PublicKey | null {
+    if (!device || !('passkey' in device.deviceKind)) {
+      return null;
+    }
+    return this.passkeyIndexPda(Array.from(device.passkeyPubkey));
+  }
+
+  /**
+   * Builds the initialize program instruction. `deploymentTag` must be non-zero and unique per
+   * cluster, since it separates the challenge domains of deployments.
    */
   async buildInitializeInstruction(
-    payer: PublicKey
+    payer: PublicKey,
+    deploymentTag: BN
   ): Promise<TransactionInstruction> {
     return await this.program.methods
-      .initialize()
+      .initialize(deploymentTag)
       .accountsPartial({
         signer: payer,
         config: this.configPda(),
@@ -228,8 +388,12 @@ export class LazorkitClient {
         smartWalletData: this.smartWalletDataPda(smartWallet),
         policyProgramRegistry: this.policyProgramRegistryPda(),
         walletDevice,
+        deviceRegistry: this.deviceRegistryPda(smartWallet),
+        credentialLookup: this.newCredentialLookup(args),
+        passkeyIndex: this.newPasskeyIndex(args),
         config: this.configPda(),
         defaultPolicyProgram: this.defaultPolicyProgram.programId,
+        ixSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
         systemProgram: SystemProgram.programId,
       })
       .remainingAccounts([
@@ -260,8 +424,14 @@ export class LazorkitClient {
         cpiProgram: cpiInstruction.programId,
         config: this.configPda(),
         ixSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+        systemProgram: SystemProgram.programId,
+        intentReceipt: null,
+        nonceLane: null,
+        slotHashes: null,
       })
       .remainingAccounts([
+        // Co-signing devices lead the remaining accounts, in the order of `args.coSigners`
+        ...this.coSignerAccountMetas(smartWallet, args.coSigners),
         ...instructionToAccountMetas(policyInstruction, payer),
         ...instructionToAccountMetas(cpiInstruction, payer),
       ])
@@ -277,12 +447,16 @@ export class LazorkitClient {
     args: types.InvokePolicyArgs,
     policyInstruction: TransactionInstruction
   ): Promise<TransactionInstruction> {
-    const remaining: AccountMeta[] = [];
+    // Co-signing devices lead the remaining accounts, in the order of `args.coSigners`
+    const remaining: AccountMeta[] = this.coSignerAccountMetas(
+      smartWallet,
+      args.coSigners
+    );
 
     if (args.newWalletDevice) {
       const newWalletDevice = this.walletDevicePda(
         smartWallet,
-        args.newWalletDevice.passkeyPubkey
+        Array.from(args.newWalletDevice.passkeyPubkey)
       );
       remaining.push({
         pubkey: newWalletDevice,
@@ -301,10 +475,15 @@ export class LazorkitClient {
         smartWallet,
         smartWalletData: this.smartWalletDataPda(smartWallet),
         walletDevice: this.walletDevicePda(smartWallet, args.passkeyPubkey),
+        deviceRegistry: this.deviceRegistryPda(smartWallet),
+        credentialLookup: this.newCredentialLookup(args.newWalletDevice),
+        passkeyIndex: this.newPasskeyIndex(args.newWalletDevice),
         policyProgram: policyInstruction.programId,
         policyProgramRegistry: this.policyProgramRegistryPda(),
         ixSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
         systemProgram: SystemProgram.programId,
+        nonceLane: null,
+        slotHashes: null,
       })
       .remainingAccounts(remaining)
       .instruction();
@@ -320,12 +499,16 @@ export class LazorkitClient {
     destroyPolicyInstruction: TransactionInstruction,
     initPolicyInstruction: TransactionInstruction
   ): Promise<TransactionInstruction> {
-    const remaining: AccountMeta[] = [];
+    // Co-signing devices lead the remaining accounts, in the order of `args.coSigners`
+    const remaining: AccountMeta[] = this.coSignerAccountMetas(
+      smartWallet,
+      args.coSigners
+    );
 
     if (args.newWalletDevice) {
       const newWalletDevice = this.walletDevicePda(
         smartWallet,
-        args.newWalletDevice.passkeyPubkey
+        Array.from(args.newWalletDevice.passkeyPubkey)
       );
       remaining.push({
         pubkey: newWalletDevice,
@@ -347,16 +530,126 @@ export class LazorkitClient {
         smartWallet,
         smartWalletData: this.smartWalletDataPda(smartWallet),
         walletDevice: this.walletDevicePda(smartWallet, args.passkeyPubkey),
+        deviceRegistry: this.deviceRegistryPda(smartWallet),
+        credentialLookup: this.newCredentialLookup(args.newWalletDevice),
+        passkeyIndex: this.newPasskeyIndex(args.newWalletDevice),
         oldPolicyProgram: destroyPolicyInstruction.programId,
         newPolicyProgram: initPolicyInstruction.programId,
         policyProgramRegistry: this.policyProgramRegistryPda(),
         ixSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
         systemProgram: SystemProgram.programId,
+        nonceLane: null,
+        slotHashes: null,
       })
       .remainingAccounts(remaining)
       .instruction();
   }
 
+  /**
+   * Builds the remove wallet device instruction
+   */
+  async buildRemoveWalletDeviceInstruction(
+    payer: PublicKey,
+    smartWallet: PublicKey,
+    removedWalletDevice: PublicKey,
+    removedCredentialId: Buffer,
+    removedPasskeyPubkey: number[],
+    rentRefund: PublicKey,
+    args: types.RemoveWalletDeviceArgs,
+    policyInstruction: TransactionInstruction
+  ): Promise<TransactionInstruction> {
+    return await this.program.methods
+      .removeWalletDevice(args)
+      .accountsPartial({
+        payer,
+        config: this.configPda(),
+        smartWallet,
+        smartWalletData: this.smartWalletDataPda(smartWallet),
+        walletDevice: this.walletDevicePda(smartWallet, args.passkeyPubkey),
+        removedWalletDevice,
+        deviceRegistry: this.deviceRegistryPda(smartWallet),
+        credentialLookup: this.credentialLookupPda(removedCredentialId),
+        passkeyIndex: this.passkeyIndexPda(removedPasskeyPubkey),
+        rentRefund,
+        policyProgram: policyInstruction.programId,
+        policyProgramRegistry: this.policyProgramRegistryPda(),
+        ixSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+        nonceLane: null,
+        slotHashes: null,
+      })
+      .remainingAccounts([
+        // Co-signing devices lead the remaining accounts, in the order of `args.coSigners`
+        ...this.coSignerAccountMetas(smartWallet, args.coSigners),
+        ...instructionToAccountMetas(policyInstruction, payer),
+      ])
+      .instruction();
+  }
+
+  /**
+   * Builds the create device registry instruction. `walletDevices` must list every device of the
+   * wallet; wallets that do not track their device count also need `args.approval` from one of
+   * them (see `buildSyncDevicesMessage`).
+   */
+  async buildCreateDeviceRegistryInstruction(
+    payer: PublicKey,
+    smartWallet: PublicKey,
+    walletDevices: PublicKey[],
+    args: types.CreateDeviceRegistryArgs
+  ): Promise<TransactionInstruction> {
+    return await this.program.methods
+      .createDeviceRegistry(args)
+      .accountsPartial({
+        payer,
+        config: this.configPda(),
+        smartWallet,
+        smartWalletData: this.smartWalletDataPda(smartWallet),
+        walletDevice: args.approval
+          ? this.walletDevicePda(smartWallet, args.approval.passkeyPubkey)
+          : null,
+        deviceRegistry: this.deviceRegistryPda(smartWallet),
+        ixSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+        slotHashes: null,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts([
+        // Co-signing devices lead the remaining accounts, in the order of `args.coSigners`
+        ...this.coSignerAccountMetas(smartWallet, args.coSigners),
+        ...walletDevices.map((pubkey) => ({
+          pubkey,
+          isWritable: false,
+          isSigner: false,
+        })),
+      ])
+      .instruction();
+  }
+
+  /**
+   * Builds the claim passkey index instruction for a device registered before unique passkeys
+   * were enforced. `passkeyProof` signs `computePasskeyIndexChallenge`.
+   */
+  async buildClaimPasskeyIndexInstruction(
+    payer: PublicKey,
+    smartWallet: PublicKey,
+    passkeyProof: types.DeviceAssertion
+  ): Promise<TransactionInstruction> {
+    return await this.program.methods
+      .claimPasskeyIndex(passkeyProof)
+      .accountsPartial({
+        payer,
+        config: this.configPda(),
+        smartWallet,
+        smartWalletData: this.smartWalletDataPda(smartWallet),
+        walletDevice: this.walletDevicePda(
+          smartWallet,
+          passkeyProof.passkeyPubkey
+        ),
+        passkeyIndex: this.passkeyIndexPda(passkeyProof.passkeyPubkey),
+        ixSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+        systemProgram: SystemProgram.programId,
+      })
+      .instruction();
+  }
+
   /**
    * Builds the create transaction session instruction
    */
@@ -378,6 +671,7 @@ export class LazorkitClient {
         policyProgram: policyInstruction.programId,
         ixSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
         systemProgram: SystemProgram.programId,
+        slotHashes: null,
       })
       .remainingAccounts([
         ...instructionToAccountMetas(policyInstruction, payer),
@@ -445,12 +739,25 @@ export class LazorkitClient {
       policyInstruction = params.policyInstruction;
     }
 
-    const args = {
-      passkeyPubkey: params.passkeyPubkey,
+    const args: types.CreateSmartWalletArgs = {
+      deviceKind: { passkey: {} },
+      passkeyPubkey: Buffer.from(params.passkeyPubkey),
       credentialId: Buffer.from(params.credentialIdBase64, 'base64'),
       policyData: policyInstruction.data,
       walletId: smartWalletId,
       isPayForUser: params.isPayForUser === true,
+      // Empty/null settings fall back to the program config
+      allowedOriginHashes: [],
+      rpIdHash: null,
+      requireUserVerification: false,
+      backupEligible: params.backupEligible === true,
+      backupState: params.backupState === true,
+      deviceBoundOperations: 0,
+      allowedAaguids: [],
+      attestationAuthenticatorData: null,
+      timestampDriftSeconds: null,
+      // the proof's verify instruction leads the transaction
+      passkeyProof: this.passkeyProofArgs(params.passkeyProof, 0),
     };
 
     const instruction = await this.buildCreateSmartWalletInstruction(
@@ -464,7 +771,7 @@ export class LazorkitClient {
     const transaction = await buildLegacyTransaction(
       this.connection,
       params.payer,
-      [instruction]
+      [...this.passkeyProofInstructions(params.passkeyProof), instruction]
     );
 
     return {
@@ -509,6 +816,8 @@ export class LazorkitClient {
         policyData: policyInstruction.data,
         cpiData: params.cpiInstruction.data,
         splitIndex: policyInstruction.keys.length,
+        intentHash: null,
+        coSigners: [],
       },
       policyInstruction,
       params.cpiInstruction
@@ -543,22 +852,17 @@ export class LazorkitClient {
       params.smartWallet,
       {
         ...signatureArgs,
-        newWalletDevice: params.newWalletDevice
-          ? {
-              passkeyPubkey: Array.from(params.newWalletDevice.passkeyPubkey),
-              credentialId: Buffer.from(
-                params.newWalletDevice.credentialIdBase64,
-                'base64'
-              ),
-            }
-          : null,
+        // the proof's verify instruction follows the auth instruction
+        newWalletDevice: this.newWalletDeviceArgs(params.newWalletDevice, 1),
         policyData: params.policyInstruction.data,
         verifyInstructionIndex: 0,
+        coSigners: [],
       },
       params.policyInstruction
     );
 
     const instructions = combineInstructionsWithAuth(authInstruction, [
+      ...this.passkeyProofInstructions(params.newWalletDevice?.passkeyProof),
       invokeInstruction,
     ]);
     return buildVersionedTransaction(
@@ -593,21 +897,16 @@ export class LazorkitClient {
         splitIndex:
           (params.newWalletDevice ? 1 : 0) +
           params.destroyPolicyInstruction.keys.length,
-        newWalletDevice: params.newWalletDevice
-          ? {
-              passkeyPubkey: Array.from(params.newWalletDevice.passkeyPubkey),
-              credentialId: Buffer.from(
-                params.newWalletDevice.credentialIdBase64,
-                'base64'
-              ),
-            }
-          : null,
+        // the proof's verify instruction follows the auth instruction
+        newWalletDevice: this.newWalletDeviceArgs(params.newWalletDevice, 1),
+        coSigners: [],
       },
       params.destroyPolicyInstruction,
       params.initPolicyInstruction
     );
 
     const instructions = combineInstructionsWithAuth(authInstruction, [
+      ...this.passkeyProofInstructions(params.newWalletDevice?.passkeyProof),
       updateInstruction,
     ]);
     return buildVersionedTransaction(
@@ -698,6 +997,13 @@ export class LazorkitClient {
   }): Promise<Buffer> {
     let message: Buffer;
     const { action, payer, smartWallet, passkeyPubkey } = params;
+    // New deployments only accept domain-separated challenges
+    const config = await this.getConfigData();
+    const domainSeparator = computeDomainSeparator(
+      this.programId,
+      config.deploymentTag,
+      smartWallet
+    );
 
     switch (action.type) {
       case types.SmartWalletAction.ExecuteTransaction: {
@@ -722,12 +1028,13 @@ export class LazorkitClient {
           smartWalletData.lastNonce,
           new BN(Math.floor(Date.now() / 1000)),
           policyInstruction,
-          cpiInstruction
+          cpiInstruction,
+          domainSeparator
         );
         break;
       }
       case types.SmartWalletAction.InvokePolicy: {
-        const { policyInstruction } =
+        const { policyInstruction, newWalletDevice } =
           action.args as types.ArgsByAction[types.SmartWalletAction.InvokePolicy];
 
         const smartWalletData = await this.getSmartWalletData(smartWallet);
@@ -737,12 +1044,14 @@ export class LazorkitClient {
           smartWallet,
           smartWalletData.lastNonce,
           new BN(Math.floor(Date.now() / 1000)),
-          policyInstruction
+          policyInstruction,
+          this.encodeNewWalletDeviceArgs(newWalletDevice),
+          domainSeparator
         );
         break;
       }
       case types.SmartWalletAction.UpdatePolicy: {
-        const { initPolicyIns, destroyPolicyIns } =
+        const { initPolicyIns, destroyPolicyIns, newWalletDevice } =
           action.args as types.ArgsByAction[types.SmartWalletAction.UpdatePolicy];
 
         const smartWalletData = await this.getSmartWalletData(smartWallet);
@@ -753,7 +1062,9 @@ export class LazorkitClient {
           smartWalletData.lastNonce,
           new BN(Math.floor(Date.now() / 1000)),
           destroyPolicyIns,
-          initPolicyIns
+          initPolicyIns,
+          this.encodeNewWalletDeviceArgs(newWalletDevice),
+          domainSeparator
         );
         break;
       }
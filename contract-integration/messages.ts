@@ -1,6 +1,7 @@
 import * as anchor from '@coral-xyz/anchor';
 import { sha256 } from 'js-sha256';
 import { instructionToAccountMetas } from './utils';
+import { compressPasskeyPubkey } from './pda/lazorkit';
 import { Buffer } from 'buffer';
 
 const coder: anchor.BorshCoder = (() => {
@@ -50,11 +51,223 @@ const coder: anchor.BorshCoder = (() => {
           ],
         },
       },
+      {
+        name: 'UpdateWalletSettingsMessage',
+        type: {
+          kind: 'struct',
+          fields: [
+            { name: 'nonce', type: 'u64' },
+            { name: 'currentTimestamp', type: 'i64' },
+            { name: 'settingsHash', type: { array: ['u8', 32] } },
+          ],
+        },
+      },
+      {
+        name: 'CancelIntentMessage',
+        type: {
+          kind: 'struct',
+          fields: [
+            { name: 'nonce', type: 'u64' },
+            { name: 'currentTimestamp', type: 'i64' },
+            { name: 'intentHash', type: { array: ['u8', 32] } },
+            { name: 'validUntil', type: 'i64' },
+          ],
+        },
+      },
+      {
+        name: 'RemoveDeviceMessage',
+        type: {
+          kind: 'struct',
+          fields: [
+            { name: 'nonce', type: 'u64' },
+            { name: 'currentTimestamp', type: 'i64' },
+            { name: 'walletDevice', type: 'pubkey' },
+            { name: 'rentRefund', type: 'pubkey' },
+            { name: 'policyDataHash', type: { array: ['u8', 32] } },
+            { name: 'policyAccountsHash', type: { array: ['u8', 32] } },
+          ],
+        },
+      },
+      {
+        name: 'SyncDevicesMessage',
+        type: {
+          kind: 'struct',
+          fields: [
+            { name: 'nonce', type: 'u64' },
+            { name: 'currentTimestamp', type: 'i64' },
+            { name: 'devicesHash', type: { array: ['u8', 32] } },
+          ],
+        },
+      },
+      {
+        name: 'SignedMessage',
+        type: {
+          kind: 'struct',
+          fields: [{ name: 'messageHash', type: { array: ['u8', 32] } }],
+        },
+      },
+      {
+        name: 'WalletSettings',
+        type: {
+          kind: 'struct',
+          fields: [
+            {
+              name: 'allowedOriginHashes',
+              type: { vec: { array: ['u8', 32] } },
+            },
+            { name: 'requireUserVerification', type: 'bool' },
+            { name: 'deviceBoundOperations', type: 'u8' },
+            { name: 'allowedAaguids', type: { vec: { array: ['u8', 16] } } },
+            { name: 'timestampDriftSeconds', type: { option: 'u32' } },
+            { name: 'threshold', type: 'u8' },
+            { name: 'thresholdOperations', type: 'u8' },
+          ],
+        },
+      },
     ],
   };
   return new anchor.BorshCoder(idl);
 })();
 
+// Challenge envelope:
+// operation tag || format version || [domain separator] || [payer binding] || [freshness]
+//   || [nonce lane] || borsh(message)
+const ENVELOPE_MESSAGE_VERSION = 1;
+const NONCE_LANE_MESSAGE_VERSION = 5;
+const DOMAIN_SEPARATOR_PREFIX = Buffer.from('lazorkit:challenge');
+const PASSKEY_INDEX_CHALLENGE_PREFIX = Buffer.from('lazorkit:passkey_index');
+const MessageOperation = {
+  Execute: 1,
+  InvokePolicy: 2,
+  UpdatePolicy: 3,
+  UpdateWalletSettings: 4,
+  CancelIntent: 5,
+  SignMessage: 6,
+  RemoveDevice: 7,
+  SyncDevices: 8,
+} as const;
+// Borsh tags of the Freshness variants
+const FRESHNESS_TIMESTAMP = 0;
+const FRESHNESS_VALID_UNTIL_SLOT = 1;
+const FRESHNESS_RECENT_SLOT_HASH = 2;
+const FRESHNESS_WINDOW = 3;
+
+/**
+ * Nonce lane of signed intents: the message nonce is a free-form salt and replay protection
+ * comes from the on-chain intent receipt. Intents must use `{ window }` freshness.
+ */
+export const INTENT_NONCE_LANE = new anchor.BN('18446744073709551615');
+
+/**
+ * Freshness replacing the timestamp drift check: an explicit expiry slot, a recent
+ * (slot, hash) pair from the SlotHashes sysvar valid while the entry is present, or a
+ * unix-timestamp validity window for signed intents
+ */
+export type ChallengeFreshness =
+  | { validUntilSlot: anchor.BN }
+  | { recentSlotHash: { slot: anchor.BN; hash: Uint8Array } }
+  | { window: { validAfter: anchor.BN; validUntil: anchor.BN } };
+
+/** Payer binding that lets any fee payer/relayer submit the challenge */
+export const ANY_PAYER = anchor.web3.PublicKey.default;
+
+/**
+ * Domain separator binding a challenge to the program, its deployment and one smart wallet:
+ * sha256("lazorkit:challenge" || programId || deploymentTag (u64 LE) || smartWallet)
+ */
+export function computeDomainSeparator(
+  programId: anchor.web3.PublicKey,
+  deploymentTag: anchor.BN,
+  smartWallet: anchor.web3.PublicKey
+): Uint8Array {
+  const h = sha256.create();
+  h.update(DOMAIN_SEPARATOR_PREFIX);
+  h.update(programId.toBytes());
+  h.update(deploymentTag.toArrayLike(Buffer, 'le', 8));
+  h.update(smartWallet.toBytes());
+  return new Uint8Array(h.arrayBuffer());
+}
+
+/**
+ * Challenge a passkey signs (as the WebAuthn assertion challenge) to claim its passkey index for
+ * a smart wallet while the program enforces unique passkeys:
+ * sha256("lazorkit:passkey_index" || programId || deploymentTag (u64 LE) || smartWallet || passkeyPubkey)
+ */
+export function computePasskeyIndexChallenge(
+  programId: anchor.web3.PublicKey,
+  deploymentTag: anchor.BN,
+  smartWallet: anchor.web3.PublicKey,
+  passkeyPubkey: number[]
+): Uint8Array {
+  const h = sha256.create();
+  h.update(PASSKEY_INDEX_CHALLENGE_PREFIX);
+  h.update(programId.toBytes());
+  h.update(deploymentTag.toArrayLike(Buffer, 'le', 8));
+  h.update(smartWallet.toBytes());
+  h.update(compressPasskeyPubkey(passkeyPubkey));
+  return new Uint8Array(h.arrayBuffer());
+}
+
+function encodeFreshness(freshness?: ChallengeFreshness): Buffer {
+  if (!freshness) {
+    return Buffer.from([FRESHNESS_TIMESTAMP]);
+  }
+  if ('validUntilSlot' in freshness) {
+    return Buffer.concat([
+      Buffer.from([FRESHNESS_VALID_UNTIL_SLOT]),
+      freshness.validUntilSlot.toArrayLike(Buffer, 'le', 8),
+    ]);
+  }
+  if ('recentSlotHash' in freshness) {
+    return Buffer.concat([
+      Buffer.from([FRESHNESS_RECENT_SLOT_HASH]),
+      freshness.recentSlotHash.slot.toArrayLike(Buffer, 'le', 8),
+      Buffer.from(freshness.recentSlotHash.hash),
+    ]);
+  }
+  return Buffer.concat([
+    Buffer.from([FRESHNESS_WINDOW]),
+    freshness.window.validAfter.toTwos(64).toArrayLike(Buffer, 'le', 8),
+    freshness.window.validUntil.toTwos(64).toArrayLike(Buffer, 'le', 8),
+  ]);
+}
+
+/** Intent hash passed as `intentHash` when executing a signed intent: sha256 of the challenge */
+export function computeIntentHash(challenge: Buffer): Uint8Array {
+  return new Uint8Array(sha256.arrayBuffer(challenge));
+}
+
+function withEnvelope(
+  operation: number,
+  encoded: Buffer,
+  domainSeparator?: Uint8Array,
+  payerBinding?: anchor.web3.PublicKey,
+  freshness?: ChallengeFreshness,
+  nonceLane?: anchor.BN
+): Buffer {
+  if (!domainSeparator) {
+    if (payerBinding || freshness || nonceLane) {
+      throw new Error(
+        'payer binding, slot freshness and nonce lanes require a domain separator'
+      );
+    }
+    return Buffer.concat([
+      Buffer.from([operation, ENVELOPE_MESSAGE_VERSION]),
+      encoded,
+    ]);
+  }
+  // Always the current format, which new deployments require: the message nonce is the
+  // sequence number on the lane (0 = the wallet's primary nonce)
+  return Buffer.concat([
+    Buffer.from([operation, NONCE_LANE_MESSAGE_VERSION]),
+    Buffer.from(domainSeparator),
+    (payerBinding ?? ANY_PAYER).toBuffer(),
+    encodeFreshness(freshness),
+    (nonceLane ?? new anchor.BN(0)).toArrayLike(Buffer, 'le', 8),
+    encoded,
+  ]);
+}
+
 function computeAccountsHash(
   programId: anchor.web3.PublicKey,
   metas: anchor.web3.AccountMeta[],
@@ -80,7 +293,11 @@ export function buildExecuteMessage(
   nonce: anchor.BN,
   now: anchor.BN,
   policyIns: anchor.web3.TransactionInstruction,
-  cpiIns: anchor.web3.TransactionInstruction
+  cpiIns: anchor.web3.TransactionInstruction,
+  domainSeparator?: Uint8Array,
+  payerBinding?: anchor.web3.PublicKey,
+  freshness?: ChallengeFreshness,
+  nonceLane?: anchor.BN
 ): Buffer {
   const policyMetas = instructionToAccountMetas(policyIns, payer);
   const policyAccountsHash = computeAccountsHash(
@@ -106,7 +323,33 @@ export function buildExecuteMessage(
     cpiDataHash: Array.from(cpiDataHash),
     cpiAccountsHash: Array.from(cpiAccountsHash),
   });
-  return Buffer.from(encoded);
+  return withEnvelope(
+    MessageOperation.Execute,
+    Buffer.from(encoded),
+    domainSeparator,
+    payerBinding,
+    freshness,
+    nonceLane
+  );
+}
+
+/**
+ * Signed hash of policy data, binding the borsh-encoded `NewWalletDeviceArgs` of a device
+ * enrolled alongside it: sha256(policyData), or
+ * sha256(sha256(policyData) || sha256(newWalletDevice)) when one is enrolled
+ */
+export function computePolicyDataHash(
+  policyData: Buffer,
+  newWalletDevice?: Uint8Array | null
+): Uint8Array {
+  const dataHash = new Uint8Array(sha256.arrayBuffer(policyData));
+  if (!newWalletDevice) {
+    return dataHash;
+  }
+  const deviceHash = new Uint8Array(sha256.arrayBuffer(newWalletDevice));
+  return new Uint8Array(
+    sha256.arrayBuffer(Buffer.concat([dataHash, deviceHash]))
+  );
 }
 
 export function buildInvokePolicyMessage(
@@ -114,7 +357,12 @@ export function buildInvokePolicyMessage(
   smartWallet: anchor.web3.PublicKey,
   nonce: anchor.BN,
   now: anchor.BN,
-  policyIns: anchor.web3.TransactionInstruction
+  policyIns: anchor.web3.TransactionInstruction,
+  newWalletDevice?: Uint8Array | null,
+  domainSeparator?: Uint8Array,
+  payerBinding?: anchor.web3.PublicKey,
+  freshness?: ChallengeFreshness,
+  nonceLane?: anchor.BN
 ): Buffer {
   const policyMetas = instructionToAccountMetas(policyIns, payer);
   const policyAccountsHash = computeAccountsHash(
@@ -122,7 +370,7 @@ export function buildInvokePolicyMessage(
     policyMetas,
     smartWallet
   );
-  const policyDataHash = new Uint8Array(sha256.arrayBuffer(policyIns.data));
+  const policyDataHash = computePolicyDataHash(policyIns.data, newWalletDevice);
 
   const encoded = coder.types.encode('InvokePolicyMessage', {
     nonce,
@@ -130,7 +378,14 @@ export function buildInvokePolicyMessage(
     policyDataHash: Array.from(policyDataHash),
     policyAccountsHash: Array.from(policyAccountsHash),
   });
-  return Buffer.from(encoded);
+  return withEnvelope(
+    MessageOperation.InvokePolicy,
+    Buffer.from(encoded),
+    domainSeparator,
+    payerBinding,
+    freshness,
+    nonceLane
+  );
 }
 
 export function buildUpdatePolicyMessage(
@@ -139,7 +394,12 @@ export function buildUpdatePolicyMessage(
   nonce: anchor.BN,
   now: anchor.BN,
   destroyPolicyIns: anchor.web3.TransactionInstruction,
-  initPolicyIns: anchor.web3.TransactionInstruction
+  initPolicyIns: anchor.web3.TransactionInstruction,
+  newWalletDevice?: Uint8Array | null,
+  domainSeparator?: Uint8Array,
+  payerBinding?: anchor.web3.PublicKey,
+  freshness?: ChallengeFreshness,
+  nonceLane?: anchor.BN
 ): Buffer {
   const oldMetas = instructionToAccountMetas(destroyPolicyIns, payer);
   const oldAccountsHash = computeAccountsHash(
@@ -155,7 +415,10 @@ export function buildUpdatePolicyMessage(
     newMetas,
     smartWallet
   );
-  const newDataHash = new Uint8Array(sha256.arrayBuffer(initPolicyIns.data));
+  const newDataHash = computePolicyDataHash(
+    initPolicyIns.data,
+    newWalletDevice
+  );
 
   const encoded = coder.types.encode('UpdatePolicyMessage', {
     nonce,
@@ -165,5 +428,172 @@ export function buildUpdatePolicyMessage(
     newPolicyDataHash: Array.from(newDataHash),
     newPolicyAccountsHash: Array.from(newAccountsHash),
   });
-  return Buffer.from(encoded);
+  return withEnvelope(
+    MessageOperation.UpdatePolicy,
+    Buffer.from(encoded),
+    domainSeparator,
+    payerBinding,
+    freshness,
+    nonceLane
+  );
+}
+
+export type WalletSettings = {
+  allowedOriginHashes: number[][];
+  requireUserVerification: boolean;
+  deviceBoundOperations: number;
+  allowedAaguids: number[][];
+  timestampDriftSeconds: number | null;
+  threshold: number;
+  thresholdOperations: number;
+};
+
+export function buildUpdateWalletSettingsMessage(
+  nonce: anchor.BN,
+  now: anchor.BN,
+  settings: WalletSettings,
+  domainSeparator?: Uint8Array,
+  payerBinding?: anchor.web3.PublicKey,
+  freshness?: ChallengeFreshness,
+  nonceLane?: anchor.BN
+): Buffer {
+  const settingsHash = new Uint8Array(
+    sha256.arrayBuffer(coder.types.encode('WalletSettings', settings))
+  );
+
+  const encoded = coder.types.encode('UpdateWalletSettingsMessage', {
+    nonce,
+    currentTimestamp: now,
+    settingsHash: Array.from(settingsHash),
+  });
+  return withEnvelope(
+    MessageOperation.UpdateWalletSettings,
+    Buffer.from(encoded),
+    domainSeparator,
+    payerBinding,
+    freshness,
+    nonceLane
+  );
+}
+
+export function buildCancelIntentMessage(
+  nonce: anchor.BN,
+  now: anchor.BN,
+  intentHash: Uint8Array,
+  validUntil: anchor.BN,
+  domainSeparator?: Uint8Array,
+  payerBinding?: anchor.web3.PublicKey,
+  freshness?: ChallengeFreshness,
+  nonceLane?: anchor.BN
+): Buffer {
+  const encoded = coder.types.encode('CancelIntentMessage', {
+    nonce,
+    currentTimestamp: now,
+    intentHash: Array.from(intentHash),
+    validUntil,
+  });
+  return withEnvelope(
+    MessageOperation.CancelIntent,
+    Buffer.from(encoded),
+    domainSeparator,
+    payerBinding,
+    freshness,
+    nonceLane
+  );
+}
+
+export function buildRemoveDeviceMessage(
+  payer: anchor.web3.PublicKey,
+  smartWallet: anchor.web3.PublicKey,
+  nonce: anchor.BN,
+  now: anchor.BN,
+  walletDevice: anchor.web3.PublicKey,
+  rentRefund: anchor.web3.PublicKey,
+  removeDevicePolicyIns: anchor.web3.TransactionInstruction,
+  domainSeparator?: Uint8Array,
+  payerBinding?: anchor.web3.PublicKey,
+  freshness?: ChallengeFreshness,
+  nonceLane?: anchor.BN
+): Buffer {
+  const policyMetas = instructionToAccountMetas(removeDevicePolicyIns, payer);
+  const policyAccountsHash = computeAccountsHash(
+    removeDevicePolicyIns.programId,
+    policyMetas,
+    smartWallet
+  );
+  const policyDataHash = new Uint8Array(
+    sha256.arrayBuffer(removeDevicePolicyIns.data)
+  );
+
+  const encoded = coder.types.encode('RemoveDeviceMessage', {
+    nonce,
+    currentTimestamp: now,
+    walletDevice,
+    rentRefund,
+    policyDataHash: Array.from(policyDataHash),
+    policyAccountsHash: Array.from(policyAccountsHash),
+  });
+  return withEnvelope(
+    MessageOperation.RemoveDevice,
+    Buffer.from(encoded),
+    domainSeparator,
+    payerBinding,
+    freshness,
+    nonceLane
+  );
+}
+
+/**
+ * Challenge for `create_device_registry` on a wallet that does not track its device count yet:
+ * approves `walletDevices` as the wallet's complete device list, in the order they are passed
+ * as remaining accounts. Always sequenced on the primary nonce.
+ */
+export function buildSyncDevicesMessage(
+  nonce: anchor.BN,
+  now: anchor.BN,
+  walletDevices: anchor.web3.PublicKey[],
+  domainSeparator?: Uint8Array,
+  payerBinding?: anchor.web3.PublicKey,
+  freshness?: ChallengeFreshness
+): Buffer {
+  const devicesHash = sha256.create();
+  for (const walletDevice of walletDevices) {
+    devicesHash.update(walletDevice.toBytes());
+  }
+
+  const encoded = coder.types.encode('SyncDevicesMessage', {
+    nonce,
+    currentTimestamp: now,
+    devicesHash: devicesHash.array(),
+  });
+  return withEnvelope(
+    MessageOperation.SyncDevices,
+    Buffer.from(encoded),
+    domainSeparator,
+    payerBinding,
+    freshness
+  );
+}
+
+/**
+ * Challenge for `verify_signature`. Signed messages carry no nonce; pass `freshness` to limit
+ * how long the approval verifies. A message bound to `payerBinding` only verifies when that
+ * key signs the transaction and is passed as the `payer` account.
+ */
+export function buildSignedMessage(
+  messageHash: Uint8Array,
+  domainSeparator?: Uint8Array,
+  freshness?: ChallengeFreshness,
+  payerBinding?: anchor.web3.PublicKey
+): Buffer {
+  const encoded = coder.types.encode('SignedMessage', {
+    messageHash: Array.from(messageHash),
+  });
+  return withEnvelope(
+    MessageOperation.SignMessage,
+    Buffer.from(encoded),
+    domainSeparator,
+    payerBinding,
+    freshness
+  );
 }
@@ -8,6 +8,9 @@ export const SMART_WALLET_SEED = Buffer.from('smart_wallet');
 export const SMART_WALLET_DATA_SEED = Buffer.from('smart_wallet_data');
 export const WALLET_DEVICE_SEED = Buffer.from('wallet_device');
 export const TRANSACTION_SESSION_SEED = Buffer.from('transaction_session');
+export const DEVICE_REGISTRY_SEED = Buffer.from('device_registry');
+export const CREDENTIAL_LOOKUP_SEED = Buffer.from('credential_lookup');
+export const PASSKEY_INDEX_SEED = Buffer.from('passkey_index');
 
 export function deriveConfigPda(programId: PublicKey): PublicKey {
   return PublicKey.findProgramAddressSync([CONFIG_SEED], programId)[0];
@@ -42,6 +45,17 @@ export function deriveSmartWalletDataPda(
   )[0];
 }
 
+/**
+ * Compressed (33-byte) form of a passkey public key, which seeds are derived from. Uncompressed
+ * (65-byte `0x04 || x || y`) keys are cheaper to register on-chain than compressed ones.
+ */
+export function compressPasskeyPubkey(passkey: number[]): number[] {
+  if (passkey.length !== 65) {
+    return passkey;
+  }
+  return [0x02 | (passkey[64] & 1), ...passkey.slice(1, 33)];
+}
+
 // Must match on-chain: sha256(passkey(33) || wallet(32))
 export function hashPasskeyWithWallet(
   passkeyCompressed33: number[],
@@ -49,7 +63,7 @@ export function hashPasskeyWithWallet(
 ): Buffer {
   const { sha256 } = require('js-sha256');
   const buf = Buffer.alloc(65);
-  Buffer.from(passkeyCompressed33).copy(buf, 0);
+  Buffer.from(compressPasskeyPubkey(passkeyCompressed33)).copy(buf, 0);
   wallet.toBuffer().copy(buf, 33);
   return Buffer.from(sha256.arrayBuffer(buf)).subarray(0, 32);
 }
@@ -80,3 +94,44 @@ export function deriveTransactionSessionPda(
     programId
   )[0];
 }
+
+export function deriveDeviceRegistryPda(
+  programId: PublicKey,
+  smartWallet: PublicKey
+): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [DEVICE_REGISTRY_SEED, smartWallet.toBuffer()],
+    programId
+  )[0];
+}
+
+// Must match on-chain: sha256(credential_id)
+export function deriveCredentialLookupPda(
+  programId: PublicKey,
+  credentialId: Buffer
+): PublicKey {
+  const { sha256 } = require('js-sha256');
+  return PublicKey.findProgramAddressSync(
+    [CREDENTIAL_LOOKUP_SEED, Buffer.from(sha256.arrayBuffer(credentialId))],
+    programId
+  )[0];
+}
+
+// Must match on-chain: sha256(passkey(33))
+export function derivePasskeyIndexPda(
+  programId: PublicKey,
+  passkeyCompressed33: number[]
+): PublicKey {
+  const { sha256 } = require('js-sha256');
+  return PublicKey.findProgramAddressSync(
+    [
+      PASSKEY_INDEX_SEED,
+      Buffer.from(
+        sha256.arrayBuffer(
+          Buffer.from(compressPasskeyPubkey(passkeyCompressed33))
+        )
+      ),
+    ],
+    programId
+  )[0];
+}
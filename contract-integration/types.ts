@@ -7,6 +7,9 @@ import { Lazorkit } from './anchor/types/lazorkit';
 export type SmartWallet = anchor.IdlTypes<Lazorkit>['smartWallet'];
 export type WalletDevice = anchor.IdlTypes<Lazorkit>['walletDevice'];
 export type Config = anchor.IdlTypes<Lazorkit>['config'];
+export type DeviceRegistry = anchor.IdlTypes<Lazorkit>['deviceRegistry'];
+export type CredentialLookup = anchor.IdlTypes<Lazorkit>['credentialLookup'];
+export type PasskeyIndex = anchor.IdlTypes<Lazorkit>['passkeyIndex'];
 export type PolicyProgramRegistry =
   anchor.IdlTypes<Lazorkit>['policyProgramRegistry'];
 
@@ -22,6 +25,11 @@ export type InvokePolicyArgs = anchor.IdlTypes<Lazorkit>['invokePolicyArgs'];
 export type CreateSessionArgs = anchor.IdlTypes<Lazorkit>['createSessionArgs'];
 export type NewWalletDeviceArgs =
   anchor.IdlTypes<Lazorkit>['newWalletDeviceArgs'];
+export type DeviceAssertion = anchor.IdlTypes<Lazorkit>['deviceAssertion'];
+export type CreateDeviceRegistryArgs =
+  anchor.IdlTypes<Lazorkit>['createDeviceRegistryArgs'];
+export type RemoveWalletDeviceArgs =
+  anchor.IdlTypes<Lazorkit>['removeWalletDeviceArgs'];
 
 // ============================================================================
 // Configuration Types
@@ -44,18 +52,12 @@ export type ArgsByAction = {
   };
   [SmartWalletAction.InvokePolicy]: {
     policyInstruction: anchor.web3.TransactionInstruction;
-    newWalletDevice: {
-      passkeyPubkey: number[];
-      credentialIdBase64: string;
-    } | null;
+    newWalletDevice: NewPasskeyDevice | null;
   };
   [SmartWalletAction.UpdatePolicy]: {
     destroyPolicyIns: anchor.web3.TransactionInstruction;
     initPolicyIns: anchor.web3.TransactionInstruction;
-    newWalletDevice: {
-      passkeyPubkey: number[];
-      credentialIdBase64: string;
-    } | null;
+    newWalletDevice: NewPasskeyDevice | null;
   };
 };
 
@@ -82,8 +84,13 @@ export interface PasskeySignature {
 }
 
 export interface NewPasskeyDevice {
+  // Compressed (33 bytes) or uncompressed (65 bytes, cheaper to register) public key
   passkeyPubkey: number[];
   credentialIdBase64: string;
+  backupEligible?: boolean;
+  backupState?: boolean;
+  // Assertion over `computePasskeyIndexChallenge`, needed while unique passkeys are enforced
+  passkeyProof?: PasskeySignature | null;
 }
 
 // ============================================================================
@@ -91,11 +98,16 @@ export interface NewPasskeyDevice {
 // ============================================================================
 export interface CreateSmartWalletParams {
   payer: anchor.web3.PublicKey;
+  // Compressed (33 bytes) or uncompressed (65 bytes, cheaper to register) public key
   passkeyPubkey: number[];
   credentialIdBase64: string;
   policyInstruction?: anchor.web3.TransactionInstruction | null;
   isPayForUser?: boolean;
   smartWalletId?: anchor.BN;
+  backupEligible?: boolean;
+  backupState?: boolean;
+  // Assertion over `computePasskeyIndexChallenge`, needed while unique passkeys are enforced
+  passkeyProof?: PasskeySignature | null;
 }
 
 export interface ExecuteTransactionParams {
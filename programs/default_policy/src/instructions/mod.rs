@@ -1,7 +1,9 @@
 mod add_device;
 mod check_policy;
 mod init_policy;
+mod remove_device;
 
 pub use add_device::*;
 pub use check_policy::*;
 pub use init_policy::*;
+pub use remove_device::*;
@@ -0,0 +1,46 @@
+use crate::{error::PolicyError, state::Policy, ID};
+use anchor_lang::prelude::*;
+use lazorkit::{program::Lazorkit, state::WalletDevice};
+
+pub fn remove_device(_ctx: Context<RemoveDevice>) -> Result<()> {
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RemoveDevice<'info> {
+    #[account(
+        owner = lazorkit.key(),
+        signer,
+    )]
+    pub wallet_device: Account<'info, WalletDevice>,
+
+    #[account(
+        owner = lazorkit.key(),
+        constraint = removed_wallet_device.smart_wallet == wallet_device.smart_wallet @ PolicyError::Unauthorized,
+    )]
+    pub removed_wallet_device: Account<'info, WalletDevice>,
+
+    #[account(
+        seeds = [Policy::PREFIX_SEED, wallet_device.key().as_ref()],
+        bump,
+        owner = ID,
+        constraint = policy.wallet_device == wallet_device.key() @ PolicyError::Unauthorized,
+    )]
+    pub policy: Account<'info, Policy>,
+
+    #[account(
+        mut,
+        close = rent_refund,
+        seeds = [Policy::PREFIX_SEED, removed_wallet_device.key().as_ref()],
+        bump,
+        owner = ID,
+        constraint = removed_policy.smart_wallet == policy.smart_wallet @ PolicyError::Unauthorized,
+    )]
+    pub removed_policy: Account<'info, Policy>,
+
+    /// CHECK: receives the rent of the removed device's policy account
+    #[account(mut)]
+    pub rent_refund: UncheckedAccount<'info>,
+
+    pub lazorkit: Program<'info, Lazorkit>,
+}
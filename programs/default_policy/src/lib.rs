@@ -24,4 +24,8 @@ pub mod default_policy {
     pub fn add_device(ctx: Context<AddDevice>) -> Result<()> {
         instructions::add_device(ctx)
     }
+
+    pub fn remove_device(ctx: Context<RemoveDevice>) -> Result<()> {
+        instructions::remove_device(ctx)
+    }
 }
@@ -3,10 +3,41 @@ use anchor_lang::prelude::*;
 /// Program IDs
 pub const SECP256R1_ID: Pubkey = pubkey!("Secp256r1SigVerify1111111111111111111111111");
 
+/// `verify_instruction_index` value asking the program to locate the secp256r1 instruction itself
+pub const AUTO_VERIFY_INSTRUCTION_INDEX: u8 = u8::MAX;
+
 /// Seeds for PDA derivation
 pub const SMART_WALLET_SEED: &[u8] = b"smart_wallet";
 
 /// Size constants for account data
 pub const PASSKEY_SIZE: usize = 33; // Secp256r1 compressed pubkey size
+pub const UNCOMPRESSED_PASSKEY_SIZE: usize = 65; // Secp256r1 uncompressed pubkey size
+pub const ED25519_PUBKEY_SIZE: usize = 32; // Ed25519 pubkey size
+pub const ETH_ADDRESS_SIZE: usize = 20; // Ethereum address size for secp256k1 devices
+
+/// EIP-191 `personal_sign` prefix; followed by the decimal message length and the message
+pub const EIP191_MESSAGE_PREFIX: &[u8] = b"\x19Ethereum Signed Message:\n";
+
+/// Half of the secp256r1 group order (big-endian); canonical signatures have `s <= n / 2`
+pub const SECP256R1_HALF_ORDER: [u8; 32] = [
+    0x7f, 0xff, 0xff, 0xff, 0x80, 0x00, 0x00, 0x00, 0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xde, 0x73, 0x7d, 0x56, 0xd3, 0x8b, 0xcf, 0x42, 0x79, 0xdc, 0xe5, 0x61, 0x7e, 0x31, 0x92, 0xa8,
+];
+
+/// WebAuthn authenticatorData layout: rpIdHash (32) || flags (1) || signCount (4)
+pub const AUTHENTICATOR_DATA_HEADER_SIZE: usize = 37;
+
+/// WebAuthn authenticatorData flag bits
+pub const AUTH_FLAG_USER_PRESENT: u8 = 0x01;
+pub const AUTH_FLAG_USER_VERIFIED: u8 = 0x04;
+pub const AUTH_FLAG_BACKUP_ELIGIBLE: u8 = 0x08;
+pub const AUTH_FLAG_BACKUP_STATE: u8 = 0x10;
+pub const AUTH_FLAG_ATTESTED_CREDENTIAL_DATA: u8 = 0x40;
+
+/// Size of the authenticator model identifier in attested credential data
+pub const AAGUID_SIZE: usize = 16;
+
+/// WebAuthn ceremony type expected in clientDataJSON for assertions
+pub const WEBAUTHN_GET_TYPE: &str = "webauthn.get";
 
 pub const SOL_TRANSFER_DISCRIMINATOR: [u8; 4] = [2, 0, 0, 0];
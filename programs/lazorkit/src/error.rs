@@ -208,4 +208,157 @@ pub enum LazorKitError {
     RefundFailed,
     #[msg("Invalid refund amount")]
     InvalidRefundAmount,
+
+    // Variants below were added after the initial release. Error codes are part of the
+    // program ABI: append new variants at the end and never reorder them.
+
+    // === WebAuthn Client Data & Authenticator Data Errors ===
+    #[msg("Client data type is not webauthn.get")]
+    ClientDataTypeMismatch,
+    #[msg("Origin field missing from client data JSON")]
+    ClientDataOriginMissing,
+    #[msg("Client data origin is not in the allowed origin list")]
+    ClientDataOriginNotAllowed,
+    #[msg("Too many allowed origins provided")]
+    TooManyAllowedOrigins,
+    #[msg("Authenticator data is shorter than the 37-byte header")]
+    AuthenticatorDataTooShort,
+    #[msg("Authenticator data rpIdHash does not match the expected relying party")]
+    RpIdHashMismatch,
+    #[msg("Authenticator data is missing the user presence flag")]
+    UserNotPresent,
+    #[msg("Authenticator data is missing the user verification flag")]
+    UserNotVerified,
+    #[msg("Authenticator signature counter did not increase (possible cloned authenticator)")]
+    SignCountRegression,
+    #[msg("Secp256r1 signature is not low-S; normalize s to n - s before submitting")]
+    SignatureNotLowS,
+    #[msg("Client data JSON contains a duplicate key")]
+    ClientDataDuplicateKey,
+    #[msg("Client data JSON contains a malformed escape sequence")]
+    ClientDataInvalidEscape,
+
+    // === Challenge Envelope Errors ===
+    #[msg("Challenge was signed for a different operation")]
+    MessageOperationMismatch,
+    #[msg("Challenge message version is not supported")]
+    UnsupportedMessageVersion,
+    #[msg("Challenge domain separator does not match this program, deployment and wallet")]
+    DomainSeparatorMismatch,
+
+    // === Device Key Errors ===
+    #[msg("Ed25519 instruction has invalid data")]
+    Ed25519InvalidInstruction,
+    #[msg("Ed25519 signature data validation failed")]
+    Ed25519DataMismatch,
+    #[msg("Ed25519 instruction not found at specified index")]
+    Ed25519InstructionNotFound,
+    #[msg("Ed25519 device key did not sign the transaction")]
+    Ed25519SignerMissing,
+    #[msg("Invalid device public key format")]
+    InvalidDeviceKeyFormat,
+    #[msg("Secp256k1 instruction has invalid data")]
+    Secp256k1InvalidInstruction,
+    #[msg("Secp256k1 signature data validation failed")]
+    Secp256k1DataMismatch,
+    #[msg("Secp256k1 instruction not found at specified index")]
+    Secp256k1InstructionNotFound,
+
+    // === Passkey Credential & Attestation Errors ===
+    #[msg("Authenticator backup eligibility (BE) flag does not match the registered device")]
+    BackupEligibilityMismatch,
+    #[msg("Invalid backup flags: backup state requires a backup-eligible passkey")]
+    InvalidBackupFlags,
+    #[msg("This operation requires a device-bound (non-synced) passkey")]
+    DeviceBoundCredentialRequired,
+    #[msg("Registration authenticator data has no attested credential data")]
+    AttestedCredentialDataMissing,
+    #[msg("Registration authenticator data belongs to a different credential")]
+    AttestationCredentialMismatch,
+    #[msg("Registration authenticator data flags do not match the declared device flags")]
+    AttestationFlagsMismatch,
+    #[msg("Authenticator model (AAGUID) is not allowed for this wallet")]
+    AaguidNotAllowed,
+    #[msg("Too many allowed AAGUIDs provided")]
+    TooManyAllowedAaguids,
+    #[msg("Passkey public key is not a point on the P-256 curve")]
+    PasskeyNotOnCurve,
+
+    // === Challenge Freshness & Replay Errors ===
+    #[msg("Challenge is bound to a different fee payer")]
+    PayerMismatch,
+    #[msg("Challenge expired: its validity window has passed")]
+    ChallengeExpired,
+    #[msg("Challenge valid_until_slot is too far in the future")]
+    ChallengeValidityTooLong,
+    #[msg("Timestamp drift window is out of range")]
+    InvalidTimestampDrift,
+    #[msg("Challenge commits to a slot hash but the SlotHashes sysvar was not provided")]
+    SlotHashesSysvarMissing,
+    #[msg("Challenge slot hash is unknown or has expired from the SlotHashes sysvar")]
+    SlotHashNotFound,
+    #[msg("Challenge is sequenced on a different nonce lane")]
+    NonceLaneMismatch,
+    #[msg("Nonce lane key is reserved")]
+    InvalidNonceLane,
+    #[msg("Signed intent is not valid yet")]
+    IntentNotYetValid,
+    #[msg("Signed intents must carry a validity window")]
+    IntentWindowRequired,
+    #[msg("Signed intent was already executed")]
+    IntentAlreadyExecuted,
+    #[msg("Signed intent was cancelled")]
+    IntentCancelled,
+    #[msg("Intent receipt does not match the signed intent")]
+    IntentReceiptMismatch,
+    #[msg("Intent receipt cannot be closed before the intent expires")]
+    IntentReceiptNotExpired,
+
+    // === Multi-Device Errors ===
+    #[msg("Operation requires approval from the wallet's device threshold")]
+    ThresholdApprovalRequired,
+    #[msg("Not enough device approvals to meet the wallet threshold")]
+    ThresholdNotMet,
+    #[msg("The same device approved more than once")]
+    DuplicateDeviceApproval,
+    #[msg("Device approvals sign different challenges")]
+    ThresholdChallengeMismatch,
+    #[msg("Device approval threshold is out of range")]
+    InvalidThreshold,
+    #[msg("Signed message hash does not match the message being verified")]
+    SignedMessageMismatch,
+    #[msg("Invalid instruction discriminator for remove_device")]
+    InvalidRemoveDeviceDiscriminator,
+    #[msg("The last device of a wallet cannot be removed")]
+    LastWalletDevice,
+    #[msg("A device cannot authorize its own removal")]
+    SelfDeviceRemoval,
+    #[msg("Attested passkey devices must be registered with their credential lookup account")]
+    CredentialLookupMissing,
+    #[msg("Passkey devices must be registered with their passkey index while unique passkeys are enforced")]
+    PasskeyIndexMissing,
+    #[msg("No allowed WebAuthn origins are configured for this wallet or the program")]
+    AllowedOriginsNotConfigured,
+    #[msg("No relying party ID hash is configured for this device or the program")]
+    RpIdHashNotConfigured,
+    #[msg("Client data reports a cross-origin assertion, which is not accepted")]
+    ClientDataCrossOrigin,
+    #[msg("Registration authenticator data has a malformed or non-ES256 credential public key")]
+    InvalidCredentialPublicKey,
+    #[msg("Threshold approval can only be required for operations that accept co-signers")]
+    ThresholdOperationNotSupported,
+    #[msg("The wallet does not track its device count yet; create its device registry first")]
+    DeviceCountNotTracked,
+    #[msg(
+        "Listing the devices of a wallet without a tracked device count needs every listed device"
+    )]
+    DeviceListApprovalRequired,
+    #[msg("Claiming a passkey index needs an assertion by that passkey")]
+    PasskeyIndexProofMissing,
+    #[msg("Passkey assertion does not sign the passkey index challenge")]
+    PasskeyIndexProofMismatch,
+    #[msg("Unique passkeys are not enforced by the config")]
+    UniquePasskeysNotEnforced,
+    #[msg("Deployment tag must be non-zero")]
+    InvalidDeploymentTag,
 }
@@ -1,6 +1,9 @@
 use anchor_lang::prelude::*;
 
-use crate::constants::PASSKEY_SIZE;
+use crate::{
+    constants::PASSKEY_SIZE,
+    state::{DeviceKind, WalletDevice},
+};
 
 /// Event emitted when a new smart wallet is created
 #[event]
@@ -18,6 +21,9 @@ pub struct SmartWalletCreated {
 pub struct TransactionExecuted {
     pub smart_wallet: Pubkey,
     pub authenticator: Pubkey,
+    pub device_kind: DeviceKind,
+    pub backup_eligible: bool,
+    pub backup_state: bool,
     pub nonce: u64,
     pub policy_program: Pubkey,
     pub cpi_program: Pubkey,
@@ -150,6 +156,7 @@ impl TransactionExecuted {
     pub fn emit_event(
         smart_wallet: Pubkey,
         authenticator: Pubkey,
+        device: &WalletDevice,
         nonce: u64,
         policy_program: Pubkey,
         cpi_program: Pubkey,
@@ -158,6 +165,9 @@ impl TransactionExecuted {
         emit!(Self {
             smart_wallet,
             authenticator,
+            device_kind: device.kind,
+            backup_eligible: device.backup_eligible,
+            backup_state: device.backup_state,
             nonce,
             policy_program,
             cpi_program,
@@ -1,5 +1,11 @@
 mod register_policy_program;
+mod update_allowed_aaguids;
+mod update_allowed_origins;
 mod update_config;
+mod update_rp_id_hash;
 
 pub use register_policy_program::*;
+pub use update_allowed_aaguids::*;
+pub use update_allowed_origins::*;
 pub use update_config::*;
+pub use update_rp_id_hash::*;
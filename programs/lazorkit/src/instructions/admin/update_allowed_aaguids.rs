@@ -0,0 +1,18 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::AAGUID_SIZE, instructions::UpdateConfig, security::validation};
+
+pub fn update_allowed_aaguids(
+    ctx: Context<UpdateConfig>,
+    aaguids: Vec<[u8; AAGUID_SIZE]>,
+) -> Result<()> {
+    validation::validate_allowed_aaguids(&aaguids)?;
+
+    let config = &mut ctx.accounts.config;
+    config.allowed_aaguids = aaguids;
+    msg!(
+        "Updated allowed AAGUIDs: {} entries",
+        config.allowed_aaguids.len()
+    );
+    Ok(())
+}
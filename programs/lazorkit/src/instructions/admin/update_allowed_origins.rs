@@ -0,0 +1,18 @@
+use anchor_lang::prelude::*;
+
+use crate::{instructions::UpdateConfig, security::validation};
+
+pub fn update_allowed_origins(
+    ctx: Context<UpdateConfig>,
+    origin_hashes: Vec<[u8; 32]>,
+) -> Result<()> {
+    validation::validate_allowed_origins(&origin_hashes)?;
+
+    let config = &mut ctx.accounts.config;
+    config.allowed_origin_hashes = origin_hashes;
+    msg!(
+        "Updated allowed origins: {} entries",
+        config.allowed_origin_hashes.len()
+    );
+    Ok(())
+}
@@ -2,7 +2,8 @@ use anchor_lang::prelude::*;
 
 use crate::{
     error::LazorKitError,
-    state::{Config, UpdateConfigType},
+    security::MAX_WALLET_DEVICES,
+    state::{Config, UpdateConfigType, MESSAGE_VERSION},
 };
 
 pub fn update_config(
@@ -49,7 +50,7 @@ pub fn update_config(
                 .ok_or(LazorKitError::InvalidRemainingAccounts)?;
 
             // Cannot set admin to system program or this program
-        require!(
+            require!(
                 new_admin_info.key() != anchor_lang::system_program::ID
                     && new_admin_info.key() != crate::ID,
                 LazorKitError::InvalidAuthority
@@ -58,6 +59,38 @@ pub fn update_config(
             config.authority = new_admin_info.key();
             msg!("Updated authority to: {}", new_admin_info.key());
         }
+        UpdateConfigType::RequireUserVerification => {
+            require!(value <= 1, LazorKitError::InvalidInstructionData);
+            config.require_user_verification = value == 1;
+            msg!("Updated require_user_verification to: {}", value == 1);
+        }
+        UpdateConfigType::MinMessageVersion => {
+            require!(
+                value <= MESSAGE_VERSION as u64,
+                LazorKitError::UnsupportedMessageVersion
+            );
+            config.min_message_version = value as u8;
+            msg!("Updated min_message_version to: {}", value);
+        }
+        UpdateConfigType::DeploymentTag => {
+            require!(value != 0, LazorKitError::InvalidDeploymentTag);
+            config.deployment_tag = value;
+            msg!("Updated deployment_tag to: {}", value);
+        }
+        UpdateConfigType::MaxWalletDevices => {
+            // 0 restores the program maximum
+            require!(
+                value <= MAX_WALLET_DEVICES as u64,
+                LazorKitError::InvalidWalletConfiguration
+            );
+            config.max_wallet_devices = value as u8;
+            msg!("Updated max_wallet_devices to: {}", value);
+        }
+        UpdateConfigType::UniquePasskeys => {
+            require!(value <= 1, LazorKitError::InvalidInstructionData);
+            config.unique_passkeys = value == 1;
+            msg!("Updated unique_passkeys to: {}", value == 1);
+        }
         UpdateConfigType::PauseProgram => {
             require!(!config.is_paused, LazorKitError::ProgramPaused);
             config.is_paused = true;
@@ -0,0 +1,10 @@
+use anchor_lang::prelude::*;
+
+use crate::instructions::UpdateConfig;
+
+pub fn update_rp_id_hash(ctx: Context<UpdateConfig>, rp_id_hash: Option<[u8; 32]>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.rp_id_hash = rp_id_hash;
+    msg!("Updated rp_id_hash: {:?}", config.rp_id_hash);
+    Ok(())
+}
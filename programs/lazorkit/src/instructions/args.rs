@@ -1,4 +1,9 @@
-use crate::{constants::PASSKEY_SIZE, error::LazorKitError};
+use crate::{
+    constants::{AAGUID_SIZE, PASSKEY_SIZE, UNCOMPRESSED_PASSKEY_SIZE},
+    error::LazorKitError,
+    security::{MAX_ATTESTATION_DATA_SIZE, MAX_CLIENT_DATA_JSON_SIZE},
+    state::DeviceKind,
+};
 use anchor_lang::prelude::*;
 
 pub trait Args {
@@ -7,11 +12,34 @@ pub trait Args {
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct CreateSmartWalletArgs {
-    pub passkey_pubkey: [u8; PASSKEY_SIZE],
+    pub device_kind: DeviceKind,
+    pub passkey_pubkey: Vec<u8>, // Compressed or uncompressed passkey, or natural-length key
     pub credential_id: Vec<u8>,
     pub policy_data: Vec<u8>,
     pub wallet_id: u64, // Random ID provided by client,
     pub is_pay_for_user: bool,
+    pub allowed_origin_hashes: Vec<[u8; 32]>, // Empty to use the config default
+    pub rp_id_hash: Option<[u8; 32]>,         // None to use the config default
+    pub require_user_verification: bool,
+    pub backup_eligible: bool,
+    pub backup_state: bool,
+    pub device_bound_operations: u8, // MessageOperation::mask bits
+    pub allowed_aaguids: Vec<[u8; AAGUID_SIZE]>, // Empty to use the config default
+    pub attestation_authenticator_data: Option<Vec<u8>>, // Registration authenticatorData
+    pub timestamp_drift_seconds: Option<u32>, // None to use the program default
+    pub passkey_proof: Option<DeviceAssertion>, // Passkey index claim, while unique passkeys are enforced
+}
+
+impl CreateSmartWalletArgs {
+    /// Last PDA seed of the wallet_device being registered. Malformed keys map to a dummy seed
+    /// here and are rejected by the handler.
+    pub fn device_seed_hash(&self, smart_wallet: Pubkey) -> [u8; 32] {
+        let key = self
+            .device_kind
+            .normalize_key(&self.passkey_pubkey)
+            .unwrap_or([0; PASSKEY_SIZE]);
+        self.device_kind.seed_hash(&key, smart_wallet)
+    }
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
@@ -24,6 +52,8 @@ pub struct ExecuteTransactionArgs {
     pub split_index: u16,
     pub policy_data: Vec<u8>,
     pub cpi_data: Vec<u8>,
+    pub intent_hash: Option<[u8; 32]>, // sha256 of the challenge when executing a signed intent
+    pub co_signers: Vec<DeviceAssertion>, // Further device approvals for threshold wallets
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
@@ -37,6 +67,7 @@ pub struct UpdatePolicyArgs {
     pub destroy_policy_data: Vec<u8>,
     pub init_policy_data: Vec<u8>,
     pub new_wallet_device: Option<NewWalletDeviceArgs>,
+    pub co_signers: Vec<DeviceAssertion>, // Further device approvals for threshold wallets
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
@@ -48,6 +79,7 @@ pub struct InvokePolicyArgs {
     pub verify_instruction_index: u8,
     pub policy_data: Vec<u8>,
     pub new_wallet_device: Option<NewWalletDeviceArgs>,
+    pub co_signers: Vec<DeviceAssertion>, // Further device approvals for threshold wallets
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
@@ -61,41 +93,113 @@ pub struct CreateSessionArgs {
     pub expires_at: i64,
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct UpdateWalletSettingsArgs {
+    pub passkey_pubkey: [u8; PASSKEY_SIZE],
+    pub signature: Vec<u8>,
+    pub client_data_json_raw: Vec<u8>,
+    pub authenticator_data_raw: Vec<u8>,
+    pub verify_instruction_index: u8,
+    pub settings: WalletSettings,
+    pub co_signers: Vec<DeviceAssertion>, // Further device approvals for threshold wallets
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CancelIntentArgs {
+    pub passkey_pubkey: [u8; PASSKEY_SIZE],
+    pub signature: Vec<u8>,
+    pub client_data_json_raw: Vec<u8>,
+    pub authenticator_data_raw: Vec<u8>,
+    pub verify_instruction_index: u8,
+    pub intent_hash: [u8; 32],
+    pub valid_until: i64, // valid_until of the cancelled intent
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct RemoveWalletDeviceArgs {
+    pub passkey_pubkey: [u8; PASSKEY_SIZE],
+    pub signature: Vec<u8>,
+    pub client_data_json_raw: Vec<u8>,
+    pub authenticator_data_raw: Vec<u8>,
+    pub verify_instruction_index: u8,
+    pub policy_data: Vec<u8>, // remove_device call on the wallet's policy program
+    pub co_signers: Vec<DeviceAssertion>, // Further device approvals for threshold wallets
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct VerifySignatureArgs {
+    pub passkey_pubkey: [u8; PASSKEY_SIZE],
+    pub signature: Vec<u8>,
+    pub client_data_json_raw: Vec<u8>,
+    pub authenticator_data_raw: Vec<u8>,
+    pub verify_instruction_index: u8,
+    pub message_hash: [u8; 32], // App-defined hash of the message the wallet approved
+    pub co_signers: Vec<DeviceAssertion>, // Further device approvals for threshold wallets
+}
+
+/// Per-wallet security settings, replaced as a whole by `update_wallet_settings`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct WalletSettings {
+    pub allowed_origin_hashes: Vec<[u8; 32]>, // Empty to use the config default
+    pub require_user_verification: bool,
+    pub device_bound_operations: u8, // MessageOperation::mask bits
+    pub allowed_aaguids: Vec<[u8; AAGUID_SIZE]>, // Empty to use the config default
+    pub timestamp_drift_seconds: Option<u32>, // None to use the program default
+    pub threshold: u8,               // Distinct devices needed for threshold_operations
+    pub threshold_operations: u8,    // MessageOperation::mask bits
+}
+
+/// Signed assertion from one device in a threshold authorization
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
-pub struct NewWalletDeviceArgs {
+pub struct DeviceAssertion {
     pub passkey_pubkey: [u8; PASSKEY_SIZE],
+    #[max_len(65)]
+    pub signature: Vec<u8>,
+    #[max_len(MAX_CLIENT_DATA_JSON_SIZE)]
+    pub client_data_json_raw: Vec<u8>,
+    #[max_len(MAX_ATTESTATION_DATA_SIZE)]
+    pub authenticator_data_raw: Vec<u8>,
+    pub verify_instruction_index: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CreateDeviceRegistryArgs {
+    pub approval: Option<DeviceAssertion>, // Required while the wallet does not track its device count
+    pub co_signers: Vec<DeviceAssertion>,  // Further device approvals for threshold wallets
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct NewWalletDeviceArgs {
+    pub device_kind: DeviceKind,
+    #[max_len(UNCOMPRESSED_PASSKEY_SIZE)]
+    pub passkey_pubkey: Vec<u8>,
     #[max_len(256)]
     pub credential_id: Vec<u8>,
+    pub rp_id_hash: Option<[u8; 32]>,
+    pub backup_eligible: bool,
+    pub backup_state: bool,
+    #[max_len(MAX_ATTESTATION_DATA_SIZE)]
+    pub attestation_authenticator_data: Option<Vec<u8>>,
+    pub passkey_proof: Option<DeviceAssertion>, // Passkey index claim, while unique passkeys are enforced
 }
 
 macro_rules! impl_args_validate {
     ($t:ty) => {
         impl Args for $t {
             fn validate(&self) -> Result<()> {
-                // Validate passkey format
+                // Signatures are 64 bytes for passkeys and ed25519 devices and 65 bytes for
+                // secp256k1 devices; ed25519 devices that sign the transaction itself send none.
+                // Key format and the kind-specific checks are enforced against the stored device.
                 require!(
-                    self.passkey_pubkey[0] == 0x02 || self.passkey_pubkey[0] == 0x03,
-                    LazorKitError::InvalidPasskeyFormat
+                    matches!(self.signature.len(), 0 | 64 | 65),
+                    LazorKitError::InvalidSignature
                 );
 
-                // Validate signature length (Secp256r1 signature should be 64 bytes)
-                require!(self.signature.len() == 64, LazorKitError::InvalidSignature);
-
-                // Validate client data and authenticator data are not empty
+                // Validate the signed payload is not empty
                 require!(
                     !self.client_data_json_raw.is_empty(),
                     LazorKitError::InvalidInstructionData
                 );
-                require!(
-                    !self.authenticator_data_raw.is_empty(),
-                    LazorKitError::InvalidInstructionData
-                );
-
-                // Validate verify instruction index
-                require!(
-                    self.verify_instruction_index < 255,
-                    LazorKitError::InvalidInstructionData
-                );
 
                 Ok(())
             }
@@ -105,24 +209,15 @@ macro_rules! impl_args_validate {
 
 impl Args for CreateSessionArgs {
     fn validate(&self) -> Result<()> {
-        // Common passkey/signature/client/auth checks
+        // Common signature/payload checks; kind-specific checks run against the stored device
         require!(
-            self.passkey_pubkey[0] == 0x02 || self.passkey_pubkey[0] == 0x03,
-            LazorKitError::InvalidPasskeyFormat
+            matches!(self.signature.len(), 0 | 64 | 65),
+            LazorKitError::InvalidSignature
         );
-        require!(self.signature.len() == 64, LazorKitError::InvalidSignature);
         require!(
             !self.client_data_json_raw.is_empty(),
             LazorKitError::InvalidInstructionData
         );
-        require!(
-            !self.authenticator_data_raw.is_empty(),
-            LazorKitError::InvalidInstructionData
-        );
-        require!(
-            self.verify_instruction_index < 255,
-            LazorKitError::InvalidInstructionData
-        );
         // Split index bounds check left to runtime with account len; ensure policy_data present
         require!(
             !self.policy_data.is_empty(),
@@ -142,3 +237,32 @@ impl Args for CreateSessionArgs {
 impl_args_validate!(ExecuteTransactionArgs);
 impl_args_validate!(UpdatePolicyArgs);
 impl_args_validate!(InvokePolicyArgs);
+impl_args_validate!(UpdateWalletSettingsArgs);
+impl_args_validate!(CancelIntentArgs);
+impl_args_validate!(VerifySignatureArgs);
+impl_args_validate!(RemoveWalletDeviceArgs);
+impl_args_validate!(DeviceAssertion);
+
+macro_rules! impl_primary_assertion {
+    ($t:ty) => {
+        impl $t {
+            /// Assertion made by the instruction's primary `wallet_device`
+            pub fn primary_assertion(&self) -> DeviceAssertion {
+                DeviceAssertion {
+                    passkey_pubkey: self.passkey_pubkey,
+                    signature: self.signature.clone(),
+                    client_data_json_raw: self.client_data_json_raw.clone(),
+                    authenticator_data_raw: self.authenticator_data_raw.clone(),
+                    verify_instruction_index: self.verify_instruction_index,
+                }
+            }
+        }
+    };
+}
+
+impl_primary_assertion!(ExecuteTransactionArgs);
+impl_primary_assertion!(InvokePolicyArgs);
+impl_primary_assertion!(UpdatePolicyArgs);
+impl_primary_assertion!(UpdateWalletSettingsArgs);
+impl_primary_assertion!(RemoveWalletDeviceArgs);
+impl_primary_assertion!(VerifySignatureArgs);
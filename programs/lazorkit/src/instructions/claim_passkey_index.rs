@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::SMART_WALLET_SEED,
+    error::LazorKitError,
+    instructions::DeviceAssertion,
+    state::{Config, PasskeyIndex, SmartWallet, WalletDevice},
+    ID,
+};
+
+/// Claim the passkey index of a device registered before unique passkeys were enforced.
+///
+/// The passkey must sign the index challenge, so only its holder can claim it; anyone may pay
+/// for the index.
+pub fn claim_passkey_index(
+    ctx: Context<ClaimPasskeyIndex>,
+    passkey_proof: DeviceAssertion,
+) -> Result<()> {
+    require!(!ctx.accounts.config.is_paused, LazorKitError::ProgramPaused);
+    require!(
+        ctx.accounts.config.unique_passkeys,
+        LazorKitError::UniquePasskeysNotEnforced
+    );
+
+    PasskeyIndex::claim(
+        Some(&ctx.accounts.passkey_index),
+        ctx.accounts.payer.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.ix_sysvar,
+        &ctx.accounts.config,
+        &ctx.accounts.smart_wallet_data,
+        &ctx.accounts.wallet_device,
+        ctx.accounts.wallet_device.key(),
+        Some(&passkey_proof),
+    )?;
+
+    msg!(
+        "Passkey index claimed for device: {}",
+        ctx.accounts.wallet_device.key()
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimPasskeyIndex<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(seeds = [Config::PREFIX_SEED], bump, owner = ID)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        seeds = [SMART_WALLET_SEED, smart_wallet_data.id.to_le_bytes().as_ref()],
+        bump = smart_wallet_data.bump,
+        owner = ID,
+    )]
+    /// CHECK: PDA verified by seeds
+    pub smart_wallet: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [SmartWallet::PREFIX_SEED, smart_wallet.key().as_ref()],
+        bump,
+        owner = ID,
+    )]
+    pub smart_wallet_data: Box<Account<'info, SmartWallet>>,
+
+    /// Registered passkey device claiming its index
+    #[account(
+        owner = ID,
+        has_one = smart_wallet @ LazorKitError::SmartWalletMismatch,
+    )]
+    pub wallet_device: Box<Account<'info, WalletDevice>>,
+
+    /// CHECK: passkey index PDA of the device, created by the claim
+    #[account(
+        mut,
+        seeds = [
+            PasskeyIndex::PREFIX_SEED,
+            PasskeyIndex::seed_hash(&wallet_device.passkey_pubkey).as_ref()
+        ],
+        bump,
+    )]
+    pub passkey_index: UncheckedAccount<'info>,
+
+    /// CHECK: instruction sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub ix_sysvar: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,23 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::LazorKitError, state::IntentReceipt, ID};
+
+/// Close an intent receipt once its intent can no longer execute, refunding the rent to
+/// whoever paid for it. Permissionless: the receipt only has to outlive the intent window.
+pub fn close_intent_receipt(ctx: Context<CloseIntentReceipt>) -> Result<()> {
+    require!(
+        Clock::get()?.unix_timestamp > ctx.accounts.intent_receipt.expires_at,
+        LazorKitError::IntentReceiptNotExpired
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseIntentReceipt<'info> {
+    #[account(mut, close = rent_refund, owner = ID)]
+    pub intent_receipt: Account<'info, IntentReceipt>,
+
+    /// CHECK: rent refund destination (stored in receipt)
+    #[account(mut, address = intent_receipt.rent_refund_to)]
+    pub rent_refund: UncheckedAccount<'info>,
+}
@@ -0,0 +1,176 @@
+use anchor_lang::{prelude::*, solana_program::hash::Hasher};
+
+use crate::{
+    constants::SMART_WALLET_SEED,
+    error::LazorKitError,
+    instructions::{Args as _, CreateDeviceRegistryArgs},
+    security::MAX_WALLET_DEVICES,
+    state::{Config, DeviceEntry, DeviceRegistry, SmartWallet, SyncDevicesMessage, WalletDevice},
+    utils::{advance_nonce, verify_co_signed_authorization, ReplayGuard},
+    ID,
+};
+
+/// Create the device registry of a wallet created before registries existed.
+///
+/// Every wallet_device of the wallet is passed as a remaining account. Wallets that track their
+/// device count are checked for completeness against it, and anyone may pay for the registry.
+/// Wallets migrated without a device count cannot be checked on chain, so every listed device
+/// must approve the exact list (one as `wallet_device`, the others as co-signers leading the
+/// remaining accounts), which then becomes the wallet's device count. A device left out of the
+/// list is not counted but can still be removed with `remove_wallet_device`.
+pub fn create_device_registry<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, CreateDeviceRegistry<'info>>,
+    args: CreateDeviceRegistryArgs,
+) -> Result<()> {
+    require!(!ctx.accounts.config.is_paused, LazorKitError::ProgramPaused);
+    let tracked_count = usize::from(ctx.accounts.smart_wallet_data.device_count);
+    require!(
+        args.co_signers.len() <= ctx.remaining_accounts.len(),
+        LazorKitError::InvalidRemainingAccounts
+    );
+    let (co_signer_accounts, device_accounts) =
+        ctx.remaining_accounts.split_at(args.co_signers.len());
+    require!(
+        !device_accounts.is_empty() && device_accounts.len() <= MAX_WALLET_DEVICES,
+        LazorKitError::InvalidRemainingAccounts
+    );
+
+    if tracked_count > 0 {
+        require!(
+            device_accounts.len() == tracked_count,
+            LazorKitError::InvalidRemainingAccounts
+        );
+    } else {
+        // The listed devices become the wallet's device count, so a device must approve the list
+        let approval = args
+            .approval
+            .ok_or(LazorKitError::DeviceListApprovalRequired)?;
+        approval.validate()?;
+        let wallet_device = ctx
+            .accounts
+            .wallet_device
+            .as_mut()
+            .ok_or(LazorKitError::DeviceListApprovalRequired)?;
+        let approver = wallet_device.key();
+        let msg: SyncDevicesMessage = verify_co_signed_authorization(
+            &ctx.accounts.ix_sysvar,
+            &mut *wallet_device,
+            approval,
+            &args.co_signers,
+            co_signer_accounts,
+            ctx.accounts.smart_wallet.key(),
+            &ctx.accounts.smart_wallet_data,
+            &ctx.accounts.config,
+            ctx.accounts.payer.key(),
+            ReplayGuard::Primary,
+            ctx.accounts.slot_hashes.as_deref(),
+        )?
+        .message;
+        ctx.accounts
+            .smart_wallet_data
+            .check_device_add_approvals(1 + args.co_signers.len())?;
+        // Every listed device must co-sign, so the list holds only devices that agree to it
+        require!(
+            1 + args.co_signers.len() == device_accounts.len(),
+            LazorKitError::DeviceListApprovalRequired
+        );
+
+        let mut hasher = Hasher::default();
+        for info in device_accounts {
+            hasher.hash(info.key.as_ref());
+        }
+        require!(
+            hasher.result().to_bytes() == msg.devices_hash,
+            LazorKitError::InvalidAccountData
+        );
+        // Approving devices are distinct, so together they must be exactly the listed devices
+        require!(
+            core::iter::once(&approver)
+                .chain(co_signer_accounts.iter().map(|info| info.key))
+                .all(|key| device_accounts.iter().any(|info| info.key == key)),
+            LazorKitError::InvalidRemainingAccounts
+        );
+
+        let wallet_data = &mut ctx.accounts.smart_wallet_data;
+        wallet_data.device_count = device_accounts.len() as u16;
+        advance_nonce(wallet_data, None)?;
+    }
+
+    let smart_wallet = ctx.accounts.smart_wallet.key();
+    let mut devices: Vec<DeviceEntry> = Vec::with_capacity(device_accounts.len());
+    for info in device_accounts {
+        let device: Account<WalletDevice> = Account::try_from(info)?;
+        require!(
+            device.smart_wallet == smart_wallet,
+            LazorKitError::SmartWalletMismatch
+        );
+        require!(
+            devices
+                .iter()
+                .all(|entry| entry.wallet_device != info.key()),
+            LazorKitError::InvalidRemainingAccounts
+        );
+        devices.push(DeviceEntry::new(
+            info.key(),
+            device.kind,
+            &device.credential_id,
+        ));
+    }
+
+    ctx.accounts.device_registry.set_inner(DeviceRegistry {
+        smart_wallet,
+        bump: ctx.bumps.device_registry,
+        devices,
+    });
+
+    msg!("Device registry created for wallet: {}", smart_wallet);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateDeviceRegistry<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(seeds = [Config::PREFIX_SEED], bump, owner = ID)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        seeds = [SMART_WALLET_SEED, smart_wallet_data.id.to_le_bytes().as_ref()],
+        bump = smart_wallet_data.bump,
+        owner = ID,
+    )]
+    /// CHECK: PDA verified by seeds
+    pub smart_wallet: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [SmartWallet::PREFIX_SEED, smart_wallet.key().as_ref()],
+        bump,
+        owner = ID,
+    )]
+    pub smart_wallet_data: Box<Account<'info, SmartWallet>>,
+
+    /// Device approving the device list of a wallet without a tracked device count
+    #[account(mut, owner = ID)]
+    pub wallet_device: Option<Account<'info, WalletDevice>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + DeviceRegistry::INIT_SPACE,
+        seeds = [DeviceRegistry::PREFIX_SEED, smart_wallet.key().as_ref()],
+        bump
+    )]
+    pub device_registry: Box<Account<'info, DeviceRegistry>>,
+
+    /// CHECK: instruction sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub ix_sysvar: UncheckedAccount<'info>,
+
+    /// CHECK: SlotHashes sysvar, only needed for challenges bound to a recent slot hash
+    #[account(address = anchor_lang::solana_program::sysvar::slot_hashes::ID)]
+    pub slot_hashes: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
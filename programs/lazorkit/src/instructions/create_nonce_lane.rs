@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::SMART_WALLET_SEED,
+    error::LazorKitError,
+    state::{Config, NonceLane, SmartWallet, INTENT_NONCE_LANE, PRIMARY_NONCE_LANE},
+    ID,
+};
+
+/// Open an additional nonce lane for a smart wallet.
+///
+/// Lanes only ever count up and are never closed, so a sequence number cannot be reused.
+/// Anyone may pay to open a lane: it authorizes nothing until a device signs for it.
+pub fn create_nonce_lane(ctx: Context<CreateNonceLane>, lane: u64) -> Result<()> {
+    require!(!ctx.accounts.config.is_paused, LazorKitError::ProgramPaused);
+    require!(
+        lane != PRIMARY_NONCE_LANE && lane != INTENT_NONCE_LANE,
+        LazorKitError::InvalidNonceLane
+    );
+
+    let nonce_lane = &mut ctx.accounts.nonce_lane;
+    nonce_lane.smart_wallet = ctx.accounts.smart_wallet.key();
+    nonce_lane.lane = lane;
+    nonce_lane.sequence = 0;
+    nonce_lane.bump = ctx.bumps.nonce_lane;
+
+    msg!(
+        "Nonce lane {} created for wallet: {}",
+        lane,
+        ctx.accounts.smart_wallet.key()
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(lane: u64)]
+pub struct CreateNonceLane<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(seeds = [Config::PREFIX_SEED], bump, owner = ID)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        seeds = [SMART_WALLET_SEED, smart_wallet_data.id.to_le_bytes().as_ref()],
+        bump = smart_wallet_data.bump,
+        owner = ID,
+    )]
+    /// CHECK: PDA verified by seeds
+    pub smart_wallet: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [SmartWallet::PREFIX_SEED, smart_wallet.key().as_ref()],
+        bump,
+        owner = ID,
+    )]
+    pub smart_wallet_data: Box<Account<'info, SmartWallet>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + NonceLane::INIT_SPACE,
+        seeds = [NonceLane::PREFIX_SEED, smart_wallet.key().as_ref(), lane.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub nonce_lane: Account<'info, NonceLane>,
+
+    pub system_program: Program<'info, System>,
+}
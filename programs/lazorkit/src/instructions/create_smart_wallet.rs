@@ -6,8 +6,11 @@ use crate::{
     events::{FeeCollected, SmartWalletCreated},
     instructions::CreateSmartWalletArgs,
     security::validation,
-    state::{Config, PolicyProgramRegistry, SmartWallet, WalletDevice},
-    utils::{execute_cpi, transfer_sol_from_pda, PasskeyExt, PdaSigner},
+    state::{
+        Config, CredentialLookup, DeviceEntry, DeviceKind, DeviceRegistry, PasskeyIndex,
+        PolicyProgramRegistry, SmartWallet, WalletDevice,
+    },
+    utils::{execute_cpi, transfer_sol_from_pda, verify_device_registration, PdaSigner},
     ID,
 };
 
@@ -18,15 +21,18 @@ pub fn create_smart_wallet(
     // Program must not be paused
     require!(!ctx.accounts.config.is_paused, LazorKitError::ProgramPaused);
     // === Input Validation ===
-    validation::validate_credential_id(&args.credential_id)?;
+    if args.device_kind == DeviceKind::Passkey {
+        validation::validate_credential_id(&args.credential_id)?;
+    }
     validation::validate_policy_data(&args.policy_data)?;
-    validation::validate_remaining_accounts(&ctx.remaining_accounts)?;
+    validation::validate_allowed_origins(&args.allowed_origin_hashes)?;
+    validation::validate_allowed_aaguids(&args.allowed_aaguids)?;
+    validation::validate_timestamp_drift(args.timestamp_drift_seconds)?;
+    validation::validate_remaining_accounts(ctx.remaining_accounts)?;
 
-    // Validate passkey format (ensure it's a valid compressed public key)
-    require!(
-        args.passkey_pubkey[0] == 0x02 || args.passkey_pubkey[0] == 0x03,
-        LazorKitError::InvalidPasskeyFormat
-    );
+    // Validate device key format and backup flags for the device kind
+    let passkey_pubkey = args.device_kind.canonical_key(&args.passkey_pubkey)?;
+    WalletDevice::validate_backup_flags(args.device_kind, args.backup_eligible, args.backup_state)?;
 
     // Validate wallet ID is not zero (reserved) and not too large
     require!(
@@ -47,23 +53,84 @@ pub fn create_smart_wallet(
         id: args.wallet_id,
         last_nonce: 0,
         bump: ctx.bumps.smart_wallet,
+        allowed_origin_hashes: args.allowed_origin_hashes.clone(),
+        require_user_verification: args.require_user_verification,
+        device_bound_operations: args.device_bound_operations,
+        allowed_aaguids: args.allowed_aaguids.clone(),
+        timestamp_drift_seconds: args.timestamp_drift_seconds,
+        threshold: 1,
+        threshold_operations: 0,
+        device_count: 1,
     });
 
+    // Only approved authenticator models may be enrolled
+    let aaguid = verify_device_registration(
+        wallet_data.effective_aaguids(&ctx.accounts.config),
+        args.rp_id_hash.or(ctx.accounts.config.rp_id_hash),
+        args.device_kind,
+        &passkey_pubkey,
+        &args.credential_id,
+        args.backup_eligible,
+        args.backup_state,
+        args.attestation_authenticator_data.as_deref(),
+    )?;
+
     // === Initialize Wallet Device ===
     wallet_device.set_inner(WalletDevice {
-        passkey_pubkey: args.passkey_pubkey,
+        passkey_pubkey,
         smart_wallet: ctx.accounts.smart_wallet.key(),
         credential_id: args.credential_id.clone(),
         bump: ctx.bumps.wallet_device,
+        kind: args.device_kind,
+        rp_id_hash: args.rp_id_hash,
+        sign_count: 0,
+        backup_eligible: args.backup_eligible,
+        backup_state: args.backup_state,
+        aaguid,
+        backup_eligibility_recorded: true,
     });
 
+    // === Initialize Device Registry ===
+    ctx.accounts.device_registry.set_inner(DeviceRegistry {
+        smart_wallet: ctx.accounts.smart_wallet.key(),
+        bump: ctx.bumps.device_registry,
+        devices: vec![DeviceEntry::new(
+            ctx.accounts.wallet_device.key(),
+            args.device_kind,
+            &args.credential_id,
+        )],
+    });
+
+    // === Index the credential for discoverable login ===
+    CredentialLookup::register(
+        ctx.accounts.credential_lookup.as_deref(),
+        ctx.accounts.payer.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        args.device_kind,
+        aaguid.is_some(),
+        &args.credential_id,
+        ctx.accounts.smart_wallet.key(),
+        ctx.accounts.wallet_device.key(),
+    )?;
+    PasskeyIndex::claim(
+        ctx.accounts.passkey_index.as_deref(),
+        ctx.accounts.payer.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.ix_sysvar,
+        &ctx.accounts.config,
+        &ctx.accounts.smart_wallet_data,
+        &ctx.accounts.wallet_device,
+        ctx.accounts.wallet_device.key(),
+        args.passkey_proof.as_ref(),
+    )?;
+
     // === Create PDA Signer ===
     let signer = PdaSigner {
         seeds: vec![
             WalletDevice::PREFIX_SEED.to_vec(),
             ctx.accounts.smart_wallet.key().as_ref().to_vec(),
-            args.passkey_pubkey
-                .to_hashed_bytes(ctx.accounts.smart_wallet.key())
+            args.device_kind
+                .seed_hash(&passkey_pubkey, ctx.accounts.smart_wallet.key())
                 .as_ref()
                 .to_vec(),
         ],
@@ -72,7 +139,7 @@ pub fn create_smart_wallet(
 
     // === Execute Policy Program CPI ===
     execute_cpi(
-        &ctx.remaining_accounts,
+        ctx.remaining_accounts,
         &args.policy_data,
         &ctx.accounts.default_policy_program,
         signer,
@@ -115,7 +182,7 @@ pub fn create_smart_wallet(
         ctx.accounts.wallet_device.key(),
         args.wallet_id,
         ctx.accounts.config.default_policy_program,
-        args.passkey_pubkey,
+        passkey_pubkey,
     )?;
 
     Ok(())
@@ -157,7 +224,7 @@ pub struct CreateSmartWallet<'info> {
     )]
     pub smart_wallet_data: Box<Account<'info, SmartWallet>>,
 
-    /// Wallet device for the passkey
+    /// Wallet device for the passkey or keypair
     #[account(
         init,
         payer = payer,
@@ -165,12 +232,30 @@ pub struct CreateSmartWallet<'info> {
         seeds = [
             WalletDevice::PREFIX_SEED,
             smart_wallet.key().as_ref(),
-            args.passkey_pubkey.to_hashed_bytes(smart_wallet.key()).as_ref()
+            args.device_seed_hash(smart_wallet.key()).as_ref()
         ],
         bump
     )]
     pub wallet_device: Box<Account<'info, WalletDevice>>,
 
+    /// Index of the wallet's devices
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + DeviceRegistry::INIT_SPACE,
+        seeds = [DeviceRegistry::PREFIX_SEED, smart_wallet.key().as_ref()],
+        bump
+    )]
+    pub device_registry: Box<Account<'info, DeviceRegistry>>,
+
+    /// CHECK: credential lookup PDA, required for attested passkey devices and verified on registration
+    #[account(mut)]
+    pub credential_lookup: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: passkey index PDA, required for passkey devices while unique passkeys are enforced
+    #[account(mut)]
+    pub passkey_index: Option<UncheckedAccount<'info>>,
+
     /// Program configuration
     #[account(
         seeds = [Config::PREFIX_SEED],
@@ -188,5 +273,9 @@ pub struct CreateSmartWallet<'info> {
     /// CHECK: Validated to be executable and in registry
     pub default_policy_program: UncheckedAccount<'info>,
 
+    /// CHECK: instruction sysvar, holding the passkey index claim's signature verification
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub ix_sysvar: UncheckedAccount<'info>,
+
     pub system_program: Program<'info, System>,
 }
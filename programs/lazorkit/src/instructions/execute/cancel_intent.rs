@@ -0,0 +1,111 @@
+use anchor_lang::prelude::*;
+
+use crate::instructions::{Args as _, CancelIntentArgs};
+use crate::state::{
+    CancelIntentMessage, Config, IntentReceipt, IntentStatus, NonceLane, SmartWallet, WalletDevice,
+};
+use crate::utils::{advance_nonce, verify_authorization};
+use crate::{error::LazorKitError, ID};
+
+pub fn cancel_intent(ctx: Context<CancelIntent>, args: CancelIntentArgs) -> Result<()> {
+    // 0. Validate args and global state
+    args.validate()?;
+    require!(!ctx.accounts.config.is_paused, LazorKitError::ProgramPaused);
+
+    // 1. Verify the device approved cancelling exactly this intent
+    let msg: CancelIntentMessage = verify_authorization(
+        &ctx.accounts.ix_sysvar,
+        &mut ctx.accounts.wallet_device,
+        ctx.accounts.smart_wallet.key(),
+        &ctx.accounts.smart_wallet_data,
+        &ctx.accounts.config,
+        ctx.accounts.payer.key(),
+        ctx.accounts.nonce_lane.as_deref().into(),
+        ctx.accounts.slot_hashes.as_deref(),
+        args.passkey_pubkey,
+        args.signature.clone(),
+        &args.client_data_json_raw,
+        &args.authenticator_data_raw,
+        args.verify_instruction_index,
+    )?
+    .message;
+    require!(
+        msg.intent_hash == args.intent_hash && msg.valid_until == args.valid_until,
+        LazorKitError::IntentReceiptMismatch
+    );
+
+    // 2. Mark the intent cancelled; the receipt blocks execution until the intent expires
+    ctx.accounts.intent_receipt.record(
+        IntentStatus::Cancelled,
+        ctx.accounts.smart_wallet.key(),
+        args.intent_hash,
+        args.valid_until,
+        ctx.accounts.payer.key(),
+        ctx.bumps.intent_receipt,
+    )?;
+
+    // 3. Increment nonce
+    advance_nonce(
+        &mut ctx.accounts.smart_wallet_data,
+        ctx.accounts.nonce_lane.as_deref_mut(),
+    )?;
+
+    msg!(
+        "Intent cancelled for wallet: {}",
+        ctx.accounts.smart_wallet.key()
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(args: CancelIntentArgs)]
+pub struct CancelIntent<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(seeds = [Config::PREFIX_SEED], bump, owner = ID)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        seeds = [crate::constants::SMART_WALLET_SEED, smart_wallet_data.id.to_le_bytes().as_ref()],
+        bump = smart_wallet_data.bump,
+        owner = ID,
+    )]
+    /// CHECK: smart wallet PDA verified by seeds
+    pub smart_wallet: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [SmartWallet::PREFIX_SEED, smart_wallet.key().as_ref()],
+        bump,
+        owner = ID,
+    )]
+    pub smart_wallet_data: Box<Account<'info, SmartWallet>>,
+
+    #[account(mut, owner = ID)]
+    pub wallet_device: Box<Account<'info, WalletDevice>>,
+
+    /// Replay registry entry of the intent being cancelled
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + IntentReceipt::INIT_SPACE,
+        seeds = [IntentReceipt::PREFIX_SEED, smart_wallet.key().as_ref(), args.intent_hash.as_ref()],
+        bump
+    )]
+    pub intent_receipt: Account<'info, IntentReceipt>,
+
+    /// CHECK: instruction sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub ix_sysvar: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Nonce lane the challenge is sequenced on (None = the wallet's primary nonce)
+    #[account(mut, has_one = smart_wallet @ LazorKitError::SmartWalletMismatch)]
+    pub nonce_lane: Option<Account<'info, NonceLane>>,
+
+    /// CHECK: SlotHashes sysvar, only needed for challenges bound to a recent slot hash
+    #[account(address = anchor_lang::solana_program::sysvar::slot_hashes::ID)]
+    pub slot_hashes: Option<UncheckedAccount<'info>>,
+}
@@ -5,7 +5,7 @@ use crate::security::validation;
 use crate::state::{
     Config, ExecuteMessage, PolicyProgramRegistry, SmartWallet, TransactionSession, WalletDevice,
 };
-use crate::utils::{execute_cpi, get_pda_signer, sighash, verify_authorization, PasskeyExt};
+use crate::utils::{execute_cpi, get_pda_signer, sighash, verify_authorization, ReplayGuard};
 use crate::{constants::SMART_WALLET_SEED, error::LazorKitError, ID};
 use anchor_lang::solana_program::hash::{hash, Hasher};
 
@@ -14,25 +14,30 @@ pub fn create_transaction_session(
     args: CreateSessionArgs,
 ) -> Result<()> {
     // 0. Validate
-    validation::validate_remaining_accounts(&ctx.remaining_accounts)?;
+    validation::validate_remaining_accounts(ctx.remaining_accounts)?;
     validation::validate_policy_data(&args.policy_data)?;
     require!(!ctx.accounts.config.is_paused, LazorKitError::ProgramPaused);
 
     // 1. Authorization -> typed ExecuteMessage
     let msg: ExecuteMessage = verify_authorization::<ExecuteMessage>(
         &ctx.accounts.ix_sysvar,
-        &ctx.accounts.wallet_device,
+        &mut ctx.accounts.wallet_device,
         ctx.accounts.smart_wallet.key(),
+        &ctx.accounts.smart_wallet_data,
+        &ctx.accounts.config,
+        ctx.accounts.payer.key(),
+        ReplayGuard::Primary,
+        ctx.accounts.slot_hashes.as_deref(),
         args.passkey_pubkey,
         args.signature.clone(),
         &args.client_data_json_raw,
         &args.authenticator_data_raw,
         args.verify_instruction_index,
-        ctx.accounts.smart_wallet_data.last_nonce,
-    )?;
+    )?
+    .message;
 
     // 2. In session mode, all remaining accounts are for policy checking
-    let policy_accounts = &ctx.remaining_accounts[..];
+    let policy_accounts = ctx.remaining_accounts;
 
     // 3. Optional policy-check now (bind policy & validate hashes)
     // Ensure policy program matches config and registry
@@ -66,6 +71,7 @@ pub fn create_transaction_session(
 
     // Execute policy check
     let policy_signer = get_pda_signer(
+        ctx.accounts.wallet_device.kind,
         &args.passkey_pubkey,
         ctx.accounts.smart_wallet.key(),
         ctx.accounts.wallet_device.bump,
@@ -121,10 +127,11 @@ pub struct CreateTransactionSession<'info> {
     pub smart_wallet_data: Box<Account<'info, SmartWallet>>,
 
     #[account(
+        mut,
         seeds = [
             WalletDevice::PREFIX_SEED,
             smart_wallet.key().as_ref(),
-            args.passkey_pubkey.to_hashed_bytes(smart_wallet.key()).as_ref()
+            wallet_device.kind.seed_hash(&args.passkey_pubkey, smart_wallet.key()).as_ref()
         ],
         bump = wallet_device.bump,
         owner = ID,
@@ -161,4 +168,8 @@ pub struct CreateTransactionSession<'info> {
     pub ix_sysvar: UncheckedAccount<'info>,
 
     pub system_program: Program<'info, System>,
+
+    /// CHECK: SlotHashes sysvar, only needed for challenges bound to a recent slot hash
+    #[account(address = anchor_lang::solana_program::sysvar::slot_hashes::ID)]
+    pub slot_hashes: Option<UncheckedAccount<'info>>,
 }
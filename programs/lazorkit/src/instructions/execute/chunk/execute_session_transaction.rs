@@ -12,11 +12,11 @@ pub fn execute_session_transaction(
     ctx: Context<ExecuteSessionTransaction>,
     cpi_data: Vec<u8>,
 ) -> Result<()> {
-    let cpi_accounts = &ctx.remaining_accounts[..];
+    let cpi_accounts = ctx.remaining_accounts;
 
     // We'll gracefully abort (close the commit and return Ok) if any binding check fails.
     // Only hard fail on obviously invalid input sizes.
-    if validation::validate_remaining_accounts(&cpi_accounts).is_err() {
+    if validation::validate_remaining_accounts(cpi_accounts).is_err() {
         msg!("Invalid remaining accounts; closing session with refund due to graceful flag");
         return Ok(());
     }
@@ -1,11 +1,12 @@
 use anchor_lang::prelude::*;
 
+use crate::events::TransactionExecuted;
 use crate::instructions::{Args as _, ExecuteTransactionArgs};
 use crate::security::validation;
-use crate::state::ExecuteMessage;
+use crate::state::{ExecuteMessage, Freshness, IntentStatus};
 use crate::utils::{
-    check_whitelist, execute_cpi, get_pda_signer, sighash, split_remaining_accounts,
-    transfer_sol_from_pda, verify_authorization, PdaSigner,
+    advance_nonce, check_whitelist, execute_cpi, get_pda_signer, sighash, split_remaining_accounts,
+    transfer_sol_from_pda, verify_co_signed_authorization, Authorized, PdaSigner, ReplayGuard,
 };
 use crate::{
     constants::{SMART_WALLET_SEED, SOL_TRANSFER_DISCRIMINATOR},
@@ -20,21 +21,68 @@ pub fn execute_transaction<'c: 'info, 'info>(
     // 0. Validate args and global state
     args.validate()?;
     require!(!ctx.accounts.config.is_paused, LazorKitError::ProgramPaused);
-    validation::validate_remaining_accounts(&ctx.remaining_accounts)?;
+    validation::validate_remaining_accounts(ctx.remaining_accounts)?;
 
-    // 0.1 Verify authorization and parse typed message
-    let msg: ExecuteMessage = verify_authorization(
+    // 0.1 Verify authorization and parse typed message; a signed intent is protected against
+    // replay by its receipt instead of a nonce
+    let replay = match args.intent_hash {
+        Some(_) => {
+            require!(
+                ctx.accounts.nonce_lane.is_none(),
+                LazorKitError::InvalidNonceLane
+            );
+            ReplayGuard::Intent
+        }
+        None => ctx.accounts.nonce_lane.as_deref().into(),
+    };
+    // Co-signing devices of a threshold approval lead the remaining accounts
+    require!(
+        args.co_signers.len() <= ctx.remaining_accounts.len(),
+        LazorKitError::InvalidRemainingAccounts
+    );
+    let (co_signer_accounts, remaining_accounts) =
+        ctx.remaining_accounts.split_at(args.co_signers.len());
+    let Authorized {
+        message: msg,
+        freshness,
+        challenge_hash,
+    } = verify_co_signed_authorization::<ExecuteMessage>(
         &ctx.accounts.ix_sysvar,
-        &ctx.accounts.wallet_device,
+        &mut ctx.accounts.wallet_device,
+        args.primary_assertion(),
+        &args.co_signers,
+        co_signer_accounts,
         ctx.accounts.smart_wallet.key(),
-        args.passkey_pubkey,
-        args.signature.clone(),
-        &args.client_data_json_raw,
-        &args.authenticator_data_raw,
-        args.verify_instruction_index,
-        ctx.accounts.smart_wallet_data.last_nonce,
+        &ctx.accounts.smart_wallet_data,
+        &ctx.accounts.config,
+        ctx.accounts.payer.key(),
+        replay,
+        ctx.accounts.slot_hashes.as_deref(),
     )?;
 
+    // 0.2 Record a signed intent in the replay registry
+    match (args.intent_hash, ctx.accounts.intent_receipt.as_mut()) {
+        (Some(intent_hash), Some(receipt)) => {
+            require!(
+                intent_hash == challenge_hash,
+                LazorKitError::IntentReceiptMismatch
+            );
+            let Freshness::Window { valid_until, .. } = freshness else {
+                return err!(LazorKitError::IntentWindowRequired);
+            };
+            receipt.record(
+                IntentStatus::Executed,
+                ctx.accounts.smart_wallet.key(),
+                intent_hash,
+                valid_until,
+                ctx.accounts.payer.key(),
+                ctx.bumps.intent_receipt.unwrap_or_default(),
+            )?;
+        }
+        (None, None) => {}
+        _ => return err!(LazorKitError::IntentReceiptMismatch),
+    }
+
     // 1. Validate and check policy program
     let policy_program_info = &ctx.accounts.policy_program;
 
@@ -55,6 +103,7 @@ pub fn execute_transaction<'c: 'info, 'info>(
 
     // 2. Prepare PDA signer for policy CPI
     let policy_signer = get_pda_signer(
+        ctx.accounts.wallet_device.kind,
         &args.passkey_pubkey,
         ctx.accounts.smart_wallet.key(),
         ctx.accounts.wallet_device.bump,
@@ -62,7 +111,7 @@ pub fn execute_transaction<'c: 'info, 'info>(
 
     // 3. Split remaining accounts
     let (policy_accounts, cpi_accounts) =
-        split_remaining_accounts(&ctx.remaining_accounts, args.split_index)?;
+        split_remaining_accounts(remaining_accounts, args.split_index)?;
 
     // Validate account counts
     require!(
@@ -216,17 +265,27 @@ pub fn execute_transaction<'c: 'info, 'info>(
     }
 
     msg!("Transaction executed successfully");
-    // 8. Increment nonce
-    ctx.accounts.smart_wallet_data.last_nonce = ctx
-        .accounts
-        .smart_wallet_data
-        .last_nonce
-        .checked_add(1)
-        .ok_or(LazorKitError::NonceOverflow)?;
+    TransactionExecuted::emit_event(
+        ctx.accounts.smart_wallet.key(),
+        ctx.accounts.wallet_device.key(),
+        &ctx.accounts.wallet_device,
+        msg.nonce,
+        ctx.accounts.policy_program.key(),
+        ctx.accounts.cpi_program.key(),
+        true,
+    )?;
+    // 8. Increment nonce (intents were recorded in their receipt instead)
+    if args.intent_hash.is_none() {
+        advance_nonce(
+            &mut ctx.accounts.smart_wallet_data,
+            ctx.accounts.nonce_lane.as_deref_mut(),
+        )?;
+    }
     Ok(())
 }
 
 #[derive(Accounts)]
+#[instruction(args: ExecuteTransactionArgs)]
 pub struct ExecuteTransaction<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
@@ -248,7 +307,7 @@ pub struct ExecuteTransaction<'info> {
     )]
     pub smart_wallet_data: Box<Account<'info, crate::state::SmartWallet>>,
 
-    #[account(owner = crate::ID)]
+    #[account(mut, owner = crate::ID)]
     pub wallet_device: Box<Account<'info, crate::state::WalletDevice>>,
     #[account(
         seeds = [crate::state::PolicyProgramRegistry::PREFIX_SEED],
@@ -271,4 +330,28 @@ pub struct ExecuteTransaction<'info> {
     /// CHECK: instruction sysvar
     #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
     pub ix_sysvar: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Replay registry entry of the signed intent being executed (required with `intent_hash`)
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + crate::state::IntentReceipt::INIT_SPACE,
+        seeds = [
+            crate::state::IntentReceipt::PREFIX_SEED,
+            smart_wallet.key().as_ref(),
+            args.intent_hash.unwrap_or_default().as_ref()
+        ],
+        bump
+    )]
+    pub intent_receipt: Option<Account<'info, crate::state::IntentReceipt>>,
+
+    /// Nonce lane the challenge is sequenced on (None = the wallet's primary nonce)
+    #[account(mut, has_one = smart_wallet @ crate::error::LazorKitError::SmartWalletMismatch)]
+    pub nonce_lane: Option<Account<'info, crate::state::NonceLane>>,
+
+    /// CHECK: SlotHashes sysvar, only needed for challenges bound to a recent slot hash
+    #[account(address = anchor_lang::solana_program::sysvar::slot_hashes::ID)]
+    pub slot_hashes: Option<UncheckedAccount<'info>>,
 }
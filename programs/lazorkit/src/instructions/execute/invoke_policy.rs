@@ -2,10 +2,16 @@ use anchor_lang::prelude::*;
 
 use crate::instructions::{Args as _, InvokePolicyArgs};
 use crate::security::validation;
-use crate::state::{Config, InvokePolicyMessage, PolicyProgramRegistry, SmartWallet, WalletDevice};
-use crate::utils::{check_whitelist, execute_cpi, get_pda_signer, verify_authorization};
+use crate::state::{
+    Config, CredentialLookup, DeviceEntry, DeviceRegistry, InvokePolicyMessage, NonceLane,
+    PasskeyIndex, PolicyProgramRegistry, SmartWallet, WalletDevice,
+};
+use crate::utils::{
+    advance_nonce, check_whitelist, execute_cpi, get_pda_signer, policy_data_hash,
+    verify_co_signed_authorization, verify_device_registration,
+};
 use crate::{error::LazorKitError, ID};
-use anchor_lang::solana_program::hash::{hash, Hasher};
+use anchor_lang::solana_program::hash::Hasher;
 
 pub fn invoke_policy<'c: 'info, 'info>(
     ctx: Context<'_, '_, 'c, 'info, InvokePolicy<'info>>,
@@ -14,9 +20,9 @@ pub fn invoke_policy<'c: 'info, 'info>(
     // 0. Validate args and global state
     args.validate()?;
     require!(!ctx.accounts.config.is_paused, LazorKitError::ProgramPaused);
-    validation::validate_remaining_accounts(&ctx.remaining_accounts)?;
+    validation::validate_remaining_accounts(ctx.remaining_accounts)?;
     validation::validate_program_executable(&ctx.accounts.policy_program)?;
-// Policy program must be the configured one and registered
+    // Policy program must be the configured one and registered
     require!(
         ctx.accounts.policy_program.key() == ctx.accounts.smart_wallet_data.policy_program,
         LazorKitError::InvalidProgramAddress
@@ -27,32 +33,52 @@ pub fn invoke_policy<'c: 'info, 'info>(
     )?;
     validation::validate_policy_data(&args.policy_data)?;
 
-    // Verify and deserialize message purpose-built for policy invocation
-    let msg: InvokePolicyMessage = verify_authorization(
+    // Enrolling a device needs the wallet's threshold of approvals
+    if args.new_wallet_device.is_some() {
+        ctx.accounts
+            .smart_wallet_data
+            .check_device_add_approvals(1 + args.co_signers.len())?;
+    }
+
+    // Verify and deserialize message purpose-built for policy invocation. Co-signing devices of
+    // a threshold approval lead the remaining accounts.
+    require!(
+        args.co_signers.len() <= ctx.remaining_accounts.len(),
+        LazorKitError::InvalidRemainingAccounts
+    );
+    let (co_signer_accounts, remaining_accounts) =
+        ctx.remaining_accounts.split_at(args.co_signers.len());
+    let msg: InvokePolicyMessage = verify_co_signed_authorization(
         &ctx.accounts.ix_sysvar,
-        &ctx.accounts.wallet_device,
+        &mut ctx.accounts.wallet_device,
+        args.primary_assertion(),
+        &args.co_signers,
+        co_signer_accounts,
         ctx.accounts.smart_wallet.key(),
-        args.passkey_pubkey,
-        args.signature.clone(),
-        &args.client_data_json_raw,
-        &args.authenticator_data_raw,
-        args.verify_instruction_index,
-        ctx.accounts.smart_wallet_data.last_nonce,
-    )?;
+        &ctx.accounts.smart_wallet_data,
+        &ctx.accounts.config,
+        ctx.accounts.payer.key(),
+        ctx.accounts.nonce_lane.as_deref().into(),
+        ctx.accounts.slot_hashes.as_deref(),
+    )?
+    .message;
 
-    // Compare inline policy_data hash
+    // Compare inline policy_data hash, which also covers the device being enrolled
     require!(
-        hash(&args.policy_data).to_bytes() == msg.policy_data_hash,
+        policy_data_hash(&args.policy_data, args.new_wallet_device.as_ref())?
+            == msg.policy_data_hash,
         LazorKitError::InvalidInstructionData
     );
 
-    // Hash policy accounts (skip optional new wallet_device at index 0)
+    // Hash policy accounts (skip the optional new wallet_device that follows the co-signers)
     let start_idx = if args.new_wallet_device.is_some() {
         1
     } else {
         0
     };
-    let policy_accs = &ctx.remaining_accounts[start_idx..];
+    let policy_accs = remaining_accounts
+        .get(start_idx..)
+        .ok_or(LazorKitError::InvalidRemainingAccounts)?;
     let mut hasher = Hasher::default();
     hasher.hash(ctx.accounts.policy_program.key().as_ref());
     for acc in policy_accs.iter() {
@@ -67,6 +93,7 @@ pub fn invoke_policy<'c: 'info, 'info>(
 
     // PDA signer for policy CPI
     let policy_signer = get_pda_signer(
+        ctx.accounts.wallet_device.kind,
         &args.passkey_pubkey,
         ctx.accounts.smart_wallet.key(),
         ctx.accounts.wallet_device.bump,
@@ -74,14 +101,25 @@ pub fn invoke_policy<'c: 'info, 'info>(
 
     // Optionally create new wallet_device if requested
     if let Some(new_wallet_device) = args.new_wallet_device {
-        require!(
-            new_wallet_device.passkey_pubkey[0] == 0x02
-                || new_wallet_device.passkey_pubkey[0] == 0x03,
-            LazorKitError::InvalidPasskeyFormat
-        );
+        let passkey_pubkey = new_wallet_device
+            .device_kind
+            .canonical_key(&new_wallet_device.passkey_pubkey)?;
+        let aaguid = verify_device_registration(
+            ctx.accounts
+                .smart_wallet_data
+                .effective_aaguids(&ctx.accounts.config),
+            new_wallet_device
+                .rp_id_hash
+                .or(ctx.accounts.config.rp_id_hash),
+            new_wallet_device.device_kind,
+            &passkey_pubkey,
+            &new_wallet_device.credential_id,
+            new_wallet_device.backup_eligible,
+            new_wallet_device.backup_state,
+            new_wallet_device.attestation_authenticator_data.as_deref(),
+        )?;
         // Get the new wallet_device account from remaining accounts
-        let new_device = ctx
-            .remaining_accounts
+        let new_device = remaining_accounts
             .first()
             .ok_or(LazorKitError::InvalidRemainingAccounts)?;
 
@@ -89,14 +127,53 @@ pub fn invoke_policy<'c: 'info, 'info>(
             new_device.data_is_empty(),
             LazorKitError::AccountAlreadyInitialized
         );
+        let device_limit = ctx.accounts.config.wallet_device_limit();
+        let entry = DeviceEntry::new(
+            new_device.key(),
+            new_wallet_device.device_kind,
+            &new_wallet_device.credential_id,
+        );
+        DeviceRegistry::update(&ctx.accounts.device_registry, |registry| {
+            registry.add(entry, device_limit)
+        })?;
+        CredentialLookup::register(
+            ctx.accounts.credential_lookup.as_deref(),
+            ctx.accounts.payer.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            new_wallet_device.device_kind,
+            aaguid.is_some(),
+            &new_wallet_device.credential_id,
+            ctx.accounts.smart_wallet.key(),
+            new_device.key(),
+        )?;
         crate::state::WalletDevice::init(
             new_device,
             ctx.accounts.payer.to_account_info(),
             ctx.accounts.system_program.to_account_info(),
             ctx.accounts.smart_wallet.key(),
-            new_wallet_device.passkey_pubkey,
+            new_wallet_device.device_kind,
+            passkey_pubkey,
             new_wallet_device.credential_id,
+            new_wallet_device.rp_id_hash,
+            new_wallet_device.backup_eligible,
+            new_wallet_device.backup_state,
+            aaguid,
+        )?;
+        let device = WalletDevice::try_deserialize(&mut &new_device.try_borrow_data()?[..])?;
+        PasskeyIndex::claim(
+            ctx.accounts.passkey_index.as_deref(),
+            ctx.accounts.payer.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            &ctx.accounts.ix_sysvar,
+            &ctx.accounts.config,
+            &ctx.accounts.smart_wallet_data,
+            &device,
+            new_device.key(),
+            new_wallet_device.passkey_proof.as_ref(),
         )?;
+        ctx.accounts
+            .smart_wallet_data
+            .count_device_added(device_limit)?;
     }
 
     // Execute policy CPI
@@ -109,12 +186,10 @@ pub fn invoke_policy<'c: 'info, 'info>(
     )?;
 
     // increment nonce
-    ctx.accounts.smart_wallet_data.last_nonce = ctx
-        .accounts
-        .smart_wallet_data
-        .last_nonce
-        .checked_add(1)
-        .ok_or(LazorKitError::NonceOverflow)?;
+    advance_nonce(
+        &mut ctx.accounts.smart_wallet_data,
+        ctx.accounts.nonce_lane.as_deref_mut(),
+    )?;
 
     Ok(())
 }
@@ -144,9 +219,25 @@ pub struct InvokePolicy<'info> {
     )]
     pub smart_wallet_data: Box<Account<'info, SmartWallet>>,
 
-    #[account(owner = ID)]
+    #[account(mut, owner = ID)]
     pub wallet_device: Box<Account<'info, WalletDevice>>,
 
+    /// CHECK: device registry PDA verified by seeds; kept in sync once the wallet has one
+    #[account(
+        mut,
+        seeds = [DeviceRegistry::PREFIX_SEED, smart_wallet.key().as_ref()],
+        bump,
+    )]
+    pub device_registry: UncheckedAccount<'info>,
+
+    /// CHECK: credential lookup PDA of a new passkey device, verified on registration
+    #[account(mut)]
+    pub credential_lookup: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: passkey index PDA of a new passkey device, verified on registration
+    #[account(mut)]
+    pub passkey_index: Option<UncheckedAccount<'info>>,
+
     /// CHECK: executable policy program
     #[account(executable)]
     pub policy_program: UncheckedAccount<'info>,
@@ -163,4 +254,12 @@ pub struct InvokePolicy<'info> {
     pub ix_sysvar: UncheckedAccount<'info>,
 
     pub system_program: Program<'info, System>,
+
+    /// Nonce lane the challenge is sequenced on (None = the wallet's primary nonce)
+    #[account(mut, has_one = smart_wallet @ LazorKitError::SmartWalletMismatch)]
+    pub nonce_lane: Option<Account<'info, NonceLane>>,
+
+    /// CHECK: SlotHashes sysvar, only needed for challenges bound to a recent slot hash
+    #[account(address = anchor_lang::solana_program::sysvar::slot_hashes::ID)]
+    pub slot_hashes: Option<UncheckedAccount<'info>>,
 }
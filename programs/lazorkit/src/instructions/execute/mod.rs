@@ -1,9 +1,15 @@
-mod invoke_policy;
-mod update_policy;
+mod cancel_intent;
 mod chunk;
 mod execute_transaction;
+mod invoke_policy;
+mod remove_wallet_device;
+mod update_policy;
+mod update_wallet_settings;
 
-pub use invoke_policy::*;
-pub use update_policy::*;
+pub use cancel_intent::*;
 pub use chunk::*;
 pub use execute_transaction::*;
+pub use invoke_policy::*;
+pub use remove_wallet_device::*;
+pub use update_policy::*;
+pub use update_wallet_settings::*;
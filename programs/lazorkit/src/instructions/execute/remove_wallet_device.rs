@@ -0,0 +1,240 @@
+use anchor_lang::prelude::*;
+
+use crate::instructions::{Args as _, RemoveWalletDeviceArgs};
+use crate::security::validation;
+use crate::state::{
+    Config, CredentialLookup, DeviceRegistry, NonceLane, PasskeyIndex, PolicyProgramRegistry,
+    RemoveDeviceMessage, SmartWallet, WalletDevice,
+};
+use crate::utils::{
+    advance_nonce, check_whitelist, execute_cpi, get_pda_signer, sighash,
+    verify_co_signed_authorization,
+};
+use crate::{error::LazorKitError, ID};
+use anchor_lang::solana_program::hash::{hash, Hasher};
+
+pub fn remove_wallet_device<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, RemoveWalletDevice<'info>>,
+    args: RemoveWalletDeviceArgs,
+) -> Result<()> {
+    // 0. Validate args and global state
+    args.validate()?;
+    require!(!ctx.accounts.config.is_paused, LazorKitError::ProgramPaused);
+    validation::validate_remaining_accounts(ctx.remaining_accounts)?;
+    validation::validate_program_executable(&ctx.accounts.policy_program)?;
+    require!(
+        ctx.accounts.policy_program.key() == ctx.accounts.smart_wallet_data.policy_program,
+        LazorKitError::InvalidProgramAddress
+    );
+    check_whitelist(
+        &ctx.accounts.policy_program_registry,
+        &ctx.accounts.policy_program.key(),
+    )?;
+    validation::validate_policy_data(&args.policy_data)?;
+
+    // The wallet must keep at least one device, and enough of them to meet its threshold. A
+    // device missing from the registry was never counted, so removing it keeps the count.
+    let removed_device = ctx.accounts.removed_wallet_device.key();
+    let counted = DeviceRegistry::counts(&ctx.accounts.device_registry, &removed_device)?;
+    let device_count = ctx.accounts.smart_wallet_data.device_count;
+    let remaining_devices = if counted {
+        device_count.saturating_sub(1)
+    } else {
+        device_count
+    };
+    require!(remaining_devices > 0, LazorKitError::LastWalletDevice);
+    require!(
+        remaining_devices >= u16::from(ctx.accounts.smart_wallet_data.threshold),
+        LazorKitError::InvalidThreshold
+    );
+
+    // 1. Verify another device (and any co-signers, which lead the remaining accounts) approved
+    // removing exactly this device
+    require!(
+        args.co_signers.len() <= ctx.remaining_accounts.len(),
+        LazorKitError::InvalidRemainingAccounts
+    );
+    let (co_signer_accounts, policy_accounts) =
+        ctx.remaining_accounts.split_at(args.co_signers.len());
+    let msg: RemoveDeviceMessage = verify_co_signed_authorization(
+        &ctx.accounts.ix_sysvar,
+        &mut ctx.accounts.wallet_device,
+        args.primary_assertion(),
+        &args.co_signers,
+        co_signer_accounts,
+        ctx.accounts.smart_wallet.key(),
+        &ctx.accounts.smart_wallet_data,
+        &ctx.accounts.config,
+        ctx.accounts.payer.key(),
+        ctx.accounts.nonce_lane.as_deref().into(),
+        ctx.accounts.slot_hashes.as_deref(),
+    )?
+    .message;
+    require!(
+        msg.wallet_device == ctx.accounts.removed_wallet_device.key()
+            && msg.rent_refund == ctx.accounts.rent_refund.key(),
+        LazorKitError::InvalidInstructionData
+    );
+    ctx.accounts
+        .smart_wallet_data
+        .check_device_removal_approvals(1 + args.co_signers.len())?;
+
+    // 2. Verify the policy hook call against the message
+    require!(
+        args.policy_data.get(0..8) == Some(&sighash("global", "remove_device")),
+        LazorKitError::InvalidRemoveDeviceDiscriminator
+    );
+    require!(
+        hash(&args.policy_data).to_bytes() == msg.policy_data_hash,
+        LazorKitError::InvalidInstructionData
+    );
+    let mut hasher = Hasher::default();
+    hasher.hash(ctx.accounts.policy_program.key().as_ref());
+    for acc in policy_accounts.iter() {
+        hasher.hash(acc.key.as_ref());
+        hasher.hash(&[acc.is_signer as u8]);
+        hasher.hash(&[acc.is_writable as u8]);
+    }
+    require!(
+        hasher.result().to_bytes() == msg.policy_accounts_hash,
+        LazorKitError::InvalidAccountData
+    );
+
+    // 3. Let the policy program clean up its state for the removed device
+    let policy_signer = get_pda_signer(
+        ctx.accounts.wallet_device.kind,
+        &args.passkey_pubkey,
+        ctx.accounts.smart_wallet.key(),
+        ctx.accounts.wallet_device.bump,
+    );
+    execute_cpi(
+        policy_accounts,
+        &args.policy_data,
+        &ctx.accounts.policy_program,
+        policy_signer,
+        &[ctx.accounts.payer.key()],
+    )?;
+
+    // 4. Update device index, count and nonce; the device account is closed on exit
+    if counted {
+        DeviceRegistry::update(&ctx.accounts.device_registry, |registry| {
+            registry.remove(&removed_device)
+        })?;
+    }
+    CredentialLookup::release(
+        &ctx.accounts.credential_lookup,
+        &removed_device,
+        &ctx.accounts.rent_refund,
+    )?;
+    PasskeyIndex::release(
+        &ctx.accounts.passkey_index,
+        &removed_device,
+        &ctx.accounts.rent_refund,
+    )?;
+    let wallet_data = &mut ctx.accounts.smart_wallet_data;
+    wallet_data.device_count = remaining_devices;
+    advance_nonce(wallet_data, ctx.accounts.nonce_lane.as_deref_mut())?;
+
+    msg!(
+        "Wallet device {} removed from wallet: {}",
+        ctx.accounts.removed_wallet_device.key(),
+        ctx.accounts.smart_wallet.key()
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RemoveWalletDevice<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(seeds = [Config::PREFIX_SEED], bump, owner = ID)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        seeds = [crate::constants::SMART_WALLET_SEED, smart_wallet_data.id.to_le_bytes().as_ref()],
+        bump = smart_wallet_data.bump,
+        owner = ID,
+    )]
+    /// CHECK: smart wallet PDA verified by seeds
+    pub smart_wallet: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [SmartWallet::PREFIX_SEED, smart_wallet.key().as_ref()],
+        bump,
+        owner = ID,
+    )]
+    pub smart_wallet_data: Box<Account<'info, SmartWallet>>,
+
+    /// Device authorizing the removal
+    #[account(mut, owner = ID)]
+    pub wallet_device: Box<Account<'info, WalletDevice>>,
+
+    /// Device being removed; its rent goes to `rent_refund`
+    #[account(
+        mut,
+        close = rent_refund,
+        owner = ID,
+        has_one = smart_wallet @ LazorKitError::SmartWalletMismatch,
+        constraint = removed_wallet_device.key() != wallet_device.key() @ LazorKitError::SelfDeviceRemoval,
+    )]
+    pub removed_wallet_device: Box<Account<'info, WalletDevice>>,
+
+    /// CHECK: device registry PDA verified by seeds; kept in sync once the wallet has one
+    #[account(
+        mut,
+        seeds = [DeviceRegistry::PREFIX_SEED, smart_wallet.key().as_ref()],
+        bump,
+    )]
+    pub device_registry: UncheckedAccount<'info>,
+
+    /// CHECK: credential lookup PDA of the removed device, closed if it points at that device
+    #[account(
+        mut,
+        seeds = [
+            CredentialLookup::PREFIX_SEED,
+            removed_wallet_device.credential_id_hash().as_ref()
+        ],
+        bump,
+    )]
+    pub credential_lookup: UncheckedAccount<'info>,
+
+    /// CHECK: passkey index PDA of the removed device, closed if it binds that device
+    #[account(
+        mut,
+        seeds = [
+            PasskeyIndex::PREFIX_SEED,
+            PasskeyIndex::seed_hash(&removed_wallet_device.passkey_pubkey).as_ref()
+        ],
+        bump,
+    )]
+    pub passkey_index: UncheckedAccount<'info>,
+
+    /// CHECK: rent refund destination, bound by the signed message
+    #[account(mut)]
+    pub rent_refund: UncheckedAccount<'info>,
+
+    /// CHECK: executable policy program
+    #[account(executable)]
+    pub policy_program: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [PolicyProgramRegistry::PREFIX_SEED],
+        bump,
+        owner = ID
+    )]
+    pub policy_program_registry: Box<Account<'info, PolicyProgramRegistry>>,
+
+    /// CHECK: instruction sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub ix_sysvar: UncheckedAccount<'info>,
+
+    /// Nonce lane the challenge is sequenced on (None = the wallet's primary nonce)
+    #[account(mut, has_one = smart_wallet @ LazorKitError::SmartWalletMismatch)]
+    pub nonce_lane: Option<Account<'info, NonceLane>>,
+
+    /// CHECK: SlotHashes sysvar, only needed for challenges bound to a recent slot hash
+    #[account(address = anchor_lang::solana_program::sysvar::slot_hashes::ID)]
+    pub slot_hashes: Option<UncheckedAccount<'info>>,
+}
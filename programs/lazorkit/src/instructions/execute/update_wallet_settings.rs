@@ -0,0 +1,109 @@
+use anchor_lang::prelude::*;
+
+use crate::instructions::{Args as _, UpdateWalletSettingsArgs};
+use crate::security::validation;
+use crate::state::{Config, NonceLane, SmartWallet, UpdateWalletSettingsMessage, WalletDevice};
+use crate::utils::{advance_nonce, verify_co_signed_authorization};
+use crate::{error::LazorKitError, ID};
+use anchor_lang::solana_program::hash::hash;
+
+pub fn update_wallet_settings<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, UpdateWalletSettings<'info>>,
+    args: UpdateWalletSettingsArgs,
+) -> Result<()> {
+    // 0. Validate args and global state
+    args.validate()?;
+    require!(!ctx.accounts.config.is_paused, LazorKitError::ProgramPaused);
+    let settings = &args.settings;
+    validation::validate_allowed_origins(&settings.allowed_origin_hashes)?;
+    validation::validate_allowed_aaguids(&settings.allowed_aaguids)?;
+    validation::validate_timestamp_drift(settings.timestamp_drift_seconds)?;
+    validation::validate_threshold(
+        settings.threshold,
+        settings.threshold_operations,
+        ctx.accounts.smart_wallet_data.device_count,
+    )?;
+
+    // 1. Verify the device (and any co-signers, passed as remaining accounts) approved exactly
+    // these settings
+    let msg: UpdateWalletSettingsMessage = verify_co_signed_authorization(
+        &ctx.accounts.ix_sysvar,
+        &mut ctx.accounts.wallet_device,
+        args.primary_assertion(),
+        &args.co_signers,
+        ctx.remaining_accounts,
+        ctx.accounts.smart_wallet.key(),
+        &ctx.accounts.smart_wallet_data,
+        &ctx.accounts.config,
+        ctx.accounts.payer.key(),
+        ctx.accounts.nonce_lane.as_deref().into(),
+        ctx.accounts.slot_hashes.as_deref(),
+    )?
+    .message;
+    require!(
+        hash(&settings.try_to_vec()?).to_bytes() == msg.settings_hash,
+        LazorKitError::InvalidInstructionData
+    );
+    ctx.accounts
+        .smart_wallet_data
+        .check_settings_approvals(settings, 1 + args.co_signers.len())?;
+
+    // 2. Apply settings
+    let wallet_data = &mut ctx.accounts.smart_wallet_data;
+    wallet_data.allowed_origin_hashes = settings.allowed_origin_hashes.clone();
+    wallet_data.require_user_verification = settings.require_user_verification;
+    wallet_data.device_bound_operations = settings.device_bound_operations;
+    wallet_data.allowed_aaguids = settings.allowed_aaguids.clone();
+    wallet_data.timestamp_drift_seconds = settings.timestamp_drift_seconds;
+    wallet_data.threshold = settings.threshold;
+    wallet_data.threshold_operations = settings.threshold_operations;
+
+    // 3. Increment nonce
+    advance_nonce(wallet_data, ctx.accounts.nonce_lane.as_deref_mut())?;
+
+    msg!(
+        "Wallet settings updated: {}",
+        ctx.accounts.smart_wallet.key()
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateWalletSettings<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(seeds = [Config::PREFIX_SEED], bump, owner = ID)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        seeds = [crate::constants::SMART_WALLET_SEED, smart_wallet_data.id.to_le_bytes().as_ref()],
+        bump = smart_wallet_data.bump,
+        owner = ID,
+    )]
+    /// CHECK: smart wallet PDA verified by seeds
+    pub smart_wallet: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [SmartWallet::PREFIX_SEED, smart_wallet.key().as_ref()],
+        bump,
+        owner = ID,
+    )]
+    pub smart_wallet_data: Box<Account<'info, SmartWallet>>,
+
+    #[account(mut, owner = ID)]
+    pub wallet_device: Box<Account<'info, WalletDevice>>,
+
+    /// CHECK: instruction sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub ix_sysvar: UncheckedAccount<'info>,
+
+    /// Nonce lane the challenge is sequenced on (None = the wallet's primary nonce)
+    #[account(mut, has_one = smart_wallet @ LazorKitError::SmartWalletMismatch)]
+    pub nonce_lane: Option<Account<'info, NonceLane>>,
+
+    /// CHECK: SlotHashes sysvar, only needed for challenges bound to a recent slot hash
+    #[account(address = anchor_lang::solana_program::sysvar::slot_hashes::ID)]
+    pub slot_hashes: Option<UncheckedAccount<'info>>,
+}
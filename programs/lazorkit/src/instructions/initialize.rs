@@ -2,10 +2,14 @@ use anchor_lang::prelude::*;
 
 use crate::{
     error::LazorKitError,
-    state::{Config, PolicyProgramRegistry},
+    state::{Config, PolicyProgramRegistry, MESSAGE_VERSION},
 };
 
-pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+/// `deployment_tag` identifies this deployment/cluster in challenge domain separators, so it
+/// must be unique per cluster and non-zero.
+pub fn initialize(ctx: Context<Initialize>, deployment_tag: u64) -> Result<()> {
+    require!(deployment_tag != 0, LazorKitError::InvalidDeploymentTag);
+
     // Check if the default policy program is executable
     if !ctx.accounts.default_policy_program.executable {
         return err!(LazorKitError::ProgramNotExecutable);
@@ -20,6 +24,15 @@ pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
     config.execute_fee = 0; // LAMPORTS
     config.default_policy_program = ctx.accounts.default_policy_program.key();
     config.is_paused = false;
+    config.allowed_origin_hashes = Vec::new();
+    config.rp_id_hash = None;
+    config.require_user_verification = false;
+    // Only migrated configs still accept legacy challenges (their appended bytes decode to 0)
+    config.min_message_version = MESSAGE_VERSION;
+    config.deployment_tag = deployment_tag;
+    config.allowed_aaguids = Vec::new();
+    config.max_wallet_devices = 0;
+    config.unique_passkeys = false;
 
     Ok(())
 }
@@ -0,0 +1,75 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+    Discriminator,
+};
+
+use crate::{
+    error::LazorKitError,
+    state::{Config, SmartWallet, WalletDevice},
+    ID,
+};
+
+/// Grow a Config, SmartWallet or WalletDevice account created before fields were appended to its
+/// layout.
+///
+/// New bytes are zero-filled, which decodes to each appended field's legacy default (empty
+/// lists, `None`, no threshold, untracked device count, passkey device kind, legacy challenges
+/// still accepted), so anyone may pay to migrate an account.
+pub fn migrate_account(ctx: Context<MigrateAccount>) -> Result<()> {
+    let account = ctx.accounts.account.to_account_info();
+    let space = {
+        let data = account.try_borrow_data()?;
+        match data.get(..8) {
+            Some(d) if d == Config::DISCRIMINATOR => 8 + Config::INIT_SPACE,
+            Some(d) if d == SmartWallet::DISCRIMINATOR => 8 + SmartWallet::INIT_SPACE,
+            Some(d) if d == WalletDevice::DISCRIMINATOR => 8 + WalletDevice::INIT_SPACE,
+            _ => return err!(LazorKitError::InvalidAccountData),
+        }
+    };
+    if account.data_len() >= space {
+        msg!("Account {} is already migrated", account.key());
+        return Ok(());
+    }
+
+    let rent_shortfall = Rent::get()?
+        .minimum_balance(space)
+        .saturating_sub(account.lamports());
+    if rent_shortfall > 0 {
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: account.clone(),
+                },
+            ),
+            rent_shortfall,
+        )?;
+    }
+    account.realloc(space, true)?;
+
+    let data = account.try_borrow_data()?;
+    if data[..8] == *Config::DISCRIMINATOR {
+        Config::try_deserialize(&mut &data[..])?;
+    } else if data[..8] == *SmartWallet::DISCRIMINATOR {
+        SmartWallet::try_deserialize(&mut &data[..])?;
+    } else {
+        WalletDevice::try_deserialize(&mut &data[..])?;
+    }
+
+    msg!("Account {} migrated to {} bytes", account.key(), space);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateAccount<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: program-owned account, identified by its discriminator
+    #[account(mut, owner = ID)]
+    pub account: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
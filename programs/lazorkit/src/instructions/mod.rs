@@ -1,11 +1,23 @@
 mod admin;
 mod args;
+mod claim_passkey_index;
+mod close_intent_receipt;
+mod create_device_registry;
+mod create_nonce_lane;
 mod create_smart_wallet;
 mod execute;
 mod initialize;
+mod migrate_account;
+mod verify_signature;
 
 pub use admin::*;
 pub use args::*;
+pub use claim_passkey_index::*;
+pub use close_intent_receipt::*;
+pub use create_device_registry::*;
+pub use create_nonce_lane::*;
 pub use create_smart_wallet::*;
 pub use execute::*;
 pub use initialize::*;
+pub use migrate_account::*;
+pub use verify_signature::*;
@@ -0,0 +1,102 @@
+use anchor_lang::prelude::*;
+
+use crate::instructions::{Args as _, VerifySignatureArgs};
+use crate::state::{Config, SignedMessage, SmartWallet, WalletDevice, ANY_PAYER};
+use crate::utils::check_co_signed_authorization;
+use crate::{error::LazorKitError, ID};
+
+pub fn verify_signature<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, VerifySignature<'info>>,
+    args: VerifySignatureArgs,
+) -> Result<bool> {
+    // Verification failures are reported through the return data so callers can branch on them
+    // instead of having their own instruction aborted
+    let valid = match check_signature(&ctx, &args) {
+        Ok(()) => true,
+        Err(err) => {
+            msg!("Signature rejected: {}", err);
+            false
+        }
+    };
+    msg!(
+        "Signature check for wallet {}: {}",
+        ctx.accounts.smart_wallet.key(),
+        valid
+    );
+    Ok(valid)
+}
+
+fn check_signature<'c: 'info, 'info>(
+    ctx: &Context<'_, '_, 'c, 'info, VerifySignature<'info>>,
+    args: &VerifySignatureArgs,
+) -> Result<()> {
+    // 0. Validate args and global state
+    args.validate()?;
+    require!(!ctx.accounts.config.is_paused, LazorKitError::ProgramPaused);
+
+    // 1. Verify the device (and any co-signers, passed as remaining accounts) signed this message
+    //    hash; no device is written, so neither the nonce nor a signature counter is consumed.
+    //    Messages bound to a payer only verify when that payer signs the transaction.
+    let payer = ctx
+        .accounts
+        .payer
+        .as_ref()
+        .map_or(ANY_PAYER, |payer| payer.key());
+    let msg: SignedMessage = check_co_signed_authorization(
+        &ctx.accounts.ix_sysvar,
+        &ctx.accounts.wallet_device,
+        args.primary_assertion(),
+        &args.co_signers,
+        ctx.remaining_accounts,
+        ctx.accounts.smart_wallet.key(),
+        &ctx.accounts.smart_wallet_data,
+        &ctx.accounts.config,
+        payer,
+        ctx.accounts.slot_hashes.as_deref(),
+    )?
+    .message;
+    // A single device of a threshold wallet cannot speak for the wallet
+    ctx.accounts
+        .smart_wallet_data
+        .check_signed_message_approvals(1 + args.co_signers.len())?;
+    require!(
+        msg.message_hash == args.message_hash,
+        LazorKitError::SignedMessageMismatch
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct VerifySignature<'info> {
+    #[account(seeds = [Config::PREFIX_SEED], bump, owner = ID)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        seeds = [crate::constants::SMART_WALLET_SEED, smart_wallet_data.id.to_le_bytes().as_ref()],
+        bump = smart_wallet_data.bump,
+        owner = ID,
+    )]
+    /// CHECK: smart wallet PDA verified by seeds
+    pub smart_wallet: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [SmartWallet::PREFIX_SEED, smart_wallet.key().as_ref()],
+        bump,
+        owner = ID,
+    )]
+    pub smart_wallet_data: Box<Account<'info, SmartWallet>>,
+
+    #[account(owner = ID)]
+    pub wallet_device: Box<Account<'info, WalletDevice>>,
+
+    /// CHECK: instruction sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub ix_sysvar: UncheckedAccount<'info>,
+
+    /// CHECK: SlotHashes sysvar, only needed for challenges bound to a recent slot hash
+    #[account(address = anchor_lang::solana_program::sysvar::slot_hashes::ID)]
+    pub slot_hashes: Option<UncheckedAccount<'info>>,
+
+    /// Signer a payer-bound message is bound to; without it only unbound messages verify
+    pub payer: Option<Signer<'info>>,
+}
@@ -4,6 +4,7 @@ pub mod constants;
 pub mod error;
 pub mod events;
 pub mod instructions;
+pub mod secp256r1;
 pub mod security;
 pub mod state;
 pub mod utils;
@@ -19,8 +20,8 @@ pub mod lazorkit {
     use super::*;
 
     /// Initialize the program by creating the sequence tracker
-    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
-        instructions::initialize(ctx)
+    pub fn initialize(ctx: Context<Initialize>, deployment_tag: u64) -> Result<()> {
+        instructions::initialize(ctx, deployment_tag)
     }
 
     /// Update the program configuration
@@ -32,6 +33,30 @@ pub mod lazorkit {
         instructions::update_config(ctx, param, value)
     }
 
+    /// Replace the default list of allowed WebAuthn origin hashes
+    pub fn update_allowed_origins(
+        ctx: Context<UpdateConfig>,
+        origin_hashes: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::update_allowed_origins(ctx, origin_hashes)
+    }
+
+    /// Set the default relying party ID hash expected in authenticatorData
+    pub fn update_rp_id_hash(
+        ctx: Context<UpdateConfig>,
+        rp_id_hash: Option<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::update_rp_id_hash(ctx, rp_id_hash)
+    }
+
+    /// Replace the default list of authenticator AAGUIDs that may be enrolled
+    pub fn update_allowed_aaguids(
+        ctx: Context<UpdateConfig>,
+        aaguids: Vec<[u8; 16]>,
+    ) -> Result<()> {
+        instructions::update_allowed_aaguids(ctx, aaguids)
+    }
+
     /// Create a new smart wallet with passkey authentication
     pub fn create_smart_wallet(
         ctx: Context<CreateSmartWallet>,
@@ -40,6 +65,33 @@ pub mod lazorkit {
         instructions::create_smart_wallet(ctx, args)
     }
 
+    /// Open an additional nonce lane so operations can be pending in parallel
+    pub fn create_nonce_lane(ctx: Context<CreateNonceLane>, lane: u64) -> Result<()> {
+        instructions::create_nonce_lane(ctx, lane)
+    }
+
+    /// Create the device registry of a wallet created before device registries existed; wallets
+    /// without a tracked device count set it from the device-approved list
+    pub fn create_device_registry<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, CreateDeviceRegistry<'info>>,
+        args: CreateDeviceRegistryArgs,
+    ) -> Result<()> {
+        instructions::create_device_registry(ctx, args)
+    }
+
+    /// Claim the passkey index of a device registered before unique passkeys were enforced
+    pub fn claim_passkey_index(
+        ctx: Context<ClaimPasskeyIndex>,
+        passkey_proof: DeviceAssertion,
+    ) -> Result<()> {
+        instructions::claim_passkey_index(ctx, passkey_proof)
+    }
+
+    /// Grow an account created before fields were appended to its layout
+    pub fn migrate_account(ctx: Context<MigrateAccount>) -> Result<()> {
+        instructions::migrate_account(ctx)
+    }
+
     /// Add a program to the policy program registry
     pub fn register_policy_program(ctx: Context<RegisterPolicyProgram>) -> Result<()> {
         instructions::register_policy_program(ctx)
@@ -66,6 +118,42 @@ pub mod lazorkit {
         instructions::execute_transaction(ctx, args)
     }
 
+    /// Replace the per-wallet security settings, authorized by a wallet device
+    pub fn update_wallet_settings<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, UpdateWalletSettings<'info>>,
+        args: UpdateWalletSettingsArgs,
+    ) -> Result<()> {
+        instructions::update_wallet_settings(ctx, args)
+    }
+
+    /// Remove a wallet device, authorized by another device of the same wallet. The policy
+    /// program is notified through its `remove_device` instruction.
+    pub fn remove_wallet_device<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, RemoveWalletDevice<'info>>,
+        args: RemoveWalletDeviceArgs,
+    ) -> Result<()> {
+        instructions::remove_wallet_device(ctx, args)
+    }
+
+    /// Cancel a signed intent before a relayer executes it, authorized by a wallet device
+    pub fn cancel_intent(ctx: Context<CancelIntent>, args: CancelIntentArgs) -> Result<()> {
+        instructions::cancel_intent(ctx, args)
+    }
+
+    /// Close an intent receipt after the intent expired, refunding its rent
+    pub fn close_intent_receipt(ctx: Context<CloseIntentReceipt>) -> Result<()> {
+        instructions::close_intent_receipt(ctx)
+    }
+
+    /// Check that a wallet's devices, up to its threshold, approved an app-supplied message hash,
+    /// without consuming a nonce. The result is returned as a bool in the return data.
+    pub fn verify_signature<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, VerifySignature<'info>>,
+        args: VerifySignatureArgs,
+    ) -> Result<bool> {
+        instructions::verify_signature(ctx, args)
+    }
+
     pub fn create_transaction_session<'c: 'info, 'info>(
         ctx: Context<'_, '_, 'c, 'info, CreateTransactionSession<'info>>,
         args: CreateSessionArgs,
@@ -0,0 +1,335 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{PASSKEY_SIZE, UNCOMPRESSED_PASSKEY_SIZE},
+    error::LazorKitError,
+};
+
+// Minimal P-256 field arithmetic used to validate passkey public keys at registration.
+// Field elements are 4 little-endian u64 limbs kept in Montgomery form (R = 2^256).
+
+type Fe = [u64; 4];
+
+/// Field prime p = 2^256 - 2^224 + 2^192 + 2^96 - 1
+const P: Fe = [
+    0xffffffffffffffff,
+    0x00000000ffffffff,
+    0x0000000000000000,
+    0xffffffff00000001,
+];
+/// Curve coefficient b of y^2 = x^3 - 3x + b
+const B: Fe = [
+    0x3bce3c3e27d2604b,
+    0x651d06b0cc53b0f6,
+    0xb3ebbd55769886bc,
+    0x5ac635d8aa3a93e7,
+];
+/// R^2 mod p, used to convert into Montgomery form
+const R2: Fe = [
+    0x0000000000000003,
+    0xfffffffbffffffff,
+    0xfffffffffffffffe,
+    0x00000004fffffffd,
+];
+/// (p + 1) / 4; since p = 3 mod 4, a^((p + 1) / 4) is a square root of a when one exists
+const SQRT_EXP: Fe = [
+    0x0000000000000000,
+    0x0000000040000000,
+    0x4000000000000000,
+    0x3fffffffc0000000,
+];
+const ONE: Fe = [1, 0, 0, 0];
+
+/// Validate a 33-byte compressed P-256 public key by decompressing it onto the curve.
+///
+/// The square root takes about 290 field multiplications against 3 for an uncompressed key
+/// ([`compress_uncompressed`]), so clients should register uncompressed keys. Registering a
+/// compressed key must still fit the default 200k compute unit limit of an instruction, which
+/// `tests/client_data_compute.test.ts` checks.
+pub fn validate_compressed(key: &[u8; PASSKEY_SIZE]) -> Result<()> {
+    require!(
+        key[0] == 0x02 || key[0] == 0x03,
+        LazorKitError::InvalidPasskeyFormat
+    );
+    let x = fe_from_be(&key[1..])?;
+    let y = sqrt(&curve_rhs(&x)).ok_or(LazorKitError::PasskeyNotOnCurve)?;
+    // y = 0 has no odd counterpart
+    require!(
+        key[0] == 0x02 || y != [0; 4],
+        LazorKitError::PasskeyNotOnCurve
+    );
+    Ok(())
+}
+
+/// Validate a 65-byte uncompressed (`0x04 || x || y`) P-256 public key and return its
+/// canonical compressed form
+pub fn compress_uncompressed(key: &[u8]) -> Result<[u8; PASSKEY_SIZE]> {
+    require!(
+        key.len() == UNCOMPRESSED_PASSKEY_SIZE && key[0] == 0x04,
+        LazorKitError::InvalidPasskeyFormat
+    );
+    let x = fe_from_be(&key[1..33])?;
+    let y = fe_from_be(&key[33..65])?;
+    require!(
+        mul(&y, &y) == curve_rhs(&x),
+        LazorKitError::PasskeyNotOnCurve
+    );
+
+    let mut compressed = [0u8; PASSKEY_SIZE];
+    compressed[0] = 0x02 | (key[64] & 1);
+    compressed[1..].copy_from_slice(&key[1..33]);
+    Ok(compressed)
+}
+
+/// x^3 - 3x + b in Montgomery form
+fn curve_rhs(x: &Fe) -> Fe {
+    let x3 = mul(&mul(x, x), x);
+    let three_x = add(&add(x, x), x);
+    add(&sub(&x3, &three_x), &mul(&B, &R2))
+}
+
+/// Square root of `a` (Montgomery form), returned in canonical (non-Montgomery) form
+fn sqrt(a: &Fe) -> Option<Fe> {
+    let mut root = mul(&ONE, &R2);
+    for i in (0..256).rev() {
+        root = mul(&root, &root);
+        if (SQRT_EXP[i / 64] >> (i % 64)) & 1 == 1 {
+            root = mul(&root, a);
+        }
+    }
+    (mul(&root, &root) == *a).then(|| mul(&root, &ONE))
+}
+
+/// Parse a 32-byte big-endian integer below p into Montgomery form
+fn fe_from_be(bytes: &[u8]) -> Result<Fe> {
+    let mut fe = [0u64; 4];
+    for (i, chunk) in bytes.chunks_exact(8).enumerate() {
+        fe[3 - i] = u64::from_be_bytes(chunk.try_into().unwrap());
+    }
+    require!(less_than(&fe, &P), LazorKitError::PasskeyNotOnCurve);
+    Ok(mul(&fe, &R2))
+}
+
+fn less_than(a: &Fe, b: &Fe) -> bool {
+    for i in (0..4).rev() {
+        if a[i] != b[i] {
+            return a[i] < b[i];
+        }
+    }
+    false
+}
+
+/// a - b over the integers; returns the borrow
+fn sub_raw(a: &Fe, b: &Fe) -> (Fe, bool) {
+    let mut out = [0u64; 4];
+    let mut borrow = false;
+    for i in 0..4 {
+        let (d, b1) = a[i].overflowing_sub(b[i]);
+        let (d, b2) = d.overflowing_sub(borrow as u64);
+        out[i] = d;
+        borrow = b1 || b2;
+    }
+    (out, borrow)
+}
+
+fn add(a: &Fe, b: &Fe) -> Fe {
+    let mut out = [0u64; 4];
+    let mut carry = false;
+    for i in 0..4 {
+        let (s, c1) = a[i].overflowing_add(b[i]);
+        let (s, c2) = s.overflowing_add(carry as u64);
+        out[i] = s;
+        carry = c1 || c2;
+    }
+    if carry || !less_than(&out, &P) {
+        out = sub_raw(&out, &P).0;
+    }
+    out
+}
+
+fn sub(a: &Fe, b: &Fe) -> Fe {
+    let (out, borrow) = sub_raw(a, b);
+    if borrow {
+        let mut fixed = [0u64; 4];
+        let mut carry = false;
+        for i in 0..4 {
+            let (s, c1) = out[i].overflowing_add(P[i]);
+            let (s, c2) = s.overflowing_add(carry as u64);
+            fixed[i] = s;
+            carry = c1 || c2;
+        }
+        fixed
+    } else {
+        out
+    }
+}
+
+/// Montgomery multiplication a * b * R^-1 mod p (CIOS); -p^-1 mod 2^64 = 1 for P-256
+fn mul(a: &Fe, b: &Fe) -> Fe {
+    let mut t = [0u64; 6];
+    for &bi in b.iter() {
+        let mut carry = 0u128;
+        for j in 0..4 {
+            let v = t[j] as u128 + (a[j] as u128) * (bi as u128) + carry;
+            t[j] = v as u64;
+            carry = v >> 64;
+        }
+        let v = t[4] as u128 + carry;
+        t[4] = v as u64;
+        t[5] = (v >> 64) as u64;
+
+        let m = t[0];
+        let mut carry = (t[0] as u128 + (m as u128) * (P[0] as u128)) >> 64;
+        for j in 1..4 {
+            let v = t[j] as u128 + (m as u128) * (P[j] as u128) + carry;
+            t[j - 1] = v as u64;
+            carry = v >> 64;
+        }
+        let v = t[4] as u128 + carry;
+        t[3] = v as u64;
+        t[4] = t[5] + (v >> 64) as u64;
+        t[5] = 0;
+    }
+
+    let out = [t[0], t[1], t[2], t[3]];
+    if t[4] != 0 || !less_than(&out, &P) {
+        sub_raw(&out, &P).0
+    } else {
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn be(hex: &str) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        for (i, byte) in out.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap();
+        }
+        out
+    }
+
+    const G_X: &str = "6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296";
+    const G_Y: &str = "4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5";
+    const G_Y_NEG: &str = "b01cbd1c01e58065711814b583f061e9d431cca994cea1313449bf97c840ae0a";
+    const G2_X: &str = "7cf27b188d034f7e8a52380304b51ac3c08969e277f21b35a60b48fc47669978";
+    const G2_Y: &str = "07775510db8ed040293d9ac69f7430dbba7dade63ce982299e04b79d227873d1";
+    const P_HEX: &str = "ffffffff00000001000000000000000000000000ffffffffffffffffffffffff";
+
+    fn compressed(prefix: u8, x: &str) -> [u8; PASSKEY_SIZE] {
+        let mut key = [prefix; PASSKEY_SIZE];
+        key[1..].copy_from_slice(&be(x));
+        key
+    }
+
+    fn uncompressed(x: &str, y: &[u8; 32]) -> Vec<u8> {
+        let mut key = vec![0x04];
+        key.extend_from_slice(&be(x));
+        key.extend_from_slice(y);
+        key
+    }
+
+    /// Canonical field element for a small integer
+    fn small(n: u64) -> Fe {
+        mul(&[n, 0, 0, 0], &R2)
+    }
+
+    #[test]
+    fn montgomery_arithmetic() {
+        assert_eq!(mul(&small(6), &ONE), [6, 0, 0, 0]);
+        assert_eq!(mul(&small(2), &small(3)), small(6));
+        // (p - 1)^2 = 1
+        let minus_one = sub(&small(0), &small(1));
+        assert_eq!(mul(&minus_one, &minus_one), small(1));
+        assert_eq!(add(&minus_one, &small(2)), small(1));
+        // 4 has the square roots 2 and p - 2 (canonical form)
+        let root = sqrt(&small(4)).unwrap();
+        assert!(root == [2, 0, 0, 0] || add(&root, &[2, 0, 0, 0]) == [0; 4]);
+    }
+
+    #[test]
+    fn square_root_recovers_generator_y() {
+        let canonical = |hex: &str| mul(&fe_from_be(&be(hex)).unwrap(), &ONE);
+        let root = sqrt(&curve_rhs(&fe_from_be(&be(G_X)).unwrap())).unwrap();
+        assert!(root == canonical(G_Y) || root == canonical(G_Y_NEG));
+    }
+
+    #[test]
+    fn curve_points_validate_with_either_parity() {
+        for x in [G_X, G2_X] {
+            validate_compressed(&compressed(0x02, x)).unwrap();
+            validate_compressed(&compressed(0x03, x)).unwrap();
+        }
+    }
+
+    #[test]
+    fn invalid_compressed_keys_are_rejected() {
+        // x = 1 gives 1 - 3 + b, which is not a square mod p
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        let mut key = [0x02; PASSKEY_SIZE];
+        key[1..].copy_from_slice(&one);
+        assert_eq!(
+            validate_compressed(&key).unwrap_err(),
+            LazorKitError::PasskeyNotOnCurve.into()
+        );
+        // coordinates must be reduced below p
+        for x in [
+            P_HEX,
+            "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+        ] {
+            assert_eq!(
+                validate_compressed(&compressed(0x02, x)).unwrap_err(),
+                LazorKitError::PasskeyNotOnCurve.into()
+            );
+        }
+        for prefix in [0x00, 0x04, 0x05] {
+            assert_eq!(
+                validate_compressed(&compressed(prefix, G_X)).unwrap_err(),
+                LazorKitError::InvalidPasskeyFormat.into()
+            );
+        }
+    }
+
+    #[test]
+    fn uncompressed_keys_are_compressed_by_y_parity() {
+        assert_eq!(
+            compress_uncompressed(&uncompressed(G_X, &be(G_Y))).unwrap(),
+            compressed(0x03, G_X)
+        );
+        assert_eq!(
+            compress_uncompressed(&uncompressed(G_X, &be(G_Y_NEG))).unwrap(),
+            compressed(0x02, G_X)
+        );
+        assert_eq!(
+            compress_uncompressed(&uncompressed(G2_X, &be(G2_Y))).unwrap(),
+            compressed(0x03, G2_X)
+        );
+    }
+
+    #[test]
+    fn invalid_uncompressed_keys_are_rejected() {
+        // y belongs to a different x
+        assert_eq!(
+            compress_uncompressed(&uncompressed(G_X, &be(G2_Y))).unwrap_err(),
+            LazorKitError::PasskeyNotOnCurve.into()
+        );
+        // y = p + (y mod p) is not reduced
+        assert_eq!(
+            compress_uncompressed(&uncompressed(G_X, &be(P_HEX))).unwrap_err(),
+            LazorKitError::PasskeyNotOnCurve.into()
+        );
+        let mut key = uncompressed(G_X, &be(G_Y));
+        key[0] = 0x03;
+        assert_eq!(
+            compress_uncompressed(&key).unwrap_err(),
+            LazorKitError::InvalidPasskeyFormat.into()
+        );
+        assert_eq!(
+            compress_uncompressed(&key[..64]).unwrap_err(),
+            LazorKitError::InvalidPasskeyFormat.into()
+        );
+    }
+}
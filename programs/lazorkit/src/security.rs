@@ -5,6 +5,12 @@ use anchor_lang::prelude::*;
 /// Maximum allowed size for credential ID to prevent DoS
 pub const MAX_CREDENTIAL_ID_SIZE: usize = 256;
 
+/// Maximum allowed size for WebAuthn clientDataJSON
+pub const MAX_CLIENT_DATA_JSON_SIZE: usize = 1024;
+
+/// Maximum allowed size of a decoded challenge payload
+pub const MAX_CHALLENGE_SIZE: usize = 512;
+
 /// Maximum allowed size for policy data
 pub const MAX_POLICY_DATA_SIZE: usize = 1024;
 
@@ -14,12 +20,30 @@ pub const MAX_CPI_DATA_SIZE: usize = 1024;
 /// Maximum allowed remaining accounts
 pub const MAX_REMAINING_ACCOUNTS: usize = 32;
 
+/// Maximum number of allowed WebAuthn origin hashes per list
+pub const MAX_ALLOWED_ORIGINS: usize = 4;
+
+/// Maximum number of allowed authenticator AAGUIDs per config or wallet
+pub const MAX_ALLOWED_AAGUIDS: usize = 8;
+
+/// Maximum allowed size for registration (attestation) authenticatorData
+pub const MAX_ATTESTATION_DATA_SIZE: usize = 1024;
+
+/// Maximum number of device approvals in one threshold authorization
+pub const MAX_THRESHOLD_APPROVALS: usize = 8;
+
+/// Maximum number of devices a wallet's device registry can hold
+pub const MAX_WALLET_DEVICES: usize = 16;
+
 /// Minimum rent-exempt balance buffer (in lamports)
 pub const MIN_RENT_EXEMPT_BUFFER: u64 = 1_000_000; // 0.001 SOL
 
 /// Maximum transaction age in seconds
 pub const MAX_TRANSACTION_AGE: i64 = 300; // 5 minutes
 
+/// Maximum per-wallet drift window for challenge timestamps in seconds
+pub const MAX_WALLET_TIMESTAMP_DRIFT_SECONDS: u32 = 300; // 5 minutes
+
 /// Maximum allowed session TTL in seconds
 pub const MAX_SESSION_TTL_SECONDS: i64 = 30; // 30 seconds
 
@@ -30,7 +54,7 @@ pub const RATE_LIMIT_WINDOW_BLOCKS: u64 = 10;
 /// Security validation functions
 pub mod validation {
     use super::*;
-    use crate::error::LazorKitError;
+    use crate::{constants::AAGUID_SIZE, error::LazorKitError, state::MessageOperation};
 
     /// Validate credential ID size
     pub fn validate_credential_id(credential_id: &[u8]) -> Result<()> {
@@ -74,6 +98,58 @@ pub mod validation {
         Ok(())
     }
 
+    /// Validate allowed origin hash list size
+    pub fn validate_allowed_origins(origin_hashes: &[[u8; 32]]) -> Result<()> {
+        require!(
+            origin_hashes.len() <= MAX_ALLOWED_ORIGINS,
+            LazorKitError::TooManyAllowedOrigins
+        );
+        Ok(())
+    }
+
+    /// Validate a per-wallet timestamp drift window (None = program default)
+    pub fn validate_timestamp_drift(drift_seconds: Option<u32>) -> Result<()> {
+        if let Some(drift_seconds) = drift_seconds {
+            require!(
+                (1..=MAX_WALLET_TIMESTAMP_DRIFT_SECONDS).contains(&drift_seconds),
+                LazorKitError::InvalidTimestampDrift
+            );
+        }
+        Ok(())
+    }
+
+    /// Validate a device approval threshold (0 and 1 both mean a single device) and the
+    /// operations it applies to, so a wallet cannot lock itself out of an operation.
+    ///
+    /// The threshold must not exceed the wallet's devices; wallets whose device count is not
+    /// tracked (`device_count == 0`) cannot require more than one device. Only operations whose
+    /// instructions accept co-signers may require a threshold.
+    pub fn validate_threshold(
+        threshold: u8,
+        threshold_operations: u8,
+        device_count: u16,
+    ) -> Result<()> {
+        require!(
+            usize::from(threshold) <= MAX_THRESHOLD_APPROVALS
+                && u16::from(threshold) <= device_count.max(1),
+            LazorKitError::InvalidThreshold
+        );
+        require!(
+            threshold_operations & !MessageOperation::CO_SIGNED_OPERATIONS == 0,
+            LazorKitError::ThresholdOperationNotSupported
+        );
+        Ok(())
+    }
+
+    /// Validate the number of allowed authenticator AAGUIDs
+    pub fn validate_allowed_aaguids(aaguids: &[[u8; AAGUID_SIZE]]) -> Result<()> {
+        require!(
+            aaguids.len() <= MAX_ALLOWED_AAGUIDS,
+            LazorKitError::TooManyAllowedAaguids
+        );
+        Ok(())
+    }
+
     /// Validate remaining accounts count
     pub fn validate_remaining_accounts(accounts: &[AccountInfo]) -> Result<()> {
         require!(
@@ -133,9 +209,149 @@ pub mod validation {
     pub fn validate_timestamp(timestamp: i64, current_time: i64) -> Result<()> {
         let age = current_time.saturating_sub(timestamp);
         require!(
-            age >= 0 && age <= MAX_TRANSACTION_AGE,
+            (0..=MAX_TRANSACTION_AGE).contains(&age),
             LazorKitError::TransactionTooOld
         );
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::validation::validate_threshold;
+    use crate::{
+        error::LazorKitError,
+        instructions::WalletSettings,
+        state::{MessageOperation, SmartWallet},
+    };
+
+    fn settings_of(wallet: &SmartWallet) -> WalletSettings {
+        WalletSettings {
+            allowed_origin_hashes: wallet.allowed_origin_hashes.clone(),
+            require_user_verification: wallet.require_user_verification,
+            device_bound_operations: wallet.device_bound_operations,
+            allowed_aaguids: wallet.allowed_aaguids.clone(),
+            timestamp_drift_seconds: wallet.timestamp_drift_seconds,
+            threshold: wallet.threshold,
+            threshold_operations: wallet.threshold_operations,
+        }
+    }
+
+    #[test]
+    fn threshold_cannot_exceed_wallet_devices() {
+        validate_threshold(2, MessageOperation::Execute.mask().unwrap(), 2).unwrap();
+        validate_threshold(0, 0, 0).unwrap();
+        validate_threshold(1, MessageOperation::Execute.mask().unwrap(), 0).unwrap();
+        for (threshold, device_count) in [(3, 2), (2, 0), (9, 9)] {
+            assert_eq!(
+                validate_threshold(
+                    threshold,
+                    MessageOperation::Execute.mask().unwrap(),
+                    device_count
+                )
+                .unwrap_err(),
+                LazorKitError::InvalidThreshold.into()
+            );
+        }
+    }
+
+    #[test]
+    fn threshold_operations_must_accept_co_signers() {
+        validate_threshold(2, MessageOperation::CO_SIGNED_OPERATIONS, 3).unwrap();
+        validate_threshold(2, MessageOperation::SignMessage.mask().unwrap(), 3).unwrap();
+        for operations in [
+            MessageOperation::CancelIntent.mask().unwrap(),
+            MessageOperation::Execute.mask().unwrap() | 1,
+        ] {
+            assert_eq!(
+                validate_threshold(2, operations, 3).unwrap_err(),
+                LazorKitError::ThresholdOperationNotSupported.into()
+            );
+        }
+    }
+
+    #[test]
+    fn single_device_cannot_lower_the_threshold() {
+        let wallet = SmartWallet {
+            threshold: 2,
+            threshold_operations: MessageOperation::Execute.mask().unwrap(),
+            device_count: 3,
+            ..Default::default()
+        };
+
+        // other settings only need the operation's own approvals
+        let mut settings = settings_of(&wallet);
+        settings.require_user_verification = true;
+        wallet.check_settings_approvals(&settings, 1).unwrap();
+
+        let lowered = [
+            WalletSettings {
+                threshold: 1,
+                ..settings_of(&wallet)
+            },
+            WalletSettings {
+                threshold_operations: 0,
+                ..settings_of(&wallet)
+            },
+            WalletSettings {
+                device_bound_operations: MessageOperation::Execute.mask().unwrap(),
+                ..settings_of(&wallet)
+            },
+        ];
+        for settings in &lowered {
+            assert_eq!(
+                wallet.check_settings_approvals(settings, 1).unwrap_err(),
+                LazorKitError::ThresholdApprovalRequired.into()
+            );
+            wallet.check_settings_approvals(settings, 2).unwrap();
+        }
+    }
+
+    #[test]
+    fn single_device_cannot_remove_devices_of_a_threshold_wallet() {
+        let wallet = SmartWallet {
+            threshold: 2,
+            device_count: 3,
+            ..Default::default()
+        };
+        assert_eq!(
+            wallet.check_device_removal_approvals(1).unwrap_err(),
+            LazorKitError::ThresholdApprovalRequired.into()
+        );
+        wallet.check_device_removal_approvals(2).unwrap();
+        SmartWallet::default()
+            .check_device_removal_approvals(1)
+            .unwrap();
+    }
+
+    #[test]
+    fn single_device_cannot_approve_messages_for_a_threshold_wallet() {
+        let wallet = SmartWallet {
+            threshold: 2,
+            device_count: 2,
+            ..Default::default()
+        };
+        assert_eq!(
+            wallet.check_signed_message_approvals(1).unwrap_err(),
+            LazorKitError::ThresholdApprovalRequired.into()
+        );
+        wallet.check_signed_message_approvals(2).unwrap();
+    }
+
+    #[test]
+    fn operations_without_a_mask_bit_need_the_threshold() {
+        let wallet = SmartWallet {
+            threshold: 2,
+            threshold_operations: MessageOperation::Execute.mask().unwrap(),
+            device_count: 2,
+            ..Default::default()
+        };
+        assert!(wallet.requires_threshold(MessageOperation::SyncDevices));
+        assert!(!wallet.requires_threshold(MessageOperation::CancelIntent));
+        assert!(!SmartWallet {
+            threshold_operations: 0,
+            ..wallet
+        }
+        .requires_threshold(MessageOperation::SyncDevices));
+    }
+}
@@ -1,5 +1,10 @@
 use anchor_lang::prelude::*;
 
+use crate::{
+    constants::AAGUID_SIZE,
+    security::{MAX_ALLOWED_AAGUIDS, MAX_ALLOWED_ORIGINS, MAX_WALLET_DEVICES},
+};
+
 #[account]
 #[derive(Default, InitSpace)]
 pub struct Config {
@@ -8,10 +13,41 @@ pub struct Config {
     pub execute_fee: u64,
     pub default_policy_program: Pubkey,
     pub is_paused: bool,
+    /// sha256 hashes of WebAuthn origins accepted for wallets without their own list. Passkey
+    /// assertions are rejected while neither list is set.
+    #[max_len(MAX_ALLOWED_ORIGINS)]
+    pub allowed_origin_hashes: Vec<[u8; 32]>,
+    /// sha256 of the relying party ID expected for devices without their own. Passkey
+    /// assertions are rejected while neither is set.
+    pub rp_id_hash: Option<[u8; 32]>,
+    /// Require the user verification (UV) flag on every assertion
+    pub require_user_verification: bool,
+    /// Oldest challenge format still accepted (0 = legacy un-enveloped challenges)
+    pub min_message_version: u8,
+    /// Deployment/cluster identifier mixed into every challenge domain separator (0 only on
+    /// configs migrated from before it existed)
+    pub deployment_tag: u64,
+    /// Authenticator models (AAGUIDs) that may be enrolled in wallets without their own list.
+    /// AAGUIDs are reported by the client without an attestation statement, so this is a filter
+    /// rather than a proof of the authenticator model.
+    #[max_len(MAX_ALLOWED_AAGUIDS)]
+    pub allowed_aaguids: Vec<[u8; AAGUID_SIZE]>,
+    /// Maximum number of devices per wallet (0 = `MAX_WALLET_DEVICES`)
+    pub max_wallet_devices: u8,
+    /// Bind each newly registered passkey to a single wallet through its `PasskeyIndex`
+    pub unique_passkeys: bool,
 }
 
 impl Config {
     pub const PREFIX_SEED: &'static [u8] = b"config";
+
+    /// Maximum number of devices a wallet may hold
+    pub fn wallet_device_limit(&self) -> usize {
+        match self.max_wallet_devices {
+            0 => MAX_WALLET_DEVICES,
+            limit => usize::from(limit),
+        }
+    }
 }
 
 #[derive(Debug, AnchorSerialize, AnchorDeserialize)]
@@ -22,4 +58,9 @@ pub enum UpdateConfigType {
     Admin = 3,
     PauseProgram = 4,
     UnpauseProgram = 5,
+    RequireUserVerification = 6,
+    MinMessageVersion = 7,
+    DeploymentTag = 8,
+    MaxWalletDevices = 9,
+    UniquePasskeys = 10,
 }
@@ -0,0 +1,96 @@
+use anchor_lang::{prelude::*, solana_program::hash::hash};
+
+use crate::{
+    error::LazorKitError,
+    state::{BpfWriter, DeviceKind},
+    utils::{close_pda_account, create_pda_account},
+    ID,
+};
+
+/// Reverse lookup from a passkey credential ID to the wallet_device registered with it, so a
+/// discoverable-credential login can find its wallet on-chain. Seeded by `sha256(credential_id)`.
+///
+/// Only registrations whose authenticatorData attests the credential ID and public key are
+/// indexed, since an unattested credential ID is just a client-supplied label that anyone could
+/// copy. The first attested registration of a credential keeps the lookup until its device is
+/// removed; later ones are registered without it. Credential IDs are not secret, so clients must
+/// still check the device's public key against the assertion they received.
+#[account]
+#[derive(Debug, InitSpace)]
+pub struct CredentialLookup {
+    /// Smart wallet the credential is registered to
+    pub smart_wallet: Pubkey,
+    /// wallet_device PDA holding the credential's public key
+    pub wallet_device: Pubkey,
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+}
+
+impl CredentialLookup {
+    pub const PREFIX_SEED: &'static [u8] = b"credential_lookup";
+
+    /// Point the lookup of a newly registered passkey at its wallet and device if its
+    /// registration was attested and the credential is not indexed yet. Keypair devices have no
+    /// credential ID and are not indexed.
+    #[allow(clippy::too_many_arguments)]
+    pub fn register<'info>(
+        lookup: Option<&AccountInfo<'info>>,
+        payer: AccountInfo<'info>,
+        system_program: AccountInfo<'info>,
+        kind: DeviceKind,
+        attested: bool,
+        credential_id: &[u8],
+        smart_wallet: Pubkey,
+        wallet_device: Pubkey,
+    ) -> Result<()> {
+        if kind != DeviceKind::Passkey || !attested {
+            return Ok(());
+        }
+        let lookup = lookup.ok_or(LazorKitError::CredentialLookupMissing)?;
+        let credential_hash = hash(credential_id).to_bytes();
+        let (address, bump) = Pubkey::find_program_address(
+            &[CredentialLookup::PREFIX_SEED, credential_hash.as_ref()],
+            &ID,
+        );
+        require_keys_eq!(lookup.key(), address, LazorKitError::InvalidPDADerivation);
+        if !lookup.data_is_empty() {
+            msg!("Credential already indexed by lookup: {}", address);
+            return Ok(());
+        }
+
+        create_pda_account(
+            lookup,
+            payer,
+            system_program,
+            &[
+                CredentialLookup::PREFIX_SEED,
+                credential_hash.as_ref(),
+                &[bump],
+            ],
+            8 + CredentialLookup::INIT_SPACE,
+        )?;
+        let dst: &mut [u8] = &mut lookup.try_borrow_mut_data()?;
+        CredentialLookup {
+            smart_wallet,
+            wallet_device,
+            bump,
+        }
+        .try_serialize(&mut BpfWriter::new(dst))
+    }
+
+    /// Close the lookup, refunding its rent, if it still points at `wallet_device`
+    pub fn release<'info>(
+        lookup: &AccountInfo<'info>,
+        wallet_device: &Pubkey,
+        rent_refund: &AccountInfo<'info>,
+    ) -> Result<()> {
+        if lookup.data_is_empty() || lookup.owner != &ID {
+            return Ok(());
+        }
+        let indexed = Self::try_deserialize(&mut &lookup.try_borrow_data()?[..])?;
+        if indexed.wallet_device != *wallet_device {
+            return Ok(());
+        }
+        close_pda_account(lookup, rent_refund)
+    }
+}
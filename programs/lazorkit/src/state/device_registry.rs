@@ -0,0 +1,130 @@
+use anchor_lang::{prelude::*, solana_program::hash::hash};
+
+use crate::{
+    error::LazorKitError,
+    security::MAX_WALLET_DEVICES,
+    state::{BpfWriter, DeviceKind},
+    ID,
+};
+
+/// Whether a registry entry still refers to a live wallet_device
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace,
+)]
+pub enum DeviceStatus {
+    #[default]
+    Active,
+    /// The device was removed; its slot is reused by the next device added
+    Removed,
+}
+
+/// One wallet_device known to a [`DeviceRegistry`]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq, InitSpace)]
+pub struct DeviceEntry {
+    /// wallet_device PDA
+    pub wallet_device: Pubkey,
+    /// sha256 of the device's credential ID (of an empty ID for keypair devices)
+    pub credential_id_hash: [u8; 32],
+    pub kind: DeviceKind,
+    pub status: DeviceStatus,
+}
+
+impl DeviceEntry {
+    pub fn new(wallet_device: Pubkey, kind: DeviceKind, credential_id: &[u8]) -> Self {
+        Self {
+            wallet_device,
+            credential_id_hash: hash(credential_id).to_bytes(),
+            kind,
+            status: DeviceStatus::Active,
+        }
+    }
+}
+
+/// Per-wallet index of wallet_device accounts, so devices can be listed without an indexer.
+/// Kept in sync by every path that adds or removes a device.
+#[account]
+#[derive(Debug, InitSpace)]
+pub struct DeviceRegistry {
+    /// Smart wallet the devices belong to
+    pub smart_wallet: Pubkey,
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+    #[max_len(MAX_WALLET_DEVICES)]
+    pub devices: Vec<DeviceEntry>,
+}
+
+impl DeviceRegistry {
+    pub const PREFIX_SEED: &'static [u8] = b"device_registry";
+
+    /// Number of devices that have not been removed
+    pub fn active_count(&self) -> usize {
+        self.devices
+            .iter()
+            .filter(|entry| entry.status == DeviceStatus::Active)
+            .count()
+    }
+
+    /// Record a new device, reusing the slot of a removed one when possible. The wallet may hold
+    /// at most `max_devices` active devices.
+    pub fn add(&mut self, entry: DeviceEntry, max_devices: usize) -> Result<()> {
+        require!(
+            self.active_count() < max_devices,
+            LazorKitError::MaxWalletLimitReached
+        );
+        match self
+            .devices
+            .iter_mut()
+            .find(|slot| slot.status == DeviceStatus::Removed)
+        {
+            Some(slot) => *slot = entry,
+            None => {
+                require!(
+                    self.devices.len() < MAX_WALLET_DEVICES,
+                    LazorKitError::MaxWalletLimitReached
+                );
+                self.devices.push(entry);
+            }
+        }
+        Ok(())
+    }
+
+    /// Mark an active device as removed
+    pub fn remove(&mut self, wallet_device: &Pubkey) -> Result<()> {
+        let entry = self
+            .devices
+            .iter_mut()
+            .find(|entry| {
+                entry.status == DeviceStatus::Active && entry.wallet_device == *wallet_device
+            })
+            .ok_or(LazorKitError::AuthenticatorNotFound)?;
+        entry.status = DeviceStatus::Removed;
+        Ok(())
+    }
+
+    /// Whether `wallet_device` is counted in `SmartWallet::device_count`: every device is while
+    /// the wallet has no registry, otherwise only those the registry lists as active. Devices
+    /// left out when a legacy wallet listed its devices are not.
+    pub fn counts(info: &AccountInfo, wallet_device: &Pubkey) -> Result<bool> {
+        if info.data_is_empty() {
+            return Ok(true);
+        }
+        require!(info.owner == &ID, LazorKitError::InvalidAccountOwner);
+        let registry = Self::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        Ok(registry.devices.iter().any(|entry| {
+            entry.status == DeviceStatus::Active && entry.wallet_device == *wallet_device
+        }))
+    }
+
+    /// Apply `f` to the registry stored in `info`. Wallets created before registries existed
+    /// may not have one yet; for those this is a no-op.
+    pub fn update(info: &AccountInfo, f: impl FnOnce(&mut Self) -> Result<()>) -> Result<()> {
+        if info.data_is_empty() {
+            return Ok(());
+        }
+        require!(info.owner == &ID, LazorKitError::InvalidAccountOwner);
+        let mut registry = Self::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        f(&mut registry)?;
+        let dst: &mut [u8] = &mut info.try_borrow_mut_data()?;
+        registry.try_serialize(&mut BpfWriter::new(dst))
+    }
+}
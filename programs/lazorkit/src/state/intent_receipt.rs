@@ -0,0 +1,116 @@
+use anchor_lang::prelude::*;
+
+/// Lifecycle of a signed intent in the replay registry
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace,
+)]
+pub enum IntentStatus {
+    /// Receipt was just created and the intent has not been used
+    #[default]
+    Unused,
+    /// The intent was executed and can never run again
+    Executed,
+    /// The wallet cancelled the intent before it was executed
+    Cancelled,
+}
+
+/// Replay registry entry for one signed intent, seeded by the intent hash.
+/// Kept until the intent's validity window has passed, then anyone may close it.
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct IntentReceipt {
+    /// Smart wallet the intent belongs to
+    pub smart_wallet: Pubkey,
+    /// sha256 of the signed intent challenge
+    pub intent_hash: [u8; 32],
+    pub status: IntentStatus,
+    /// Unix timestamp after which the intent can no longer execute and the receipt may be closed
+    pub expires_at: i64,
+    /// Where to refund rent when closing the receipt
+    pub rent_refund_to: Pubkey,
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+}
+
+impl IntentReceipt {
+    pub const PREFIX_SEED: &'static [u8] = b"intent_receipt";
+
+    /// Record the intent as `status`, failing if it was already executed or cancelled
+    pub fn record(
+        &mut self,
+        status: IntentStatus,
+        smart_wallet: Pubkey,
+        intent_hash: [u8; 32],
+        expires_at: i64,
+        rent_refund_to: Pubkey,
+        bump: u8,
+    ) -> Result<()> {
+        match self.status {
+            IntentStatus::Unused => {}
+            IntentStatus::Executed => {
+                return err!(crate::error::LazorKitError::IntentAlreadyExecuted)
+            }
+            IntentStatus::Cancelled => return err!(crate::error::LazorKitError::IntentCancelled),
+        }
+        self.smart_wallet = smart_wallet;
+        self.intent_hash = intent_hash;
+        self.status = status;
+        self.expires_at = expires_at;
+        self.rent_refund_to = rent_refund_to;
+        self.bump = bump;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::LazorKitError;
+
+    fn record(receipt: &mut IntentReceipt, status: IntentStatus) -> Result<()> {
+        receipt.record(
+            status,
+            Pubkey::new_unique(),
+            [1; 32],
+            100,
+            Pubkey::new_unique(),
+            255,
+        )
+    }
+
+    fn unused() -> IntentReceipt {
+        IntentReceipt {
+            smart_wallet: Pubkey::default(),
+            intent_hash: [0; 32],
+            status: IntentStatus::Unused,
+            expires_at: 0,
+            rent_refund_to: Pubkey::default(),
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn executed_intent_cannot_be_replayed() {
+        let mut receipt = unused();
+        record(&mut receipt, IntentStatus::Executed).unwrap();
+        assert_eq!(receipt.status, IntentStatus::Executed);
+        assert_eq!(
+            record(&mut receipt, IntentStatus::Executed).unwrap_err(),
+            LazorKitError::IntentAlreadyExecuted.into()
+        );
+        assert_eq!(
+            record(&mut receipt, IntentStatus::Cancelled).unwrap_err(),
+            LazorKitError::IntentAlreadyExecuted.into()
+        );
+    }
+
+    #[test]
+    fn cancelled_intent_cannot_execute() {
+        let mut receipt = unused();
+        record(&mut receipt, IntentStatus::Cancelled).unwrap();
+        assert_eq!(
+            record(&mut receipt, IntentStatus::Executed).unwrap_err(),
+            LazorKitError::IntentCancelled.into()
+        );
+    }
+}
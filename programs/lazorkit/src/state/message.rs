@@ -1,9 +1,298 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{prelude::*, solana_program::hash::hashv};
 
+/// Default drift window for `current_timestamp` when a wallet does not configure its own
 pub const MAX_TIMESTAMP_DRIFT_SECONDS: i64 = 30;
+/// Furthest ahead of the current slot a `Freshness::ValidUntilSlot` expiry may be (~5 minutes)
+pub const MAX_VALID_UNTIL_SLOTS: u64 = 750;
+/// Longest validity window of a signed intent (`Freshness::Window`), in seconds (30 days)
+pub const MAX_INTENT_WINDOW_SECONDS: i64 = 30 * 24 * 60 * 60;
 
-pub trait Message {
-    fn verify(challenge_bytes: Vec<u8>, last_nonce: u64) -> Result<()>;
+/// Challenge format without an envelope, accepted only while `Config::min_message_version` allows
+pub const LEGACY_MESSAGE_VERSION: u8 = 0;
+/// First enveloped format: `operation (u8) || version (u8) || borsh(message)`
+pub const ENVELOPE_MESSAGE_VERSION: u8 = 1;
+/// Envelope followed by a 32-byte domain separator before the message body
+pub const DOMAIN_SEPARATED_MESSAGE_VERSION: u8 = 2;
+/// Domain-separated envelope followed by the 32-byte fee payer the challenge is bound to
+pub const PAYER_BOUND_MESSAGE_VERSION: u8 = 3;
+/// Payer-bound envelope followed by a borsh [`Freshness`] rule
+pub const FRESHNESS_MESSAGE_VERSION: u8 = 4;
+/// Freshness envelope followed by the nonce lane (u64 LE) the message nonce is sequenced on
+pub const NONCE_LANE_MESSAGE_VERSION: u8 = 5;
+/// Current challenge format produced by clients
+pub const MESSAGE_VERSION: u8 = NONCE_LANE_MESSAGE_VERSION;
+
+/// Nonce lane backed by `SmartWallet::last_nonce`; older envelope versions always use it
+pub const PRIMARY_NONCE_LANE: u64 = 0;
+/// Nonce lane of signed intents: replay protection comes from an `IntentReceipt` keyed by the
+/// challenge hash instead of a sequence number, and the message nonce is a free-form salt
+pub const INTENT_NONCE_LANE: u64 = u64::MAX;
+
+/// Payer binding that lets any fee payer/relayer submit the challenge
+pub const ANY_PAYER: Pubkey = Pubkey::new_from_array([0; 32]);
+
+/// Prefix hashed into every domain separator
+pub const DOMAIN_SEPARATOR_PREFIX: &[u8] = b"lazorkit:challenge";
+
+/// Domain separator binding a challenge to this program, its deployment and one smart wallet:
+/// `sha256(DOMAIN_SEPARATOR_PREFIX || program_id || deployment_tag (u64 LE) || smart_wallet)`
+pub fn domain_separator(deployment_tag: u64, smart_wallet: &Pubkey) -> [u8; 32] {
+    hashv(&[
+        DOMAIN_SEPARATOR_PREFIX,
+        crate::ID.as_ref(),
+        &deployment_tag.to_le_bytes(),
+        smart_wallet.as_ref(),
+    ])
+    .to_bytes()
+}
+
+/// Operation tag leading every versioned challenge, so a challenge signed for one
+/// operation can never be parsed as another
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum MessageOperation {
+    Execute = 1,
+    InvokePolicy = 2,
+    UpdatePolicy = 3,
+    UpdateWalletSettings = 4,
+    CancelIntent = 5,
+    SignMessage = 6,
+    RemoveDevice = 7,
+    SyncDevices = 8,
+}
+
+impl MessageOperation {
+    /// Operations whose instructions accept co-signing devices, and so may require a threshold
+    pub const CO_SIGNED_OPERATIONS: u8 = Self::Execute.bit()
+        | Self::InvokePolicy.bit()
+        | Self::UpdatePolicy.bit()
+        | Self::UpdateWalletSettings.bit()
+        | Self::SignMessage.bit()
+        | Self::RemoveDevice.bit();
+
+    /// Bit identifying this operation in per-wallet operation masks, or `None` for operations
+    /// past bit 7, which cannot be selected in a mask
+    #[inline]
+    pub const fn mask(self) -> Option<u8> {
+        1u8.checked_shl(self as u32)
+    }
+
+    /// Whether `operations` covers this operation. Operations without a bit are covered by any
+    /// non-empty mask, so a wallet restricting some operations restricts them too.
+    #[inline]
+    pub const fn is_in(self, operations: u8) -> bool {
+        match self.mask() {
+            Some(mask) => operations & mask != 0,
+            None => operations != 0,
+        }
+    }
+
+    /// Mask bit for use in constants; fails to compile for operations without one
+    const fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+/// How a challenge proves it is fresh; older envelope versions always use `Timestamp`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Freshness {
+    /// `current_timestamp` must lie within the wallet's drift window of the cluster clock
+    Timestamp,
+    /// The challenge is valid up to and including this slot, independent of device clocks
+    ValidUntilSlot(u64),
+    /// The challenge commits to a recent `(slot, hash)` from the SlotHashes sysvar: it was created
+    /// after that slot and expires once the entry falls out of the sysvar
+    RecentSlotHash { slot: u64, hash: [u8; 32] },
+    /// The challenge is valid between two unix timestamps, for intents signed ahead of time
+    Window { valid_after: i64, valid_until: i64 },
+}
+
+impl Freshness {
+    /// Check this rule against the cluster clock or the SlotHashes sysvar
+    fn check(self, current_timestamp: i64, ctx: &MessageContext) -> Result<()> {
+        match self {
+            Freshness::Timestamp => {
+                let now = Clock::get()?.unix_timestamp;
+                require!(
+                    current_timestamp >= now.saturating_sub(ctx.max_timestamp_drift),
+                    crate::error::LazorKitError::TimestampTooOld
+                );
+                require!(
+                    current_timestamp <= now.saturating_add(ctx.max_timestamp_drift),
+                    crate::error::LazorKitError::TimestampTooNew
+                );
+            }
+            Freshness::ValidUntilSlot(valid_until_slot) => {
+                let slot = Clock::get()?.slot;
+                require!(
+                    slot <= valid_until_slot,
+                    crate::error::LazorKitError::ChallengeExpired
+                );
+                require!(
+                    valid_until_slot <= slot.saturating_add(MAX_VALID_UNTIL_SLOTS),
+                    crate::error::LazorKitError::ChallengeValidityTooLong
+                );
+            }
+            Freshness::RecentSlotHash { slot, hash } => {
+                let slot_hashes = ctx
+                    .slot_hashes
+                    .ok_or(crate::error::LazorKitError::SlotHashesSysvarMissing)?;
+                require!(
+                    slot_hashes_contains(slot_hashes, slot, &hash),
+                    crate::error::LazorKitError::SlotHashNotFound
+                );
+            }
+            Freshness::Window {
+                valid_after,
+                valid_until,
+            } => {
+                let now = Clock::get()?.unix_timestamp;
+                require!(
+                    now >= valid_after,
+                    crate::error::LazorKitError::IntentNotYetValid
+                );
+                require!(
+                    now <= valid_until,
+                    crate::error::LazorKitError::ChallengeExpired
+                );
+                require!(
+                    valid_until.saturating_sub(valid_after) <= MAX_INTENT_WINDOW_SECONDS,
+                    crate::error::LazorKitError::ChallengeValidityTooLong
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Look up `(slot, hash)` in raw SlotHashes sysvar data: `len (u64 LE)` followed by
+/// `(slot (u64 LE), hash (32))` entries sorted by descending slot
+fn slot_hashes_contains(data: &[u8], slot: u64, hash: &[u8; 32]) -> bool {
+    const ENTRY_SIZE: usize = 8 + 32;
+    let Some(len) = data.get(..8) else {
+        return false;
+    };
+    let len = u64::from_le_bytes(len.try_into().unwrap()) as usize;
+    let Some(entries) = data.get(8..len.saturating_mul(ENTRY_SIZE).saturating_add(8)) else {
+        return false;
+    };
+    let entry_slot = |i: usize| {
+        let start = i * ENTRY_SIZE;
+        u64::from_le_bytes(entries[start..start + 8].try_into().unwrap())
+    };
+
+    // Binary search over descending slots
+    let (mut lo, mut hi) = (0, len);
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        match entry_slot(mid).cmp(&slot) {
+            core::cmp::Ordering::Equal => {
+                let start = mid * ENTRY_SIZE + 8;
+                return entries[start..start + 32] == hash[..];
+            }
+            core::cmp::Ordering::Greater => lo = mid + 1,
+            core::cmp::Ordering::Less => hi = mid,
+        }
+    }
+    false
+}
+
+/// On-chain state a challenge is checked against
+pub struct MessageContext<'a> {
+    /// Next nonce expected on `nonce_lane`; unused on `INTENT_NONCE_LANE`
+    pub last_nonce: u64,
+    /// Nonce lane the instruction advances, or `INTENT_NONCE_LANE` when it records an intent
+    pub nonce_lane: u64,
+    pub min_version: u8,
+    pub domain_separator: [u8; 32],
+    /// Fee payer of the transaction submitting the challenge
+    pub payer: Pubkey,
+    /// Allowed drift of `current_timestamp` under [`Freshness::Timestamp`], in seconds
+    pub max_timestamp_drift: i64,
+    /// Raw SlotHashes sysvar data, when the instruction was given the sysvar
+    pub slot_hashes: Option<&'a [u8]>,
+}
+
+pub trait Message: Sized {
+    const OPERATION: MessageOperation;
+
+    /// Open the challenge envelope, check freshness and nonce, and return the typed message
+    /// together with the freshness rule it was checked against
+    fn verify(challenge_bytes: &[u8], ctx: &MessageContext) -> Result<(Self, Freshness)>;
+}
+
+/// Strip the challenge envelope and deserialize the body, rejecting trailing bytes.
+/// Legacy (un-enveloped) challenges are accepted only while `ctx.min_version` allows them.
+fn open_envelope<T: AnchorDeserialize>(
+    challenge_bytes: &[u8],
+    operation: MessageOperation,
+    ctx: &MessageContext,
+) -> Result<(T, Freshness)> {
+    let enveloped = open_versioned(challenge_bytes, operation, ctx);
+    if enveloped.is_err()
+        && ctx.min_version == LEGACY_MESSAGE_VERSION
+        && ctx.nonce_lane == PRIMARY_NONCE_LANE
+    {
+        if let Ok(msg) = T::try_from_slice(challenge_bytes) {
+            return Ok((msg, Freshness::Timestamp));
+        }
+    }
+    enveloped
+}
+
+fn open_versioned<T: AnchorDeserialize>(
+    challenge_bytes: &[u8],
+    operation: MessageOperation,
+    ctx: &MessageContext,
+) -> Result<(T, Freshness)> {
+    let [tag, version, rest @ ..] = challenge_bytes else {
+        return err!(crate::error::LazorKitError::ChallengeDeserializationError);
+    };
+    require!(
+        *tag == operation as u8,
+        crate::error::LazorKitError::MessageOperationMismatch
+    );
+    require!(
+        (ctx.min_version.max(ENVELOPE_MESSAGE_VERSION)..=MESSAGE_VERSION).contains(version),
+        crate::error::LazorKitError::UnsupportedMessageVersion
+    );
+    let mut body = rest;
+    if *version >= DOMAIN_SEPARATED_MESSAGE_VERSION {
+        require!(
+            body.get(..32) == Some(&ctx.domain_separator[..]),
+            crate::error::LazorKitError::DomainSeparatorMismatch
+        );
+        body = &body[32..];
+    }
+    if *version >= PAYER_BOUND_MESSAGE_VERSION {
+        let payer = body
+            .get(..32)
+            .ok_or(crate::error::LazorKitError::ChallengeDeserializationError)?;
+        require!(
+            payer == ANY_PAYER.as_ref() || payer == ctx.payer.as_ref(),
+            crate::error::LazorKitError::PayerMismatch
+        );
+        body = &body[32..];
+    }
+    let freshness = if *version >= FRESHNESS_MESSAGE_VERSION {
+        Freshness::deserialize(&mut body)
+            .map_err(|_| crate::error::LazorKitError::ChallengeDeserializationError)?
+    } else {
+        Freshness::Timestamp
+    };
+    let nonce_lane = if *version >= NONCE_LANE_MESSAGE_VERSION {
+        u64::deserialize(&mut body)
+            .map_err(|_| crate::error::LazorKitError::ChallengeDeserializationError)?
+    } else {
+        PRIMARY_NONCE_LANE
+    };
+    require!(
+        nonce_lane == ctx.nonce_lane,
+        crate::error::LazorKitError::NonceLaneMismatch
+    );
+    let msg = T::try_from_slice(body)
+        .map_err(|_| crate::error::LazorKitError::ChallengeDeserializationError)?;
+    Ok((msg, freshness))
 }
 
 #[derive(Default, AnchorSerialize, AnchorDeserialize, Debug)]
@@ -34,29 +323,221 @@ pub struct UpdatePolicyMessage {
     pub new_policy_accounts_hash: [u8; 32],
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Default, Clone)]
+pub struct UpdateWalletSettingsMessage {
+    pub nonce: u64,
+    pub current_timestamp: i64,
+    pub settings_hash: [u8; 32],
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Default, Clone)]
+pub struct CancelIntentMessage {
+    pub nonce: u64,
+    pub current_timestamp: i64,
+    pub intent_hash: [u8; 32],
+    /// `valid_until` of the cancelled intent; its receipt is kept until then
+    pub valid_until: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Default, Clone)]
+pub struct RemoveDeviceMessage {
+    pub nonce: u64,
+    pub current_timestamp: i64,
+    /// wallet_device PDA being removed
+    pub wallet_device: Pubkey,
+    /// Account receiving the removed device's rent
+    pub rent_refund: Pubkey,
+    pub policy_data_hash: [u8; 32],
+    pub policy_accounts_hash: [u8; 32],
+}
+
+/// Approval of the complete device list of a wallet that does not track its device count yet
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Default, Clone)]
+pub struct SyncDevicesMessage {
+    pub nonce: u64,
+    pub current_timestamp: i64,
+    /// sha256 of the wallet_device PDAs, concatenated in the order they are passed
+    pub devices_hash: [u8; 32],
+}
+
+/// Arbitrary app message approved by the wallet, checked by `verify_signature`
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Default, Clone)]
+pub struct SignedMessage {
+    pub message_hash: [u8; 32],
+}
+
+impl Message for SignedMessage {
+    const OPERATION: MessageOperation = MessageOperation::SignMessage;
+
+    fn verify(challenge_bytes: &[u8], ctx: &MessageContext) -> Result<(Self, Freshness)> {
+        // Always enveloped: a bare 32-byte legacy challenge is not bound to any operation
+        let (msg, freshness): (Self, Freshness) =
+            open_versioned(challenge_bytes, Self::OPERATION, ctx)?;
+        // Like an ERC-1271 signature, a signed message carries no nonce or timestamp and stays
+        // valid unless the signer bound it to a slot or time window
+        if freshness != Freshness::Timestamp {
+            freshness.check(0, ctx)?;
+        }
+        Ok((msg, freshness))
+    }
+}
+
+/// Implement [`Message`] for a nonce-bearing challenge. Operations that predate the envelope
+/// open challenges with `open_envelope`; operations added later pass `open_versioned` so a
+/// legacy body can never be mistaken for them.
 macro_rules! impl_message_verify {
-    ($t:ty) => {
+    ($t:ty, $op:expr) => {
+        impl_message_verify!($t, $op, open_envelope);
+    };
+    ($t:ty, $op:expr, $open:ident) => {
         impl Message for $t {
-            fn verify(challenge_bytes: Vec<u8>, last_nonce: u64) -> Result<()> {
-                let hdr: $t = AnchorDeserialize::deserialize(&mut &challenge_bytes[..])
-                    .map_err(|_| crate::error::LazorKitError::ChallengeDeserializationError)?;
-                let now = Clock::get()?.unix_timestamp;
-                if hdr.current_timestamp < now.saturating_sub(MAX_TIMESTAMP_DRIFT_SECONDS) {
-                    return Err(crate::error::LazorKitError::TimestampTooOld.into());
-                }
-                if hdr.current_timestamp > now.saturating_add(MAX_TIMESTAMP_DRIFT_SECONDS) {
-                    return Err(crate::error::LazorKitError::TimestampTooNew.into());
+            const OPERATION: MessageOperation = $op;
+
+            fn verify(challenge_bytes: &[u8], ctx: &MessageContext) -> Result<(Self, Freshness)> {
+                let (hdr, freshness): ($t, Freshness) =
+                    $open(challenge_bytes, Self::OPERATION, ctx)?;
+                freshness.check(hdr.current_timestamp, ctx)?;
+                if ctx.nonce_lane == INTENT_NONCE_LANE {
+                    // Intents are replay-protected by their receipt, which lives until the
+                    // end of the window
+                    require!(
+                        matches!(freshness, Freshness::Window { .. }),
+                        crate::error::LazorKitError::IntentWindowRequired
+                    );
+                } else {
+                    require!(
+                        hdr.nonce == ctx.last_nonce,
+                        crate::error::LazorKitError::NonceMismatch
+                    );
                 }
-                require!(
-                    hdr.nonce == last_nonce,
-                    crate::error::LazorKitError::NonceMismatch
-                );
-                Ok(())
+                Ok((hdr, freshness))
             }
         }
     };
 }
 
-impl_message_verify!(ExecuteMessage);
-impl_message_verify!(InvokePolicyMessage);
-impl_message_verify!(UpdatePolicyMessage);
+impl_message_verify!(ExecuteMessage, MessageOperation::Execute);
+impl_message_verify!(InvokePolicyMessage, MessageOperation::InvokePolicy);
+impl_message_verify!(UpdatePolicyMessage, MessageOperation::UpdatePolicy);
+impl_message_verify!(
+    UpdateWalletSettingsMessage,
+    MessageOperation::UpdateWalletSettings,
+    open_versioned
+);
+impl_message_verify!(
+    CancelIntentMessage,
+    MessageOperation::CancelIntent,
+    open_versioned
+);
+impl_message_verify!(
+    RemoveDeviceMessage,
+    MessageOperation::RemoveDevice,
+    open_versioned
+);
+impl_message_verify!(
+    SyncDevicesMessage,
+    MessageOperation::SyncDevices,
+    open_versioned
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Raw SlotHashes data for entries sorted by descending slot
+    fn slot_hashes(slots: &[u64]) -> Vec<u8> {
+        let mut data = (slots.len() as u64).to_le_bytes().to_vec();
+        for slot in slots {
+            data.extend_from_slice(&slot.to_le_bytes());
+            data.extend_from_slice(&[*slot as u8; 32]);
+        }
+        data
+    }
+
+    #[test]
+    fn recent_slot_hash_is_found() {
+        let data = slot_hashes(&[105, 104, 102, 101, 100]);
+        for slot in [105, 104, 102, 101, 100] {
+            assert!(slot_hashes_contains(&data, slot, &[slot as u8; 32]));
+        }
+    }
+
+    #[test]
+    fn slot_hash_must_match_its_slot() {
+        let data = slot_hashes(&[105, 104, 102]);
+        assert!(!slot_hashes_contains(&data, 104, &[105; 32]));
+        assert!(!slot_hashes_contains(&data, 103, &[103; 32]));
+    }
+
+    #[test]
+    fn aged_out_or_future_slots_are_rejected() {
+        let data = slot_hashes(&[105, 104, 102]);
+        assert!(!slot_hashes_contains(&data, 101, &[101; 32]));
+        assert!(!slot_hashes_contains(&data, 106, &[106; 32]));
+    }
+
+    #[test]
+    fn malformed_sysvar_data_is_rejected() {
+        let mut data = slot_hashes(&[105, 104]);
+        assert!(!slot_hashes_contains(&[], 105, &[105; 32]));
+        assert!(!slot_hashes_contains(&data[..4], 105, &[105; 32]));
+        // length claims more entries than present
+        data[..8].copy_from_slice(&3u64.to_le_bytes());
+        assert!(!slot_hashes_contains(&data, 105, &[105; 32]));
+    }
+
+    /// Context of a program that still accepts legacy (un-enveloped) challenges
+    fn legacy_context() -> MessageContext<'static> {
+        MessageContext {
+            last_nonce: 0,
+            nonce_lane: PRIMARY_NONCE_LANE,
+            min_version: LEGACY_MESSAGE_VERSION,
+            domain_separator: [0; 32],
+            payer: Pubkey::default(),
+            max_timestamp_drift: 30,
+            slot_hashes: None,
+        }
+    }
+
+    #[test]
+    fn legacy_challenges_only_open_for_legacy_operations() {
+        let ctx = legacy_context();
+        let legacy = ExecuteMessage::default().try_to_vec().unwrap();
+        assert!(open_envelope::<ExecuteMessage>(&legacy, MessageOperation::Execute, &ctx).is_ok());
+
+        let legacy = UpdateWalletSettingsMessage::default().try_to_vec().unwrap();
+        assert_eq!(
+            UpdateWalletSettingsMessage::verify(&legacy, &ctx).unwrap_err(),
+            crate::error::LazorKitError::MessageOperationMismatch.into()
+        );
+        let legacy = CancelIntentMessage::default().try_to_vec().unwrap();
+        assert_eq!(
+            CancelIntentMessage::verify(&legacy, &ctx).unwrap_err(),
+            crate::error::LazorKitError::MessageOperationMismatch.into()
+        );
+        // same length as a legacy ExecuteMessage
+        let legacy = RemoveDeviceMessage::default().try_to_vec().unwrap();
+        assert_eq!(
+            legacy.len(),
+            ExecuteMessage::default().try_to_vec().unwrap().len()
+        );
+        assert_eq!(
+            RemoveDeviceMessage::verify(&legacy, &ctx).unwrap_err(),
+            crate::error::LazorKitError::MessageOperationMismatch.into()
+        );
+        let legacy = SyncDevicesMessage::default().try_to_vec().unwrap();
+        assert_eq!(
+            SyncDevicesMessage::verify(&legacy, &ctx).unwrap_err(),
+            crate::error::LazorKitError::MessageOperationMismatch.into()
+        );
+    }
+
+    #[test]
+    fn operations_past_bit_seven_are_covered_by_any_mask() {
+        assert_eq!(MessageOperation::RemoveDevice.mask(), Some(0x80));
+        assert_eq!(MessageOperation::SyncDevices.mask(), None);
+        assert!(!MessageOperation::SyncDevices.is_in(0));
+        assert!(MessageOperation::SyncDevices.is_in(0x01));
+        assert!(!MessageOperation::RemoveDevice.is_in(0x7f));
+    }
+}
@@ -1,17 +1,27 @@
 mod config;
+mod credential_lookup;
+mod device_registry;
+mod intent_receipt;
 pub mod message;
+mod nonce_lane;
+mod passkey_index;
+mod smart_wallet;
 mod transaction_session;
 mod wallet_device;
-mod smart_wallet;
 // mod smart_wallet_seq;  // No longer needed - using random IDs instead
 mod policy_program_registry;
 mod writer;
 
 pub use config::*;
+pub use credential_lookup::*;
+pub use device_registry::*;
+pub use intent_receipt::*;
 pub use message::*;
+pub use nonce_lane::*;
+pub use passkey_index::*;
+pub use smart_wallet::*;
 pub use transaction_session::*;
 pub use wallet_device::*;
-pub use smart_wallet::*;
 // pub use smart_wallet_seq::*;  // No longer needed - using random IDs instead
 pub use policy_program_registry::*;
 pub use writer::*;
@@ -0,0 +1,20 @@
+use anchor_lang::prelude::*;
+
+/// Independent nonce sequence for one lane of a smart wallet, so operations approved on
+/// different lanes can be pending at the same time without invalidating each other
+#[account]
+#[derive(Default, InitSpace)]
+pub struct NonceLane {
+    /// Smart wallet this lane belongs to
+    pub smart_wallet: Pubkey,
+    /// Lane key chosen by the client; `PRIMARY_NONCE_LANE` and `INTENT_NONCE_LANE` are reserved
+    pub lane: u64,
+    /// Next sequence number expected on this lane
+    pub sequence: u64,
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+}
+
+impl NonceLane {
+    pub const PREFIX_SEED: &'static [u8] = b"nonce_lane";
+}
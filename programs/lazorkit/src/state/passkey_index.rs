@@ -0,0 +1,124 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::hash::{hash, hashv},
+};
+
+use crate::{
+    constants::PASSKEY_SIZE,
+    error::LazorKitError,
+    instructions::DeviceAssertion,
+    state::{BpfWriter, Config, DeviceKind, SmartWallet, WalletDevice},
+    utils::{close_pda_account, create_pda_account, verify_passkey_index_proof},
+    ID,
+};
+
+/// Prefix hashed into the challenge a passkey signs to claim its index
+pub const PASSKEY_INDEX_CHALLENGE_PREFIX: &[u8] = b"lazorkit:passkey_index";
+
+/// Global claim on a passkey public key, seeded by `sha256(passkey_pubkey)`. While
+/// `Config::unique_passkeys` is on, a passkey can only be registered to the wallet holding its
+/// index, and only with an assertion by the passkey itself so that a key cannot be claimed by
+/// someone who merely knows it.
+///
+/// Passkeys registered while the mode was off have no index until they claim one with
+/// `claim_passkey_index`. If such a passkey was registered to several wallets, the first to claim
+/// keeps the index; the other registrations are not undone.
+#[account]
+#[derive(Debug, InitSpace)]
+pub struct PasskeyIndex {
+    /// Smart wallet the passkey is bound to
+    pub smart_wallet: Pubkey,
+    /// wallet_device PDA holding the passkey
+    pub wallet_device: Pubkey,
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+}
+
+impl PasskeyIndex {
+    pub const PREFIX_SEED: &'static [u8] = b"passkey_index";
+
+    /// Seed of the index of a compressed passkey public key
+    pub fn seed_hash(passkey_pubkey: &[u8; PASSKEY_SIZE]) -> [u8; 32] {
+        hash(passkey_pubkey).to_bytes()
+    }
+
+    /// Challenge a passkey signs to claim its index for `smart_wallet`:
+    /// `sha256(PASSKEY_INDEX_CHALLENGE_PREFIX || program_id || deployment_tag (u64 LE) ||
+    /// smart_wallet || passkey_pubkey)`
+    pub fn claim_challenge(
+        deployment_tag: u64,
+        smart_wallet: &Pubkey,
+        passkey_pubkey: &[u8; PASSKEY_SIZE],
+    ) -> [u8; 32] {
+        hashv(&[
+            PASSKEY_INDEX_CHALLENGE_PREFIX,
+            ID.as_ref(),
+            &deployment_tag.to_le_bytes(),
+            smart_wallet.as_ref(),
+            passkey_pubkey,
+        ])
+        .to_bytes()
+    }
+
+    /// Bind the passkey of `device` to its wallet and device, failing if another device already
+    /// holds it. `proof` must be an assertion by the passkey over [`Self::claim_challenge`]. A
+    /// no-op unless the config enforces unique passkeys.
+    #[allow(clippy::too_many_arguments)]
+    pub fn claim<'info>(
+        index: Option<&AccountInfo<'info>>,
+        payer: AccountInfo<'info>,
+        system_program: AccountInfo<'info>,
+        ix_sysvar: &AccountInfo,
+        config: &Config,
+        wallet: &SmartWallet,
+        device: &WalletDevice,
+        wallet_device: Pubkey,
+        proof: Option<&DeviceAssertion>,
+    ) -> Result<()> {
+        if !config.unique_passkeys || device.kind != DeviceKind::Passkey {
+            return Ok(());
+        }
+        let index = index.ok_or(LazorKitError::PasskeyIndexMissing)?;
+        let passkey_hash = Self::seed_hash(&device.passkey_pubkey);
+        let (address, bump) =
+            Pubkey::find_program_address(&[Self::PREFIX_SEED, passkey_hash.as_ref()], &ID);
+        require_keys_eq!(index.key(), address, LazorKitError::InvalidPDADerivation);
+        require!(
+            index.data_is_empty(),
+            LazorKitError::PasskeyAlreadyRegistered
+        );
+        let proof = proof.ok_or(LazorKitError::PasskeyIndexProofMissing)?;
+        verify_passkey_index_proof(ix_sysvar, device, wallet, config, proof)?;
+
+        create_pda_account(
+            index,
+            payer,
+            system_program,
+            &[Self::PREFIX_SEED, passkey_hash.as_ref(), &[bump]],
+            8 + PasskeyIndex::INIT_SPACE,
+        )?;
+        let dst: &mut [u8] = &mut index.try_borrow_mut_data()?;
+        PasskeyIndex {
+            smart_wallet: device.smart_wallet,
+            wallet_device,
+            bump,
+        }
+        .try_serialize(&mut BpfWriter::new(dst))
+    }
+
+    /// Close the index, refunding its rent, if it binds the passkey to `wallet_device`
+    pub fn release<'info>(
+        index: &AccountInfo<'info>,
+        wallet_device: &Pubkey,
+        rent_refund: &AccountInfo<'info>,
+    ) -> Result<()> {
+        if index.data_is_empty() || index.owner != &ID {
+            return Ok(());
+        }
+        let indexed = Self::try_deserialize(&mut &index.try_borrow_data()?[..])?;
+        if indexed.wallet_device != *wallet_device {
+            return Ok(());
+        }
+        close_pda_account(index, rent_refund)
+    }
+}
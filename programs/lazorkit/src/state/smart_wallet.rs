@@ -1,5 +1,13 @@
 use anchor_lang::prelude::*;
 
+use crate::{
+    constants::AAGUID_SIZE,
+    error::LazorKitError,
+    instructions::WalletSettings,
+    security::{MAX_ALLOWED_AAGUIDS, MAX_ALLOWED_ORIGINS},
+    state::{Config, MessageOperation, MAX_TIMESTAMP_DRIFT_SECONDS},
+};
+
 /// Data account for a smart wallet
 #[account]
 #[derive(Default, InitSpace)]
@@ -12,8 +20,124 @@ pub struct SmartWallet {
     pub last_nonce: u64,
     /// Bump seed for PDA derivation
     pub bump: u8,
+    /// sha256 hashes of WebAuthn origins accepted for this wallet (empty = use config default)
+    #[max_len(MAX_ALLOWED_ORIGINS)]
+    pub allowed_origin_hashes: Vec<[u8; 32]>,
+    /// Require the user verification (UV) flag regardless of the config setting
+    pub require_user_verification: bool,
+    /// Bitmask of [`MessageOperation::mask`] values that only a device-bound passkey may approve;
+    /// operations without a bit are included whenever any bit is set
+    ///
+    /// [`MessageOperation::mask`]: crate::state::MessageOperation::mask
+    pub device_bound_operations: u8,
+    /// Authenticator models (AAGUIDs) that may be enrolled (empty = use config default)
+    #[max_len(MAX_ALLOWED_AAGUIDS)]
+    pub allowed_aaguids: Vec<[u8; AAGUID_SIZE]>,
+    /// Drift window for challenge timestamps in seconds (None = program default)
+    pub timestamp_drift_seconds: Option<u32>,
+    /// Number of distinct devices that must approve operations in `threshold_operations`
+    pub threshold: u8,
+    /// Bitmask of [`MessageOperation::mask`] values that need `threshold` device approvals;
+    /// operations without a bit are included whenever any bit is set
+    ///
+    /// [`MessageOperation::mask`]: crate::state::MessageOperation::mask
+    pub threshold_operations: u8,
+    /// Number of wallet_device accounts registered to this wallet (0 = not tracked, for wallets
+    /// migrated from a layout without this field; such wallets cannot add or remove devices until
+    /// their devices are listed with `create_device_registry`)
+    pub device_count: u16,
 }
 
 impl SmartWallet {
     pub const PREFIX_SEED: &'static [u8] = b"smart_wallet_data";
+
+    /// Origin hashes that apply to this wallet, falling back to the config default
+    pub fn effective_origin_hashes<'a>(&'a self, config: &'a Config) -> &'a [[u8; 32]] {
+        if self.allowed_origin_hashes.is_empty() {
+            &config.allowed_origin_hashes
+        } else {
+            &self.allowed_origin_hashes
+        }
+    }
+
+    /// Drift window applied to challenge timestamps for this wallet, in seconds
+    pub fn timestamp_drift(&self) -> i64 {
+        self.timestamp_drift_seconds
+            .map_or(MAX_TIMESTAMP_DRIFT_SECONDS, i64::from)
+    }
+
+    /// Whether `operation` needs approval from more than one device
+    pub fn requires_threshold(&self, operation: MessageOperation) -> bool {
+        self.threshold > 1 && operation.is_in(self.threshold_operations)
+    }
+
+    /// Check that `approvals` distinct devices meet the wallet's threshold
+    fn check_threshold_approvals(&self, approvals: usize) -> Result<()> {
+        require!(
+            approvals >= usize::from(self.threshold.max(1)),
+            LazorKitError::ThresholdApprovalRequired
+        );
+        Ok(())
+    }
+
+    /// Check that enough devices approved adding a device. Enrolling a device always needs the
+    /// wallet's threshold, whatever `threshold_operations` says, so that one device cannot add a
+    /// second device to co-sign with.
+    pub fn check_device_add_approvals(&self, approvals: usize) -> Result<()> {
+        self.check_threshold_approvals(approvals)
+    }
+
+    /// Check that enough devices approved removing a device. Like enrolling, revoking always
+    /// needs the wallet's threshold, so that one device cannot strip the wallet of its co-signers.
+    pub fn check_device_removal_approvals(&self, approvals: usize) -> Result<()> {
+        self.check_threshold_approvals(approvals)
+    }
+
+    /// Check that enough devices approved a message checked by `verify_signature`. A message only
+    /// counts as approved by the wallet once its threshold approved it, whatever
+    /// `threshold_operations` says.
+    pub fn check_signed_message_approvals(&self, approvals: usize) -> Result<()> {
+        self.check_threshold_approvals(approvals)
+    }
+
+    /// Check that enough devices approved new settings. Changing the threshold, the operations
+    /// it covers or the device-bound operations always needs the current threshold, so that one
+    /// device cannot switch the threshold off.
+    pub fn check_settings_approvals(
+        &self,
+        settings: &WalletSettings,
+        approvals: usize,
+    ) -> Result<()> {
+        if settings.threshold == self.threshold
+            && settings.threshold_operations == self.threshold_operations
+            && settings.device_bound_operations == self.device_bound_operations
+        {
+            return Ok(());
+        }
+        self.check_threshold_approvals(approvals)
+    }
+
+    /// Count a device added to the wallet, keeping it within `max_devices`. Wallets whose device
+    /// count is not tracked must list their devices with `create_device_registry` first.
+    pub fn count_device_added(&mut self, max_devices: usize) -> Result<()> {
+        require!(self.device_count > 0, LazorKitError::DeviceCountNotTracked);
+        require!(
+            usize::from(self.device_count) < max_devices,
+            LazorKitError::MaxWalletLimitReached
+        );
+        self.device_count = self
+            .device_count
+            .checked_add(1)
+            .ok_or(LazorKitError::IntegerOverflow)?;
+        Ok(())
+    }
+
+    /// AAGUIDs that may be enrolled in this wallet, falling back to the config default
+    pub fn effective_aaguids<'a>(&'a self, config: &'a Config) -> &'a [[u8; AAGUID_SIZE]] {
+        if self.allowed_aaguids.is_empty() {
+            &config.allowed_aaguids
+        } else {
+            &self.allowed_aaguids
+        }
+    }
 }
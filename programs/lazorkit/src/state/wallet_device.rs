@@ -1,16 +1,102 @@
 use crate::{
-    constants::PASSKEY_SIZE, error::LazorKitError, state::BpfWriter, utils::PasskeyExt as _, ID,
+    constants::{
+        AAGUID_SIZE, ED25519_PUBKEY_SIZE, ETH_ADDRESS_SIZE, PASSKEY_SIZE, UNCOMPRESSED_PASSKEY_SIZE,
+    },
+    error::LazorKitError,
+    secp256r1,
+    state::BpfWriter,
+    utils::{create_pda_account, PasskeyExt as _},
+    ID,
 };
 use anchor_lang::{
     prelude::*,
-    system_program::{create_account, CreateAccount},
+    solana_program::hash::{hash, hashv},
 };
 
-/// Account that stores a wallet_device (passkey) used to authenticate to a smart wallet
+/// Key type a wallet_device authenticates with
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace,
+)]
+pub enum DeviceKind {
+    /// WebAuthn passkey (compressed secp256r1 public key)
+    #[default]
+    Passkey,
+    /// Native Solana ed25519 keypair (32-byte public key, zero-padded to `PASSKEY_SIZE`)
+    Ed25519,
+    /// Ethereum-style secp256k1 key identified by its 20-byte address (zero-padded)
+    Secp256k1,
+}
+
+impl DeviceKind {
+    /// Bring a registration key into the stored `PASSKEY_SIZE` layout.
+    ///
+    /// Passkeys may be given compressed or uncompressed (65 bytes, checked against the curve and
+    /// then compressed); ed25519 keys and Ethereum addresses may be given at their natural length
+    /// and are zero-padded. Keys already in the stored layout are copied unchecked.
+    pub fn normalize_key(self, key: &[u8]) -> Result<[u8; PASSKEY_SIZE]> {
+        let mut out = [0u8; PASSKEY_SIZE];
+        match (self, key.len()) {
+            (_, PASSKEY_SIZE) => out.copy_from_slice(key),
+            (DeviceKind::Passkey, UNCOMPRESSED_PASSKEY_SIZE) => {
+                out = secp256r1::compress_uncompressed(key)?
+            }
+            (DeviceKind::Passkey, _) => return err!(LazorKitError::InvalidPasskeyFormat),
+            (DeviceKind::Ed25519, ED25519_PUBKEY_SIZE)
+            | (DeviceKind::Secp256k1, ETH_ADDRESS_SIZE) => out[..key.len()].copy_from_slice(key),
+            _ => return err!(LazorKitError::InvalidDeviceKeyFormat),
+        }
+        Ok(out)
+    }
+
+    /// Validate a registration key and return it in the canonical stored layout. Passkeys are
+    /// checked to lie on the P-256 curve, which is far cheaper for uncompressed keys (see
+    /// [`secp256r1::validate_compressed`]).
+    pub fn canonical_key(self, key: &[u8]) -> Result<[u8; PASSKEY_SIZE]> {
+        let normalized = self.normalize_key(key)?;
+        // Uncompressed passkeys were already checked against the curve while normalizing
+        if self != DeviceKind::Passkey || key.len() != UNCOMPRESSED_PASSKEY_SIZE {
+            self.validate_key(&normalized)?;
+        }
+        Ok(normalized)
+    }
+
+    /// Check that a stored-layout `key` is well-formed for this kind of device
+    pub fn validate_key(self, key: &[u8; PASSKEY_SIZE]) -> Result<()> {
+        match self {
+            DeviceKind::Passkey => secp256r1::validate_compressed(key)?,
+            DeviceKind::Ed25519 => require!(
+                key[PASSKEY_SIZE - 1] == 0,
+                LazorKitError::InvalidDeviceKeyFormat
+            ),
+            DeviceKind::Secp256k1 => require!(
+                key[ETH_ADDRESS_SIZE..].iter().all(|b| *b == 0),
+                LazorKitError::InvalidDeviceKeyFormat
+            ),
+        }
+        Ok(())
+    }
+
+    /// Hash used as the last PDA seed of a wallet_device. Non-passkey keys are domain-separated
+    /// by kind so that they can never collide with another device of the same wallet.
+    pub fn seed_hash(self, key: &[u8; PASSKEY_SIZE], wallet: Pubkey) -> [u8; 32] {
+        match self {
+            DeviceKind::Passkey => key.to_hashed_bytes(wallet),
+            DeviceKind::Ed25519 => {
+                hashv(&[b"ed25519", &key[..PASSKEY_SIZE - 1], wallet.as_ref()]).to_bytes()
+            }
+            DeviceKind::Secp256k1 => {
+                hashv(&[b"secp256k1", &key[..ETH_ADDRESS_SIZE], wallet.as_ref()]).to_bytes()
+            }
+        }
+    }
+}
+
+/// Account that stores a wallet_device (passkey or keypair) used to authenticate to a smart wallet
 #[account]
 #[derive(Debug, InitSpace)]
 pub struct WalletDevice {
-    /// The public key of the passkey for this wallet_device that can authorize transactions
+    /// The public key of this wallet_device that can authorize transactions. Ed25519 keys occupy
+    /// the first 32 bytes and Ethereum addresses the first 20 bytes; the rest is zero.
     pub passkey_pubkey: [u8; PASSKEY_SIZE],
     /// The smart wallet this wallet_device belongs to
     pub smart_wallet: Pubkey,
@@ -21,6 +107,29 @@ pub struct WalletDevice {
 
     /// Bump seed for PDA derivation
     pub bump: u8,
+
+    // Fields below were appended after the initial release; zero-filled bytes of a migrated
+    // account decode to their legacy defaults.
+    /// Key type of `passkey_pubkey`
+    pub kind: DeviceKind,
+
+    /// sha256 of the relying party ID this passkey was created for (None = use config)
+    pub rp_id_hash: Option<[u8; 32]>,
+
+    /// Last WebAuthn signature counter seen for this passkey (0 = authenticator has no counter)
+    pub sign_count: u32,
+
+    /// WebAuthn backup eligibility (BE) flag; fixed for the lifetime of a credential
+    pub backup_eligible: bool,
+    /// WebAuthn backup state (BS) flag as of the last assertion
+    pub backup_state: bool,
+
+    /// Authenticator model reported in the registration authenticatorData, if supplied
+    pub aaguid: Option<[u8; AAGUID_SIZE]>,
+
+    /// Whether `backup_eligible` has been recorded. Migrated legacy passkeys record it from their
+    /// first assertion.
+    pub backup_eligibility_recorded: bool,
 }
 
 impl WalletDevice {
@@ -36,38 +145,69 @@ impl WalletDevice {
         WalletDevice::try_serialize(self, &mut writer)
     }
 
+    /// Check registration-time backup flags: only passkeys carry them, and a credential can only
+    /// be backed up (BS) if it is backup eligible (BE)
+    pub fn validate_backup_flags(
+        kind: DeviceKind,
+        backup_eligible: bool,
+        backup_state: bool,
+    ) -> Result<()> {
+        require!(
+            (kind == DeviceKind::Passkey || !backup_eligible) && (backup_eligible || !backup_state),
+            LazorKitError::InvalidBackupFlags
+        );
+        Ok(())
+    }
+
+    /// sha256 of the credential ID, the seed of the device's [`CredentialLookup`]
+    ///
+    /// [`CredentialLookup`]: crate::state::CredentialLookup
+    pub fn credential_id_hash(&self) -> [u8; 32] {
+        hash(&self.credential_id).to_bytes()
+    }
+
+    /// Whether this device is a passkey that cannot be synced off its authenticator
+    #[inline]
+    pub fn is_device_bound(&self) -> bool {
+        self.kind == DeviceKind::Passkey
+            && self.backup_eligibility_recorded
+            && !self.backup_eligible
+    }
+
+    /// Create the wallet_device account. `passkey_pubkey` must come from
+    /// [`DeviceKind::canonical_key`], which validates it; checking a compressed passkey again
+    /// would repeat its costly decompression.
+    #[allow(clippy::too_many_arguments)]
     pub fn init<'info>(
         wallet_device: &'info AccountInfo<'info>,
         payer: AccountInfo<'info>,
         system_program: AccountInfo<'info>,
         smart_wallet: Pubkey,
+        kind: DeviceKind,
         passkey_pubkey: [u8; PASSKEY_SIZE],
         credential_id: Vec<u8>,
+        rp_id_hash: Option<[u8; 32]>,
+        backup_eligible: bool,
+        backup_state: bool,
+        aaguid: Option<[u8; AAGUID_SIZE]>,
     ) -> Result<()> {
-        let a = passkey_pubkey.to_hashed_bytes(smart_wallet);
+        Self::validate_backup_flags(kind, backup_eligible, backup_state)?;
+        let a = kind.seed_hash(&passkey_pubkey, smart_wallet);
         if wallet_device.data_is_empty() {
             // Create the seeds and bump for PDA address calculation
             let seeds: &[&[u8]] = &[WalletDevice::PREFIX_SEED, smart_wallet.as_ref(), a.as_ref()];
-            let (_, bump) = Pubkey::find_program_address(&seeds, &ID);
+            let (_, bump) = Pubkey::find_program_address(seeds, &ID);
             let seeds_signer = &mut seeds.to_vec();
             let binding = [bump];
             seeds_signer.push(&binding);
 
-            let space: u64 = (8 + WalletDevice::INIT_SPACE) as u64;
-
-            // Create account if it doesn't exist
-            create_account(
-                CpiContext::new(
-                    system_program,
-                    CreateAccount {
-                        from: payer,
-                        to: wallet_device.clone(),
-                    },
-                )
-                .with_signer(&[seeds_signer]),
-                Rent::get()?.minimum_balance(space.try_into().unwrap()),
-                space,
-                &ID,
+            // Create account if it doesn't exist; the address may already hold lamports
+            create_pda_account(
+                wallet_device,
+                payer,
+                system_program,
+                seeds_signer,
+                8 + WalletDevice::INIT_SPACE,
             )?;
 
             let mut auth = WalletDevice::from(wallet_device);
@@ -77,10 +217,17 @@ impl WalletDevice {
                 smart_wallet,
                 credential_id,
                 bump,
+                kind,
+                rp_id_hash,
+                sign_count: 0,
+                backup_eligible,
+                backup_state,
+                aaguid,
+                backup_eligibility_recorded: true,
             });
             auth.serialize(auth.to_account_info())
         } else {
-            return err!(LazorKitError::WalletDeviceAlreadyInitialized);
+            err!(LazorKitError::WalletDeviceAlreadyInitialized)
         }
     }
 }
@@ -1,18 +1,30 @@
-use crate::constants::{PASSKEY_SIZE, SECP256R1_ID};
-use crate::state::{ExecuteMessage, InvokePolicyMessage, UpdatePolicyMessage};
+use crate::constants::{
+    AAGUID_SIZE, AUTHENTICATOR_DATA_HEADER_SIZE, AUTH_FLAG_ATTESTED_CREDENTIAL_DATA,
+    AUTH_FLAG_BACKUP_ELIGIBLE, AUTH_FLAG_BACKUP_STATE, AUTH_FLAG_USER_PRESENT,
+    AUTH_FLAG_USER_VERIFIED, AUTO_VERIFY_INSTRUCTION_INDEX, EIP191_MESSAGE_PREFIX,
+    ETH_ADDRESS_SIZE, PASSKEY_SIZE, SECP256R1_HALF_ORDER, SECP256R1_ID, UNCOMPRESSED_PASSKEY_SIZE,
+    WEBAUTHN_GET_TYPE,
+};
+use crate::state::{
+    CancelIntentMessage, ExecuteMessage, InvokePolicyMessage, RemoveDeviceMessage,
+    SyncDevicesMessage, UpdatePolicyMessage, UpdateWalletSettingsMessage,
+};
 use crate::{error::LazorKitError, ID};
-use anchor_lang::solana_program::{
-    instruction::Instruction,
-    program::invoke_signed,
+use anchor_lang::solana_program::{instruction::Instruction, program::invoke_signed};
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        ed25519_program::ID as ED25519_ID,
+        hash::{hash, hashv},
+        secp256k1_program::ID as SECP256K1_ID,
+    },
 };
-use anchor_lang::{prelude::*, solana_program::hash::hash};
 
-// Constants for Secp256r1 signature verification
-const SECP_HEADER_SIZE: u16 = 14;
-const SECP_DATA_START: u16 = 2 + SECP_HEADER_SIZE;
+// Constants for signature precompile verification
+const SECP_OFFSETS_START: usize = 2;
+const SECP_OFFSETS_SIZE: usize = 14;
+const SECP_CURRENT_INSTRUCTION: u16 = u16::MAX;
 const SECP_PUBKEY_SIZE: u16 = 33;
-const SECP_SIGNATURE_SIZE: u16 = 64;
-const SECP_HEADER_TOTAL: usize = 16;
 
 /// Convenience wrapper to pass PDA seeds & bump into [`execute_cpi`].
 ///
@@ -91,82 +103,847 @@ fn create_cpi_instruction(
     }
 }
 
-/// Verify a Secp256r1 signature instruction
-pub fn verify_secp256r1_instruction(
+/// Signature precompiles whose instructions can authorize a wallet_device
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SignatureScheme {
+    Secp256r1,
+    Ed25519,
+}
+
+impl SignatureScheme {
+    #[inline]
+    fn program_id(self) -> Pubkey {
+        match self {
+            SignatureScheme::Secp256r1 => SECP256R1_ID,
+            SignatureScheme::Ed25519 => ED25519_ID,
+        }
+    }
+
+    #[inline]
+    fn invalid_length(self) -> LazorKitError {
+        match self {
+            SignatureScheme::Secp256r1 => LazorKitError::Secp256r1InvalidLength,
+            SignatureScheme::Ed25519 => LazorKitError::Ed25519InvalidInstruction,
+        }
+    }
+
+    #[inline]
+    fn header_mismatch(self) -> LazorKitError {
+        match self {
+            SignatureScheme::Secp256r1 => LazorKitError::Secp256r1HeaderMismatch,
+            SignatureScheme::Ed25519 => LazorKitError::Ed25519InvalidInstruction,
+        }
+    }
+
+    #[inline]
+    fn data_mismatch(self) -> LazorKitError {
+        match self {
+            SignatureScheme::Secp256r1 => LazorKitError::Secp256r1DataMismatch,
+            SignatureScheme::Ed25519 => LazorKitError::Ed25519DataMismatch,
+        }
+    }
+
+    #[inline]
+    fn not_found(self) -> LazorKitError {
+        match self {
+            SignatureScheme::Secp256r1 => LazorKitError::Secp256r1InstructionNotFound,
+            SignatureScheme::Ed25519 => LazorKitError::Ed25519InstructionNotFound,
+        }
+    }
+}
+
+/// Verify that a signature precompile instruction covers `(pubkey, sig, msg)`.
+///
+/// The precompile may carry several signatures and may reference data stored in other
+/// instructions of the transaction. Its offsets table is walked until an entry is found whose
+/// public key, signature and message all match; the runtime has already verified every entry.
+pub fn verify_precompile_instruction(
+    scheme: SignatureScheme,
+    ix_sysvar: &AccountInfo,
     ix: &Instruction,
-    pubkey: [u8; SECP_PUBKEY_SIZE as usize],
-    msg: Vec<u8>,
-    sig: Vec<u8>,
+    pubkey: &[u8],
+    msg: &[u8],
+    sig: &[u8],
 ) -> Result<()> {
-    let expected_len =
-        (SECP_DATA_START + SECP_PUBKEY_SIZE + SECP_SIGNATURE_SIZE) as usize + msg.len();
-    if ix.program_id != SECP256R1_ID || !ix.accounts.is_empty() || ix.data.len() != expected_len {
-        return Err(LazorKitError::Secp256r1InvalidLength.into());
+    if ix.program_id != scheme.program_id() || !ix.accounts.is_empty() {
+        return Err(scheme.invalid_length().into());
+    }
+    let num_signatures = *ix.data.first().ok_or(scheme.invalid_length())? as usize;
+    if num_signatures == 0 {
+        return Err(scheme.header_mismatch().into());
+    }
+    let table_end = SECP_OFFSETS_START + num_signatures * SECP_OFFSETS_SIZE;
+    if ix.data.len() < table_end {
+        return Err(scheme.invalid_length().into());
+    }
+
+    for entry in ix.data[SECP_OFFSETS_START..table_end].chunks_exact(SECP_OFFSETS_SIZE) {
+        let offsets = SecpOffsets::from_bytes(entry);
+        if offsets.msg_len as usize == msg.len()
+            && secp_field_matches(
+                ix_sysvar,
+                &ix.data,
+                offsets.pubkey_ix_index,
+                offsets.pubkey_offset,
+                pubkey,
+            )?
+            && secp_field_matches(
+                ix_sysvar,
+                &ix.data,
+                offsets.sig_ix_index,
+                offsets.sig_offset,
+                sig,
+            )?
+            && secp_field_matches(
+                ix_sysvar,
+                &ix.data,
+                offsets.msg_ix_index,
+                offsets.msg_offset,
+                msg,
+            )?
+        {
+            return Ok(());
+        }
     }
-    verify_secp256r1_data(&ix.data, pubkey, msg, sig)
+
+    Err(scheme.data_mismatch().into())
 }
 
-/// Verify the data portion of a Secp256r1 signature
-fn verify_secp256r1_data(
-    data: &[u8],
-    public_key: [u8; SECP_PUBKEY_SIZE as usize],
-    message: Vec<u8>,
-    signature: Vec<u8>,
+/// Verify `(pubkey, sig, msg)` against the precompile instruction at `verify_instruction_index`.
+///
+/// When the index is [`AUTO_VERIFY_INSTRUCTION_INDEX`] the instructions sysvar is scanned for
+/// any instruction of `scheme` that covers the expected data, so clients do not need to account
+/// for compute-budget or memo instructions prepended by wallets and relayers.
+pub fn locate_and_verify_precompile(
+    scheme: SignatureScheme,
+    ix_sysvar: &AccountInfo,
+    verify_instruction_index: u8,
+    pubkey: &[u8],
+    msg: &[u8],
+    sig: &[u8],
 ) -> Result<()> {
-    let msg_len = message.len() as u16;
-    let offsets = calculate_secp_offsets(msg_len);
+    use anchor_lang::solana_program::sysvar::instructions::load_instruction_at_checked;
 
-    if !verify_secp_header(data, &offsets) {
-        return Err(LazorKitError::Secp256r1HeaderMismatch.into());
+    if verify_instruction_index != AUTO_VERIFY_INSTRUCTION_INDEX {
+        let verify_ix = load_instruction_at_checked(verify_instruction_index as usize, ix_sysvar)?;
+        return verify_precompile_instruction(scheme, ix_sysvar, &verify_ix, pubkey, msg, sig);
     }
 
-    if !verify_secp_data(data, &public_key, &signature, &message) {
-        return Err(LazorKitError::Secp256r1DataMismatch.into());
+    let num_instructions = {
+        let data = ix_sysvar.try_borrow_data()?;
+        let count = data.get(..2).ok_or(scheme.not_found())?;
+        u16::from_le_bytes([count[0], count[1]]) as usize
+    };
+    for index in 0..num_instructions {
+        let ix = load_instruction_at_checked(index, ix_sysvar)?;
+        if ix.program_id == scheme.program_id()
+            && verify_precompile_instruction(scheme, ix_sysvar, &ix, pubkey, msg, sig).is_ok()
+        {
+            return Ok(());
+        }
     }
 
+    Err(scheme.not_found().into())
+}
+
+/// Check that `signer` signed the transaction and is passed to the currently executing
+/// instruction, so a plain transaction signature can stand in for a precompile proof.
+pub fn require_instruction_signer(ix_sysvar: &AccountInfo, signer: &Pubkey) -> Result<()> {
+    use anchor_lang::solana_program::sysvar::instructions::{
+        load_current_index_checked, load_instruction_at_checked,
+    };
+
+    let current = load_current_index_checked(ix_sysvar)?;
+    let ix = load_instruction_at_checked(current as usize, ix_sysvar)?;
+    require!(
+        ix.accounts
+            .iter()
+            .any(|meta| meta.pubkey == *signer && meta.is_signer),
+        LazorKitError::Ed25519SignerMissing
+    );
     Ok(())
 }
 
-/// Calculate offsets for Secp256r1 signature verification
+// Constants for the secp256k1 precompile, whose offsets use u8 instruction indices
+const SECP256K1_OFFSETS_START: usize = 1;
+const SECP256K1_OFFSETS_SIZE: usize = 11;
+const SECP256K1_SIGNATURE_SIZE: usize = 65; // r || s || recovery id
+
+/// Verify that a secp256k1 precompile instruction recovered `eth_address` from `sig` over `msg`.
+///
+/// The precompile hashes `msg` with keccak256 and compares the recovered address against the one
+/// referenced by each offsets entry, so matching all three fields proves the signature.
+pub fn verify_secp256k1_instruction(
+    ix_sysvar: &AccountInfo,
+    ix: &Instruction,
+    eth_address: &[u8],
+    msg: &[u8],
+    sig: &[u8],
+) -> Result<()> {
+    if ix.program_id != SECP256K1_ID || !ix.accounts.is_empty() {
+        return err!(LazorKitError::Secp256k1InvalidInstruction);
+    }
+    let num_signatures = *ix
+        .data
+        .first()
+        .ok_or(LazorKitError::Secp256k1InvalidInstruction)? as usize;
+    let table_end = SECP256K1_OFFSETS_START + num_signatures * SECP256K1_OFFSETS_SIZE;
+    if num_signatures == 0 || ix.data.len() < table_end {
+        return err!(LazorKitError::Secp256k1InvalidInstruction);
+    }
+
+    for entry in ix.data[SECP256K1_OFFSETS_START..table_end].chunks_exact(SECP256K1_OFFSETS_SIZE) {
+        let read = |i: usize| u16::from_le_bytes([entry[i], entry[i + 1]]);
+        if read(8) as usize == msg.len()
+            && secp256k1_field_matches(ix_sysvar, entry[5], read(3), eth_address)?
+            && secp256k1_field_matches(ix_sysvar, entry[2], read(0), sig)?
+            && secp256k1_field_matches(ix_sysvar, entry[10], read(6), msg)?
+        {
+            return Ok(());
+        }
+    }
+
+    err!(LazorKitError::Secp256k1DataMismatch)
+}
+
+/// Verify `(eth_address, sig, msg)` against the secp256k1 instruction at
+/// `verify_instruction_index`, scanning the sysvar when it is [`AUTO_VERIFY_INSTRUCTION_INDEX`].
+pub fn locate_and_verify_secp256k1(
+    ix_sysvar: &AccountInfo,
+    verify_instruction_index: u8,
+    eth_address: &[u8],
+    msg: &[u8],
+    sig: &[u8],
+) -> Result<()> {
+    use anchor_lang::solana_program::sysvar::instructions::load_instruction_at_checked;
+
+    if verify_instruction_index != AUTO_VERIFY_INSTRUCTION_INDEX {
+        let verify_ix = load_instruction_at_checked(verify_instruction_index as usize, ix_sysvar)?;
+        return verify_secp256k1_instruction(ix_sysvar, &verify_ix, eth_address, msg, sig);
+    }
+
+    let num_instructions = {
+        let data = ix_sysvar.try_borrow_data()?;
+        let count = data
+            .get(..2)
+            .ok_or(LazorKitError::Secp256k1InstructionNotFound)?;
+        u16::from_le_bytes([count[0], count[1]]) as usize
+    };
+    for index in 0..num_instructions {
+        let ix = load_instruction_at_checked(index, ix_sysvar)?;
+        if ix.program_id == SECP256K1_ID
+            && verify_secp256k1_instruction(ix_sysvar, &ix, eth_address, msg, sig).is_ok()
+        {
+            return Ok(());
+        }
+    }
+
+    err!(LazorKitError::Secp256k1InstructionNotFound)
+}
+
+/// Secp256k1 offsets always name an absolute instruction index, so the referenced instruction
+/// is loaded from the sysvar even when it is the precompile itself.
+#[inline]
+fn secp256k1_field_matches(
+    ix_sysvar: &AccountInfo,
+    ix_index: u8,
+    offset: u16,
+    expected: &[u8],
+) -> Result<bool> {
+    use anchor_lang::solana_program::sysvar::instructions::load_instruction_at_checked;
+
+    let referenced = load_instruction_at_checked(ix_index as usize, ix_sysvar)?;
+    let range = offset as usize..offset as usize + expected.len();
+    Ok(referenced.data.get(range) == Some(expected))
+}
+
+/// Build the EIP-191 `personal_sign` message for `payload`
+pub fn eip191_message(payload: &[u8]) -> Vec<u8> {
+    let len = payload.len().to_string();
+    let mut message = Vec::with_capacity(EIP191_MESSAGE_PREFIX.len() + len.len() + payload.len());
+    message.extend_from_slice(EIP191_MESSAGE_PREFIX);
+    message.extend_from_slice(len.as_bytes());
+    message.extend_from_slice(payload);
+    message
+}
+
+/// One entry of a signature precompile offsets table (shared by secp256r1 and ed25519)
 #[derive(Debug)]
 struct SecpOffsets {
-    pubkey_offset: u16,
     sig_offset: u16,
+    sig_ix_index: u16,
+    pubkey_offset: u16,
+    pubkey_ix_index: u16,
     msg_offset: u16,
     msg_len: u16,
+    msg_ix_index: u16,
+}
+
+impl SecpOffsets {
+    #[inline]
+    fn from_bytes(entry: &[u8]) -> Self {
+        let read = |i: usize| u16::from_le_bytes([entry[i], entry[i + 1]]);
+        Self {
+            sig_offset: read(0),
+            sig_ix_index: read(2),
+            pubkey_offset: read(4),
+            pubkey_ix_index: read(6),
+            msg_offset: read(8),
+            msg_len: read(10),
+            msg_ix_index: read(12),
+        }
+    }
 }
 
+/// Compare the bytes referenced by a precompile offset against `expected`, loading the
+/// referenced instruction from the sysvar when it is not the precompile itself.
 #[inline]
-fn calculate_secp_offsets(msg_len: u16) -> SecpOffsets {
-    SecpOffsets {
-        pubkey_offset: SECP_DATA_START,
-        sig_offset: SECP_DATA_START + SECP_PUBKEY_SIZE,
-        msg_offset: SECP_DATA_START + SECP_PUBKEY_SIZE + SECP_SIGNATURE_SIZE,
-        msg_len,
+fn secp_field_matches(
+    ix_sysvar: &AccountInfo,
+    current_data: &[u8],
+    ix_index: u16,
+    offset: u16,
+    expected: &[u8],
+) -> Result<bool> {
+    use anchor_lang::solana_program::sysvar::instructions::load_instruction_at_checked;
+
+    let range = offset as usize..offset as usize + expected.len();
+    if ix_index == SECP_CURRENT_INSTRUCTION {
+        return Ok(current_data.get(range) == Some(expected));
     }
+    let referenced = load_instruction_at_checked(ix_index as usize, ix_sysvar)?;
+    Ok(referenced.data.get(range) == Some(expected))
 }
 
+/// Check that a 64-byte `r || s` secp256r1 signature uses the canonical low-S form
 #[inline]
-fn verify_secp_header(data: &[u8], offsets: &SecpOffsets) -> bool {
-    data[0] == 1
-        && u16::from_le_bytes(data[2..=3].try_into().unwrap()) == offsets.sig_offset
-        && u16::from_le_bytes(data[4..=5].try_into().unwrap()) == 0xFFFF
-        && u16::from_le_bytes(data[6..=7].try_into().unwrap()) == offsets.pubkey_offset
-        && u16::from_le_bytes(data[8..=9].try_into().unwrap()) == 0xFFFF
-        && u16::from_le_bytes(data[10..=11].try_into().unwrap()) == offsets.msg_offset
-        && u16::from_le_bytes(data[12..=13].try_into().unwrap()) == offsets.msg_len
-        && u16::from_le_bytes(data[14..=15].try_into().unwrap()) == 0xFFFF
+pub fn is_low_s(signature: &[u8]) -> bool {
+    signature
+        .get(32..64)
+        .is_some_and(|s| s <= &SECP256R1_HALF_ORDER[..])
+}
+
+/// Decoded fixed-size header of WebAuthn authenticatorData
+#[derive(Debug, Clone, Copy)]
+pub struct AuthenticatorData {
+    pub rp_id_hash: [u8; 32],
+    pub flags: u8,
+    pub sign_count: u32,
+}
+
+impl AuthenticatorData {
+    /// Parse the 37-byte header; extensions and attested credential data are ignored
+    pub fn parse(raw: &[u8]) -> Result<Self> {
+        let header = raw
+            .get(..AUTHENTICATOR_DATA_HEADER_SIZE)
+            .ok_or(LazorKitError::AuthenticatorDataTooShort)?;
+        let mut rp_id_hash = [0u8; 32];
+        rp_id_hash.copy_from_slice(&header[..32]);
+        Ok(Self {
+            rp_id_hash,
+            flags: header[32],
+            sign_count: u32::from_be_bytes(header[33..37].try_into().unwrap()),
+        })
+    }
+
+    #[inline]
+    pub fn user_present(&self) -> bool {
+        self.flags & AUTH_FLAG_USER_PRESENT != 0
+    }
+
+    #[inline]
+    pub fn user_verified(&self) -> bool {
+        self.flags & AUTH_FLAG_USER_VERIFIED != 0
+    }
+
+    #[inline]
+    pub fn backup_eligible(&self) -> bool {
+        self.flags & AUTH_FLAG_BACKUP_ELIGIBLE != 0
+    }
+
+    #[inline]
+    pub fn backup_state(&self) -> bool {
+        self.flags & AUTH_FLAG_BACKUP_STATE != 0
+    }
+
+    /// Extract the attested credential data that follows the header of registration
+    /// authenticatorData: aaguid (16) || credentialIdLength (2, big-endian) || credentialId ||
+    /// credentialPublicKey (COSE_Key)
+    pub fn attested_credential<'a>(&self, raw: &'a [u8]) -> Result<AttestedCredential<'a>> {
+        require!(
+            self.flags & AUTH_FLAG_ATTESTED_CREDENTIAL_DATA != 0,
+            LazorKitError::AttestedCredentialDataMissing
+        );
+        let body = &raw[AUTHENTICATOR_DATA_HEADER_SIZE..];
+        let fixed = body
+            .get(..AAGUID_SIZE + 2)
+            .ok_or(LazorKitError::AttestedCredentialDataMissing)?;
+        let mut aaguid = [0u8; AAGUID_SIZE];
+        aaguid.copy_from_slice(&fixed[..AAGUID_SIZE]);
+        let id_len = u16::from_be_bytes([fixed[AAGUID_SIZE], fixed[AAGUID_SIZE + 1]]) as usize;
+        let credential_id = body
+            .get(AAGUID_SIZE + 2..AAGUID_SIZE + 2 + id_len)
+            .ok_or(LazorKitError::AttestedCredentialDataMissing)?;
+        let public_key = cose_p256_public_key(&body[AAGUID_SIZE + 2 + id_len..])?;
+        Ok(AttestedCredential {
+            aaguid,
+            credential_id,
+            public_key,
+        })
+    }
+}
+
+/// Attested credential data from registration authenticatorData
+#[derive(Debug)]
+pub struct AttestedCredential<'a> {
+    pub aaguid: [u8; AAGUID_SIZE],
+    pub credential_id: &'a [u8],
+    /// Credential public key, compressed and checked to lie on the curve
+    pub public_key: [u8; PASSKEY_SIZE],
+}
+
+/// COSE_Key labels and values of an ES256 (ECDSA P-256 with SHA-256) EC2 key
+const COSE_KEY_KTY: i64 = 1;
+const COSE_KEY_ALG: i64 = 3;
+const COSE_KEY_CRV: i64 = -1;
+const COSE_KEY_X: i64 = -2;
+const COSE_KEY_Y: i64 = -3;
+const COSE_KTY_EC2: i64 = 2;
+const COSE_ALG_ES256: i64 = -7;
+const COSE_CRV_P256: i64 = 1;
+/// Upper bound on COSE_Key entries; an EC2 key has five
+const MAX_COSE_KEY_ENTRIES: u8 = 8;
+
+/// Decode the CBOR COSE_Key at the start of `data` as an ES256 public key and return it in
+/// compressed form. Only integer labels with integer or byte string values are accepted.
+fn cose_p256_public_key(data: &[u8]) -> Result<[u8; PASSKEY_SIZE]> {
+    let mut reader = CborReader { data, pos: 0 };
+    let (major, entries) = reader.header()?;
+    require!(
+        major == CBOR_MAP && entries <= MAX_COSE_KEY_ENTRIES as u64,
+        LazorKitError::InvalidCredentialPublicKey
+    );
+
+    let (mut kty, mut alg, mut crv, mut x, mut y) = (None, None, None, None, None);
+    for _ in 0..entries {
+        match reader.int()? {
+            COSE_KEY_KTY => set_once(&mut kty, reader.int()?)?,
+            COSE_KEY_ALG => set_once(&mut alg, reader.int()?)?,
+            COSE_KEY_CRV => set_once(&mut crv, reader.int()?)?,
+            COSE_KEY_X => set_once(&mut x, reader.bytes()?)?,
+            COSE_KEY_Y => set_once(&mut y, reader.bytes()?)?,
+            _ => reader.skip_scalar()?,
+        }
+    }
+    require!(
+        kty == Some(COSE_KTY_EC2) && alg == Some(COSE_ALG_ES256) && crv == Some(COSE_CRV_P256),
+        LazorKitError::InvalidCredentialPublicKey
+    );
+    let (x, y) = x.zip(y).ok_or(LazorKitError::InvalidCredentialPublicKey)?;
+    require!(
+        x.len() == 32 && y.len() == 32,
+        LazorKitError::InvalidCredentialPublicKey
+    );
+
+    let mut uncompressed = [0u8; UNCOMPRESSED_PASSKEY_SIZE];
+    uncompressed[0] = 0x04;
+    uncompressed[1..33].copy_from_slice(x);
+    uncompressed[33..].copy_from_slice(y);
+    crate::secp256r1::compress_uncompressed(&uncompressed)
 }
 
+/// Store a COSE_Key parameter, rejecting duplicate labels
 #[inline]
-fn verify_secp_data(data: &[u8], public_key: &[u8], signature: &[u8], message: &[u8]) -> bool {
-    let pubkey_range = SECP_HEADER_TOTAL..SECP_HEADER_TOTAL + SECP_PUBKEY_SIZE as usize;
-    let sig_range = pubkey_range.end..pubkey_range.end + SECP_SIGNATURE_SIZE as usize;
-    let msg_range = sig_range.end..;
+fn set_once<T>(slot: &mut Option<T>, value: T) -> Result<()> {
+    require!(slot.is_none(), LazorKitError::InvalidCredentialPublicKey);
+    *slot = Some(value);
+    Ok(())
+}
+
+const CBOR_UNSIGNED: u8 = 0;
+const CBOR_NEGATIVE: u8 = 1;
+const CBOR_BYTES: u8 = 2;
+const CBOR_TEXT: u8 = 3;
+const CBOR_MAP: u8 = 5;
+
+/// Minimal CBOR reader for the definite-length items found in a COSE_Key
+struct CborReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> CborReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.data.len())
+            .ok_or(LazorKitError::InvalidCredentialPublicKey)?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    /// Read an item header and return its major type and argument
+    fn header(&mut self) -> Result<(u8, u64)> {
+        let initial = self.take(1)?[0];
+        let argument = match initial & 0x1f {
+            info @ 0..=23 => info as u64,
+            24 => self.take(1)?[0] as u64,
+            25 => u16::from_be_bytes(self.take(2)?.try_into().unwrap()) as u64,
+            26 => u32::from_be_bytes(self.take(4)?.try_into().unwrap()) as u64,
+            27 => u64::from_be_bytes(self.take(8)?.try_into().unwrap()),
+            // indefinite lengths and reserved values never appear in a COSE_Key
+            _ => return err!(LazorKitError::InvalidCredentialPublicKey),
+        };
+        Ok((initial >> 5, argument))
+    }
+
+    fn int(&mut self) -> Result<i64> {
+        let (major, argument) = self.header()?;
+        let argument =
+            i64::try_from(argument).map_err(|_| LazorKitError::InvalidCredentialPublicKey)?;
+        match major {
+            CBOR_UNSIGNED => Ok(argument),
+            CBOR_NEGATIVE => Ok(-1 - argument),
+            _ => err!(LazorKitError::InvalidCredentialPublicKey),
+        }
+    }
+
+    fn bytes(&mut self) -> Result<&'a [u8]> {
+        let (major, len) = self.header()?;
+        require!(
+            major == CBOR_BYTES,
+            LazorKitError::InvalidCredentialPublicKey
+        );
+        self.take(len as usize)
+    }
+
+    /// Skip an integer or string value; nested values never appear in an EC2 key
+    fn skip_scalar(&mut self) -> Result<()> {
+        match self.header()? {
+            (CBOR_UNSIGNED | CBOR_NEGATIVE, _) => Ok(()),
+            (CBOR_BYTES | CBOR_TEXT, len) => self.take(len as usize).map(|_| ()),
+            _ => err!(LazorKitError::InvalidCredentialPublicKey),
+        }
+    }
+}
+
+/// Check a device being enrolled against the AAGUID allowlist and return the AAGUID to record.
+///
+/// When registration authenticatorData is supplied it must describe the same credential, public
+/// key, relying party and backup flags as the registration arguments. A non-empty allowlist only
+/// admits passkeys whose registration data reports an allowed AAGUID.
+///
+/// No attestation statement is verified, so the AAGUID is whatever the client reports: the
+/// allowlist filters out honest clients using other authenticator models but does not prove the
+/// key lives on an allowed authenticator.
+#[allow(clippy::too_many_arguments)]
+pub fn verify_device_registration(
+    allowed_aaguids: &[[u8; AAGUID_SIZE]],
+    expected_rp_id_hash: Option<[u8; 32]>,
+    kind: crate::state::DeviceKind,
+    passkey_pubkey: &[u8; PASSKEY_SIZE],
+    credential_id: &[u8],
+    backup_eligible: bool,
+    backup_state: bool,
+    attestation_authenticator_data: Option<&[u8]>,
+) -> Result<Option<[u8; AAGUID_SIZE]>> {
+    let aaguid = match attestation_authenticator_data {
+        Some(raw) => {
+            require!(
+                kind == crate::state::DeviceKind::Passkey
+                    && raw.len() <= crate::security::MAX_ATTESTATION_DATA_SIZE,
+                LazorKitError::InvalidInstructionData
+            );
+            let auth_data = AuthenticatorData::parse(raw)?;
+            require!(
+                auth_data.rp_id_hash
+                    == expected_rp_id_hash.ok_or(LazorKitError::RpIdHashNotConfigured)?,
+                LazorKitError::RpIdHashMismatch
+            );
+            require!(auth_data.user_present(), LazorKitError::UserNotPresent);
+            require!(
+                auth_data.backup_eligible() == backup_eligible
+                    && auth_data.backup_state() == backup_state,
+                LazorKitError::AttestationFlagsMismatch
+            );
+            let credential = auth_data.attested_credential(raw)?;
+            require!(
+                credential.credential_id == credential_id
+                    && credential.public_key == *passkey_pubkey,
+                LazorKitError::AttestationCredentialMismatch
+            );
+            Some(credential.aaguid)
+        }
+        None => None,
+    };
+
+    if !allowed_aaguids.is_empty() {
+        require!(
+            aaguid.is_some_and(|aaguid| allowed_aaguids.contains(&aaguid)),
+            LazorKitError::AaguidNotAllowed
+        );
+    }
+    Ok(aaguid)
+}
+
+/// Upper bound on distinct top-level keys tracked while scanning clientDataJSON
+const MAX_CLIENT_DATA_KEYS: usize = 16;
+/// Maximum nesting depth of values skipped while scanning clientDataJSON (e.g. `tokenBinding`)
+const MAX_CLIENT_DATA_DEPTH: usize = 4;
+
+/// Fields of WebAuthn clientDataJSON used during authorization, borrowed from the raw bytes.
+///
+/// String values are returned without their quotes. Browsers never escape characters in these
+/// fields, so values containing escapes are rejected instead of being decoded.
+#[derive(Debug, Default)]
+pub struct ClientData<'a> {
+    pub ty: Option<&'a [u8]>,
+    pub challenge: Option<&'a [u8]>,
+    pub origin: Option<&'a [u8]>,
+    pub cross_origin: bool,
+}
+
+impl<'a> ClientData<'a> {
+    /// Scan clientDataJSON in a single bounded pass without allocating.
+    ///
+    /// Only the top-level object is interpreted; other values are validated and skipped.
+    /// Duplicate keys, malformed escapes and trailing data are rejected.
+    pub fn parse(raw: &'a [u8]) -> Result<Self> {
+        require!(
+            raw.len() <= crate::security::MAX_CLIENT_DATA_JSON_SIZE,
+            LazorKitError::ClientDataJsonParseError
+        );
+        let mut scanner = JsonScanner { data: raw, pos: 0 };
+        let mut client_data = Self::default();
+        let mut seen_keys: [&[u8]; MAX_CLIENT_DATA_KEYS] = [&[]; MAX_CLIENT_DATA_KEYS];
+        let mut seen_len = 0;
+
+        scanner.expect(b'{')?;
+        if !scanner.eat(b'}') {
+            loop {
+                let key = scanner.plain_string()?;
+                require!(
+                    !seen_keys[..seen_len].contains(&key),
+                    LazorKitError::ClientDataDuplicateKey
+                );
+                require!(
+                    seen_len < MAX_CLIENT_DATA_KEYS,
+                    LazorKitError::ClientDataJsonParseError
+                );
+                seen_keys[seen_len] = key;
+                seen_len += 1;
 
-    data[pubkey_range] == public_key[..]
-        && data[sig_range] == signature[..]
-        && data[msg_range] == message[..]
+                scanner.expect(b':')?;
+                match key {
+                    b"type" => client_data.ty = Some(scanner.plain_string()?),
+                    b"challenge" => client_data.challenge = Some(scanner.plain_string()?),
+                    b"origin" => client_data.origin = Some(scanner.plain_string()?),
+                    b"crossOrigin" => client_data.cross_origin = scanner.boolean()?,
+                    _ => scanner.skip_value(0)?,
+                }
+
+                if !scanner.eat(b',') {
+                    scanner.expect(b'}')?;
+                    break;
+                }
+            }
+        }
+        scanner.skip_whitespace();
+        require!(
+            scanner.pos == raw.len(),
+            LazorKitError::ClientDataJsonParseError
+        );
+        Ok(client_data)
+    }
+}
+
+/// Minimal JSON tokenizer over a byte slice used by [`ClientData::parse`]
+struct JsonScanner<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> JsonScanner<'a> {
+    #[inline]
+    fn skip_whitespace(&mut self) {
+        while matches!(self.data.get(self.pos), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    #[inline]
+    fn next_byte(&mut self) -> Result<u8> {
+        let byte = *self
+            .data
+            .get(self.pos)
+            .ok_or(LazorKitError::ClientDataJsonParseError)?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    /// Consume `byte` (after whitespace) if it is next
+    #[inline]
+    fn eat(&mut self, byte: u8) -> bool {
+        self.skip_whitespace();
+        if self.data.get(self.pos) == Some(&byte) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    #[inline]
+    fn expect(&mut self, byte: u8) -> Result<()> {
+        require!(self.eat(byte), LazorKitError::ClientDataJsonParseError);
+        Ok(())
+    }
+
+    /// Consume a literal such as `true` or `null` (after whitespace) if it is next
+    #[inline]
+    fn eat_literal(&mut self, literal: &[u8]) -> bool {
+        self.skip_whitespace();
+        if self.data[self.pos..].starts_with(literal) {
+            self.pos += literal.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Scan a string and return its raw contents, validating escape sequences
+    fn string(&mut self) -> Result<&'a [u8]> {
+        self.expect(b'"')?;
+        let start = self.pos;
+        loop {
+            match self.next_byte()? {
+                b'"' => return Ok(&self.data[start..self.pos - 1]),
+                b'\\' => match self.next_byte()? {
+                    b'"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't' => {}
+                    b'u' => {
+                        for _ in 0..4 {
+                            require!(
+                                self.next_byte()?.is_ascii_hexdigit(),
+                                LazorKitError::ClientDataInvalidEscape
+                            );
+                        }
+                    }
+                    _ => return err!(LazorKitError::ClientDataInvalidEscape),
+                },
+                byte if byte < 0x20 => return err!(LazorKitError::ClientDataJsonParseError),
+                _ => {}
+            }
+        }
+    }
+
+    /// Scan a string that must not contain escape sequences
+    #[inline]
+    fn plain_string(&mut self) -> Result<&'a [u8]> {
+        let value = self.string()?;
+        require!(
+            !value.contains(&b'\\'),
+            LazorKitError::ClientDataJsonParseError
+        );
+        Ok(value)
+    }
+
+    #[inline]
+    fn boolean(&mut self) -> Result<bool> {
+        if self.eat_literal(b"true") {
+            Ok(true)
+        } else if self.eat_literal(b"false") {
+            Ok(false)
+        } else {
+            err!(LazorKitError::ClientDataJsonParseError)
+        }
+    }
+
+    /// Consume one or more ASCII digits, returning how many were read
+    #[inline]
+    fn digits(&mut self) -> usize {
+        let start = self.pos;
+        while matches!(self.data.get(self.pos), Some(b'0'..=b'9')) {
+            self.pos += 1;
+        }
+        self.pos - start
+    }
+
+    /// Scan a number following the JSON grammar:
+    /// `-? (0 | [1-9][0-9]*) (. [0-9]+)? ([eE] [+-]? [0-9]+)?`
+    fn number(&mut self) -> Result<()> {
+        if self.data.get(self.pos) == Some(&b'-') {
+            self.pos += 1;
+        }
+        match self.data.get(self.pos) {
+            Some(b'0') => self.pos += 1,
+            Some(b'1'..=b'9') => {
+                self.digits();
+            }
+            _ => return err!(LazorKitError::ClientDataJsonParseError),
+        }
+        if self.data.get(self.pos) == Some(&b'.') {
+            self.pos += 1;
+            require!(self.digits() > 0, LazorKitError::ClientDataJsonParseError);
+        }
+        if matches!(self.data.get(self.pos), Some(b'e' | b'E')) {
+            self.pos += 1;
+            if matches!(self.data.get(self.pos), Some(b'+' | b'-')) {
+                self.pos += 1;
+            }
+            require!(self.digits() > 0, LazorKitError::ClientDataJsonParseError);
+        }
+        Ok(())
+    }
+
+    /// Validate and skip any JSON value, bounding nesting depth
+    fn skip_value(&mut self, depth: usize) -> Result<()> {
+        require!(
+            depth < MAX_CLIENT_DATA_DEPTH,
+            LazorKitError::ClientDataJsonParseError
+        );
+        self.skip_whitespace();
+        match self.data.get(self.pos) {
+            Some(b'"') => self.string().map(|_| ()),
+            Some(b'{') => {
+                self.pos += 1;
+                if self.eat(b'}') {
+                    return Ok(());
+                }
+                loop {
+                    self.string()?;
+                    self.expect(b':')?;
+                    self.skip_value(depth + 1)?;
+                    if !self.eat(b',') {
+                        return self.expect(b'}');
+                    }
+                }
+            }
+            Some(b'[') => {
+                self.pos += 1;
+                if self.eat(b']') {
+                    return Ok(());
+                }
+                loop {
+                    self.skip_value(depth + 1)?;
+                    if !self.eat(b',') {
+                        return self.expect(b']');
+                    }
+                }
+            }
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            _ => {
+                require!(
+                    self.eat_literal(b"true")
+                        || self.eat_literal(b"false")
+                        || self.eat_literal(b"null"),
+                    LazorKitError::ClientDataJsonParseError
+                );
+                Ok(())
+            }
+        }
+    }
 }
 
 /// Extension trait for passkey operations
@@ -205,6 +982,85 @@ pub fn transfer_sol_from_pda(from: &AccountInfo, to: &AccountInfo, amount: u64)
     Ok(())
 }
 
+/// Create a program-owned PDA account of `space` bytes at `info`, funded by `payer`.
+/// `signer_seeds` must include the bump.
+///
+/// Anyone can send lamports to the address before it is created, which would make the system
+/// program's `create_account` fail, so a funded address is topped up to the rent-exempt minimum
+/// and then allocated and assigned instead.
+pub fn create_pda_account<'info>(
+    info: &AccountInfo<'info>,
+    payer: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    signer_seeds: &[&[u8]],
+    space: usize,
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(space);
+    let balance = info.lamports();
+    if balance == 0 {
+        return anchor_lang::system_program::create_account(
+            CpiContext::new(
+                system_program,
+                anchor_lang::system_program::CreateAccount {
+                    from: payer,
+                    to: info.clone(),
+                },
+            )
+            .with_signer(&[signer_seeds]),
+            rent,
+            space as u64,
+            &ID,
+        );
+    }
+
+    let shortfall = rent.saturating_sub(balance);
+    if shortfall > 0 {
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                anchor_lang::system_program::Transfer {
+                    from: payer,
+                    to: info.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+    anchor_lang::system_program::allocate(
+        CpiContext::new(
+            system_program.clone(),
+            anchor_lang::system_program::Allocate {
+                account_to_allocate: info.clone(),
+            },
+        )
+        .with_signer(&[signer_seeds]),
+        space as u64,
+    )?;
+    anchor_lang::system_program::assign(
+        CpiContext::new(
+            system_program,
+            anchor_lang::system_program::Assign {
+                account_to_assign: info.clone(),
+            },
+        )
+        .with_signer(&[signer_seeds]),
+        &ID,
+    )
+}
+
+/// Close a program-owned account, moving its lamports to `destination`
+pub fn close_pda_account(info: &AccountInfo, destination: &AccountInfo) -> Result<()> {
+    let refund = destination
+        .lamports()
+        .checked_add(info.lamports())
+        .ok_or(LazorKitError::IntegerOverflow)?;
+    **destination.try_borrow_mut_lamports()? = refund;
+    **info.try_borrow_mut_lamports()? = 0;
+    info.assign(&anchor_lang::system_program::ID);
+    info.realloc(0, false)?;
+    Ok(())
+}
+
 /// Helper to get sighash for anchor instructions
 pub fn sighash(namespace: &str, name: &str) -> [u8; 8] {
     let preimage = format!("{}:{}", namespace, name);
@@ -215,6 +1071,20 @@ pub fn sighash(namespace: &str, name: &str) -> [u8; 8] {
     out
 }
 
+/// Signed hash of policy data, binding the device enrolled alongside it: `sha256(policy_data)`,
+/// or `sha256(sha256(policy_data) || sha256(borsh(new_wallet_device)))` when one is enrolled
+pub fn policy_data_hash(
+    policy_data: &[u8],
+    new_wallet_device: Option<&crate::instructions::NewWalletDeviceArgs>,
+) -> Result<[u8; 32]> {
+    let data_hash = hash(policy_data);
+    let Some(new_wallet_device) = new_wallet_device else {
+        return Ok(data_hash.to_bytes());
+    };
+    let device_hash = hash(&new_wallet_device.try_to_vec()?);
+    Ok(hashv(&[data_hash.as_ref(), device_hash.as_ref()]).to_bytes())
+}
+
 /// Helper: Get a slice of accounts from remaining_accounts
 pub fn get_account_slice<'a>(
     accounts: &'a [AccountInfo<'a>],
@@ -227,12 +1097,17 @@ pub fn get_account_slice<'a>(
 }
 
 /// Helper: Create a PDA signer struct
-pub fn get_pda_signer(passkey: &[u8; PASSKEY_SIZE], wallet: Pubkey, bump: u8) -> PdaSigner {
+pub fn get_pda_signer(
+    kind: crate::state::DeviceKind,
+    device_key: &[u8; PASSKEY_SIZE],
+    wallet: Pubkey,
+    bump: u8,
+) -> PdaSigner {
     PdaSigner {
         seeds: vec![
             crate::state::WalletDevice::PREFIX_SEED.to_vec(),
             wallet.to_bytes().to_vec(),
-            passkey.to_hashed_bytes(wallet).to_vec(),
+            kind.seed_hash(device_key, wallet).to_vec(),
         ],
         bump,
     }
@@ -250,23 +1125,442 @@ pub fn check_whitelist(
     Ok(())
 }
 
-/// Same as `verify_authorization` but deserializes the challenge payload into the
-/// caller-provided type `T`.
-pub fn verify_authorization<M: crate::state::Message + AnchorDeserialize>(
+/// How an authorized message is protected against replay
+#[derive(Clone, Copy)]
+pub enum ReplayGuard<'a> {
+    /// Sequenced on the wallet's primary nonce
+    Primary,
+    /// Sequenced on an additional nonce lane
+    Lane(&'a crate::state::NonceLane),
+    /// Signed intent, recorded in an intent receipt instead of advancing a nonce
+    Intent,
+}
+
+impl<'a> From<Option<&'a crate::state::NonceLane>> for ReplayGuard<'a> {
+    fn from(nonce_lane: Option<&'a crate::state::NonceLane>) -> Self {
+        nonce_lane.map_or(ReplayGuard::Primary, ReplayGuard::Lane)
+    }
+}
+
+/// A message authorized by a wallet device
+pub struct Authorized<M> {
+    pub message: M,
+    /// Freshness rule the challenge was checked against
+    pub freshness: crate::state::Freshness,
+    /// sha256 of the signed challenge, identifying a signed intent
+    pub challenge_hash: [u8; 32],
+}
+
+/// Authenticate `device` for `smart_wallet_key` and deserialize the signed challenge payload
+/// into the caller-provided message type `M`.
+///
+/// Passkeys sign a WebAuthn assertion whose challenge is the payload. Ed25519 devices sign the
+/// payload (passed as `client_data_json_raw`) directly, either through the ed25519 precompile or,
+/// when `signature` is empty, by signing the transaction itself. Secp256k1 devices sign the
+/// payload with EIP-191 `personal_sign`, checked through the secp256k1 precompile.
+#[allow(clippy::too_many_arguments)]
+pub fn verify_authorization<M: crate::state::Message>(
     ix_sysvar: &AccountInfo,
-    device: &crate::state::WalletDevice,
+    device: &mut crate::state::WalletDevice,
     smart_wallet_key: Pubkey,
+    wallet: &crate::state::SmartWallet,
+    config: &crate::state::Config,
+    payer: Pubkey,
+    replay: ReplayGuard,
+    slot_hashes: Option<&AccountInfo>,
     passkey_pubkey: [u8; PASSKEY_SIZE],
     signature: Vec<u8>,
     client_data_json_raw: &[u8],
     authenticator_data_raw: &[u8],
     verify_instruction_index: u8,
-    last_nonce: u64,
-) -> Result<M> {
-    use anchor_lang::solana_program::sysvar::instructions::load_instruction_at_checked;
-    use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+) -> Result<Authorized<M>> {
+    let (authorized, auth_data) = authorize_single_device(
+        ix_sysvar,
+        device,
+        smart_wallet_key,
+        wallet,
+        config,
+        payer,
+        replay,
+        slot_hashes,
+        passkey_pubkey,
+        &signature,
+        client_data_json_raw,
+        authenticator_data_raw,
+        verify_instruction_index,
+    )?;
+
+    // only count the signature once the message was accepted
+    if let Some(auth_data) = auth_data {
+        commit_authenticator_data(device, smart_wallet_key, &auth_data)?;
+    }
+    Ok(authorized)
+}
+
+/// Read-only counterpart of [`verify_authorization`] for `verify_signature`: the device's
+/// signature counter must be ahead of the stored one but is not updated, so an approval can be
+/// verified repeatedly until the device signs a later assertion.
+///
+/// `payer` is the signer the challenge may be bound to, or `ANY_PAYER` when none was proven.
+#[allow(clippy::too_many_arguments)]
+pub fn check_authorization<M: crate::state::Message>(
+    ix_sysvar: &AccountInfo,
+    device: &crate::state::WalletDevice,
+    smart_wallet_key: Pubkey,
+    wallet: &crate::state::SmartWallet,
+    config: &crate::state::Config,
+    payer: Pubkey,
+    slot_hashes: Option<&AccountInfo>,
+    passkey_pubkey: [u8; PASSKEY_SIZE],
+    signature: &[u8],
+    client_data_json_raw: &[u8],
+    authenticator_data_raw: &[u8],
+    verify_instruction_index: u8,
+) -> Result<Authorized<M>> {
+    let (authorized, auth_data) = authorize_single_device(
+        ix_sysvar,
+        device,
+        smart_wallet_key,
+        wallet,
+        config,
+        payer,
+        ReplayGuard::Primary,
+        slot_hashes,
+        passkey_pubkey,
+        signature,
+        client_data_json_raw,
+        authenticator_data_raw,
+        verify_instruction_index,
+    )?;
+
+    // a cloned authenticator reports a counter the stored one already passed
+    if let Some(auth_data) = auth_data {
+        check_sign_count(device, smart_wallet_key, auth_data.sign_count)?;
+    }
+    Ok(authorized)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn authorize_single_device<M: crate::state::Message>(
+    ix_sysvar: &AccountInfo,
+    device: &crate::state::WalletDevice,
+    smart_wallet_key: Pubkey,
+    wallet: &crate::state::SmartWallet,
+    config: &crate::state::Config,
+    payer: Pubkey,
+    replay: ReplayGuard,
+    slot_hashes: Option<&AccountInfo>,
+    passkey_pubkey: [u8; PASSKEY_SIZE],
+    signature: &[u8],
+    client_data_json_raw: &[u8],
+    authenticator_data_raw: &[u8],
+    verify_instruction_index: u8,
+) -> Result<(Authorized<M>, Option<AuthenticatorData>)> {
+    // 1) operations the wallet reserves for threshold approval need several devices
+    require!(
+        !wallet.requires_threshold(M::OPERATION),
+        crate::error::LazorKitError::ThresholdApprovalRequired
+    );
+
+    // 2) authenticate the challenge payload with the device
+    let mut challenge_buf = [0u8; crate::security::MAX_CHALLENGE_SIZE];
+    let (challenge_len, auth_data) = authenticate_device(
+        ix_sysvar,
+        device,
+        smart_wallet_key,
+        wallet,
+        config,
+        M::OPERATION,
+        passkey_pubkey,
+        signature,
+        client_data_json_raw,
+        authenticator_data_raw,
+        verify_instruction_index,
+        &mut challenge_buf,
+    )?;
+
+    // 3) verify envelope and header, and return the typed message
+    let authorized = open_authorized(
+        &challenge_buf[..challenge_len],
+        smart_wallet_key,
+        wallet,
+        config,
+        payer,
+        replay,
+        slot_hashes,
+    )?;
+    Ok((authorized, auth_data))
+}
+
+/// Authenticate one challenge approved by several devices of the same wallet and deserialize it
+/// into `M`.
+///
+/// `devices[i]` made `assertions[i]`; every assertion must sign the same challenge, each device
+/// may approve once, and at least `SmartWallet::threshold` devices must approve.
+#[allow(clippy::too_many_arguments)]
+pub fn verify_threshold_authorization<M: crate::state::Message>(
+    ix_sysvar: &AccountInfo,
+    devices: &mut [&mut crate::state::WalletDevice],
+    assertions: &[crate::instructions::DeviceAssertion],
+    smart_wallet_key: Pubkey,
+    wallet: &crate::state::SmartWallet,
+    config: &crate::state::Config,
+    payer: Pubkey,
+    replay: ReplayGuard,
+    slot_hashes: Option<&AccountInfo>,
+) -> Result<Authorized<M>> {
+    let readonly: Vec<&crate::state::WalletDevice> =
+        devices.iter().map(|device| &**device).collect();
+    let (authorized, auth_datas) = authorize_threshold(
+        ix_sysvar,
+        &readonly,
+        assertions,
+        smart_wallet_key,
+        wallet,
+        config,
+        payer,
+        replay,
+        slot_hashes,
+    )?;
+
+    // count every passkey signature
+    for (device, auth_data) in devices.iter_mut().zip(auth_datas) {
+        if let Some(auth_data) = auth_data {
+            commit_authenticator_data(device, smart_wallet_key, &auth_data)?;
+        }
+    }
+    Ok(authorized)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn authorize_threshold<M: crate::state::Message>(
+    ix_sysvar: &AccountInfo,
+    devices: &[&crate::state::WalletDevice],
+    assertions: &[crate::instructions::DeviceAssertion],
+    smart_wallet_key: Pubkey,
+    wallet: &crate::state::SmartWallet,
+    config: &crate::state::Config,
+    payer: Pubkey,
+    replay: ReplayGuard,
+    slot_hashes: Option<&AccountInfo>,
+) -> Result<(Authorized<M>, Vec<Option<AuthenticatorData>>)> {
+    // 1) one distinct device per assertion, enough of them to meet the threshold
+    require!(
+        devices.len() == assertions.len()
+            && assertions.len() <= crate::security::MAX_THRESHOLD_APPROVALS,
+        crate::error::LazorKitError::InvalidRemainingAccounts
+    );
+    require!(
+        assertions.len() >= usize::from(wallet.threshold.max(1)),
+        crate::error::LazorKitError::ThresholdNotMet
+    );
+    for (i, device) in devices.iter().enumerate() {
+        require!(
+            devices[..i]
+                .iter()
+                .all(|other| other.passkey_pubkey != device.passkey_pubkey),
+            crate::error::LazorKitError::DuplicateDeviceApproval
+        );
+    }
+
+    // 2) authenticate every assertion against the same challenge payload
+    let mut challenge: Option<Vec<u8>> = None;
+    let mut auth_datas = Vec::with_capacity(assertions.len());
+    let mut challenge_buf = [0u8; crate::security::MAX_CHALLENGE_SIZE];
+    for (device, assertion) in devices.iter().zip(assertions) {
+        let (challenge_len, auth_data) = authenticate_device(
+            ix_sysvar,
+            device,
+            smart_wallet_key,
+            wallet,
+            config,
+            M::OPERATION,
+            assertion.passkey_pubkey,
+            &assertion.signature,
+            &assertion.client_data_json_raw,
+            &assertion.authenticator_data_raw,
+            assertion.verify_instruction_index,
+            &mut challenge_buf,
+        )?;
+        let signed = &challenge_buf[..challenge_len];
+        match &challenge {
+            Some(challenge) => require!(
+                challenge[..] == *signed,
+                crate::error::LazorKitError::ThresholdChallengeMismatch
+            ),
+            None => challenge = Some(signed.to_vec()),
+        }
+        auth_datas.push(auth_data);
+    }
 
-    // 1) passkey & wallet checks
+    // 3) verify envelope and header once for the shared challenge
+    let authorized = open_authorized(
+        challenge.as_deref().unwrap_or_default(),
+        smart_wallet_key,
+        wallet,
+        config,
+        payer,
+        replay,
+        slot_hashes,
+    )?;
+
+    Ok((authorized, auth_datas))
+}
+
+/// Authorize `M` with the primary device alone or, when `co_signers` is non-empty, together
+/// with the co-signing devices passed as `co_signer_accounts` (in the same order).
+#[allow(clippy::too_many_arguments)]
+pub fn verify_co_signed_authorization<'info, M: crate::state::Message>(
+    ix_sysvar: &AccountInfo,
+    primary: &mut crate::state::WalletDevice,
+    primary_assertion: crate::instructions::DeviceAssertion,
+    co_signers: &[crate::instructions::DeviceAssertion],
+    co_signer_accounts: &'info [AccountInfo<'info>],
+    smart_wallet_key: Pubkey,
+    wallet: &crate::state::SmartWallet,
+    config: &crate::state::Config,
+    payer: Pubkey,
+    replay: ReplayGuard,
+    slot_hashes: Option<&AccountInfo>,
+) -> Result<Authorized<M>> {
+    use crate::instructions::Args as _;
+
+    if co_signers.is_empty() {
+        return verify_authorization(
+            ix_sysvar,
+            primary,
+            smart_wallet_key,
+            wallet,
+            config,
+            payer,
+            replay,
+            slot_hashes,
+            primary_assertion.passkey_pubkey,
+            primary_assertion.signature,
+            &primary_assertion.client_data_json_raw,
+            &primary_assertion.authenticator_data_raw,
+            primary_assertion.verify_instruction_index,
+        );
+    }
+
+    for assertion in co_signers {
+        assertion.validate()?;
+    }
+    let mut co_signer_devices = load_wallet_devices(co_signer_accounts)?;
+    let assertions: Vec<_> = core::iter::once(primary_assertion)
+        .chain(co_signers.iter().cloned())
+        .collect();
+    let mut devices: Vec<&mut crate::state::WalletDevice> = core::iter::once(primary)
+        .chain(co_signer_devices.iter_mut().map(|device| &mut **device))
+        .collect();
+    let authorized = verify_threshold_authorization(
+        ix_sysvar,
+        &mut devices,
+        &assertions,
+        smart_wallet_key,
+        wallet,
+        config,
+        payer,
+        replay,
+        slot_hashes,
+    )?;
+    drop(devices);
+
+    // Co-signers are not part of the instruction's accounts struct, so persist them here
+    for device in &co_signer_devices {
+        device.exit(&crate::ID)?;
+    }
+    Ok(authorized)
+}
+
+/// Read-only counterpart of [`verify_co_signed_authorization`] for `verify_signature`: the
+/// devices' signature counters must be ahead of the stored ones but are not updated, and the
+/// co-signing devices need not be writable.
+#[allow(clippy::too_many_arguments)]
+pub fn check_co_signed_authorization<'info, M: crate::state::Message>(
+    ix_sysvar: &AccountInfo,
+    primary: &crate::state::WalletDevice,
+    primary_assertion: crate::instructions::DeviceAssertion,
+    co_signers: &[crate::instructions::DeviceAssertion],
+    co_signer_accounts: &'info [AccountInfo<'info>],
+    smart_wallet_key: Pubkey,
+    wallet: &crate::state::SmartWallet,
+    config: &crate::state::Config,
+    payer: Pubkey,
+    slot_hashes: Option<&AccountInfo>,
+) -> Result<Authorized<M>> {
+    use crate::instructions::Args as _;
+
+    if co_signers.is_empty() {
+        return check_authorization(
+            ix_sysvar,
+            primary,
+            smart_wallet_key,
+            wallet,
+            config,
+            payer,
+            slot_hashes,
+            primary_assertion.passkey_pubkey,
+            &primary_assertion.signature,
+            &primary_assertion.client_data_json_raw,
+            &primary_assertion.authenticator_data_raw,
+            primary_assertion.verify_instruction_index,
+        );
+    }
+
+    for assertion in co_signers {
+        assertion.validate()?;
+    }
+    let co_signer_devices = co_signer_accounts
+        .iter()
+        .map(Account::<crate::state::WalletDevice>::try_from)
+        .collect::<Result<Vec<_>>>()?;
+    let assertions: Vec<_> = core::iter::once(primary_assertion)
+        .chain(co_signers.iter().cloned())
+        .collect();
+    let devices: Vec<&crate::state::WalletDevice> = core::iter::once(primary)
+        .chain(co_signer_devices.iter().map(|device| &**device))
+        .collect();
+    let (authorized, auth_datas) = authorize_threshold(
+        ix_sysvar,
+        &devices,
+        &assertions,
+        smart_wallet_key,
+        wallet,
+        config,
+        payer,
+        ReplayGuard::Primary,
+        slot_hashes,
+    )?;
+
+    // a cloned authenticator reports a counter the stored one already passed
+    for (device, auth_data) in devices.iter().zip(auth_datas) {
+        if let Some(auth_data) = auth_data {
+            check_sign_count(device, smart_wallet_key, auth_data.sign_count)?;
+        }
+    }
+    Ok(authorized)
+}
+
+/// Check that `device` may approve `operation` for the wallet and authenticate the signed
+/// challenge payload, copying it into `challenge_buf`. Returns the payload length and, for
+/// passkeys, the authenticatorData to commit once the message is accepted.
+#[allow(clippy::too_many_arguments)]
+fn authenticate_device(
+    ix_sysvar: &AccountInfo,
+    device: &crate::state::WalletDevice,
+    smart_wallet_key: Pubkey,
+    wallet: &crate::state::SmartWallet,
+    config: &crate::state::Config,
+    operation: crate::state::MessageOperation,
+    passkey_pubkey: [u8; PASSKEY_SIZE],
+    signature: &[u8],
+    client_data_json_raw: &[u8],
+    authenticator_data_raw: &[u8],
+    verify_instruction_index: u8,
+    challenge_buf: &mut [u8],
+) -> Result<(usize, Option<AuthenticatorData>)> {
+    // device & wallet checks
     require!(
         device.passkey_pubkey == passkey_pubkey,
         crate::error::LazorKitError::PasskeyMismatch
@@ -275,36 +1569,358 @@ pub fn verify_authorization<M: crate::state::Message + AnchorDeserialize>(
         device.smart_wallet == smart_wallet_key,
         crate::error::LazorKitError::SmartWalletMismatch
     );
+    if operation.is_in(wallet.device_bound_operations) {
+        require!(
+            device.is_device_bound(),
+            crate::error::LazorKitError::DeviceBoundCredentialRequired
+        );
+    }
+
+    // authenticate the challenge payload according to the device kind
+    match device.kind {
+        crate::state::DeviceKind::Passkey => {
+            let (challenge_len, auth_data) = verify_passkey_assertion(
+                ix_sysvar,
+                device,
+                wallet,
+                config,
+                signature,
+                client_data_json_raw,
+                authenticator_data_raw,
+                verify_instruction_index,
+                challenge_buf,
+            )?;
+            Ok((challenge_len, Some(auth_data)))
+        }
+        crate::state::DeviceKind::Ed25519 => {
+            require!(
+                authenticator_data_raw.is_empty()
+                    && client_data_json_raw.len() <= challenge_buf.len(),
+                crate::error::LazorKitError::InvalidInstructionData
+            );
+            let key = &device.passkey_pubkey[..PASSKEY_SIZE - 1];
+            if signature.is_empty() {
+                let signer = Pubkey::try_from(key)
+                    .map_err(|_| crate::error::LazorKitError::InvalidDeviceKeyFormat)?;
+                require_instruction_signer(ix_sysvar, &signer)?;
+            } else {
+                locate_and_verify_precompile(
+                    SignatureScheme::Ed25519,
+                    ix_sysvar,
+                    verify_instruction_index,
+                    key,
+                    client_data_json_raw,
+                    signature,
+                )?;
+            }
+            challenge_buf[..client_data_json_raw.len()].copy_from_slice(client_data_json_raw);
+            Ok((client_data_json_raw.len(), None))
+        }
+        crate::state::DeviceKind::Secp256k1 => {
+            require!(
+                authenticator_data_raw.is_empty()
+                    && client_data_json_raw.len() <= challenge_buf.len(),
+                crate::error::LazorKitError::InvalidInstructionData
+            );
+            require!(
+                signature.len() == SECP256K1_SIGNATURE_SIZE,
+                crate::error::LazorKitError::InvalidSignature
+            );
+            locate_and_verify_secp256k1(
+                ix_sysvar,
+                verify_instruction_index,
+                &device.passkey_pubkey[..ETH_ADDRESS_SIZE],
+                &eip191_message(client_data_json_raw),
+                signature,
+            )?;
+            challenge_buf[..client_data_json_raw.len()].copy_from_slice(client_data_json_raw);
+            Ok((client_data_json_raw.len(), None))
+        }
+    }
+}
+
+/// Open the envelope of an authenticated challenge and check it against the wallet state
+fn open_authorized<M: crate::state::Message>(
+    challenge_bytes: &[u8],
+    smart_wallet_key: Pubkey,
+    wallet: &crate::state::SmartWallet,
+    config: &crate::state::Config,
+    payer: Pubkey,
+    replay: ReplayGuard,
+    slot_hashes: Option<&AccountInfo>,
+) -> Result<Authorized<M>> {
+    let slot_hashes_data = slot_hashes.map(|info| info.try_borrow_data()).transpose()?;
+    let (last_nonce, nonce_lane) = match replay {
+        ReplayGuard::Primary => (wallet.last_nonce, crate::state::PRIMARY_NONCE_LANE),
+        ReplayGuard::Lane(lane) => (lane.sequence, lane.lane),
+        ReplayGuard::Intent => (0, crate::state::INTENT_NONCE_LANE),
+    };
+    let (message, freshness) = M::verify(
+        challenge_bytes,
+        &crate::state::MessageContext {
+            last_nonce,
+            nonce_lane,
+            min_version: config.min_message_version,
+            domain_separator: crate::state::domain_separator(
+                config.deployment_tag,
+                &smart_wallet_key,
+            ),
+            payer,
+            max_timestamp_drift: wallet.timestamp_drift(),
+            slot_hashes: slot_hashes_data.as_ref().map(|data| &data[..]),
+        },
+    )?;
+    Ok(Authorized {
+        message,
+        freshness,
+        challenge_hash: anchor_lang::solana_program::hash::hash(challenge_bytes).to_bytes(),
+    })
+}
+
+/// Signature counter must strictly increase unless the authenticator never counts; the backup
+/// state may change over the credential's lifetime, so track the latest value. Migrated legacy
+/// passkeys record their backup eligibility from the first assertion.
+fn commit_authenticator_data(
+    device: &mut crate::state::WalletDevice,
+    smart_wallet_key: Pubkey,
+    auth_data: &AuthenticatorData,
+) -> Result<()> {
+    update_sign_count(device, smart_wallet_key, auth_data.sign_count)?;
+    if !device.backup_eligibility_recorded {
+        device.backup_eligible = auth_data.backup_eligible();
+        device.backup_eligibility_recorded = true;
+    }
+    device.backup_state = auth_data.backup_state();
+    Ok(())
+}
 
-    // 2) locate the secp256r1 verify instruction
-    let secp_ix = load_instruction_at_checked(verify_instruction_index as usize, ix_sysvar)?;
+/// Load co-signing wallet devices passed as remaining accounts; they must be writable so their
+/// signature counters can be stored with `exit`
+pub fn load_wallet_devices<'info>(
+    infos: &'info [AccountInfo<'info>],
+) -> Result<Vec<Account<'info, crate::state::WalletDevice>>> {
+    infos
+        .iter()
+        .map(|info| {
+            require!(
+                info.is_writable,
+                crate::error::LazorKitError::InvalidRemainingAccounts
+            );
+            Account::try_from(info)
+        })
+        .collect()
+}
+
+/// Advance the nonce an authorized message was sequenced on: the given lane, or the wallet's
+/// primary nonce when no lane account was passed
+pub fn advance_nonce(
+    wallet: &mut crate::state::SmartWallet,
+    nonce_lane: Option<&mut crate::state::NonceLane>,
+) -> Result<()> {
+    let nonce = match nonce_lane {
+        Some(lane) => &mut lane.sequence,
+        None => &mut wallet.last_nonce,
+    };
+    *nonce = nonce
+        .checked_add(1)
+        .ok_or(crate::error::LazorKitError::NonceOverflow)?;
+    Ok(())
+}
+
+/// Verify a WebAuthn assertion made by a passkey device and decode its challenge into
+/// `challenge_buf`. Returns the challenge length and the parsed authenticatorData header.
+#[allow(clippy::too_many_arguments)]
+fn verify_passkey_assertion(
+    ix_sysvar: &AccountInfo,
+    device: &crate::state::WalletDevice,
+    wallet: &crate::state::SmartWallet,
+    config: &crate::state::Config,
+    signature: &[u8],
+    client_data_json_raw: &[u8],
+    authenticator_data_raw: &[u8],
+    verify_instruction_index: u8,
+    challenge_buf: &mut [u8],
+) -> Result<(usize, AuthenticatorData)> {
+    use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+
+    require!(
+        signature.len() == 64,
+        crate::error::LazorKitError::InvalidSignature
+    );
+    // Reject malleable high-S signatures
+    require!(
+        is_low_s(signature),
+        crate::error::LazorKitError::SignatureNotLowS
+    );
 
-    // 3) reconstruct signed message (wallet_device authenticatorData || SHA256(clientDataJSON))
+    // reconstruct signed message (wallet_device authenticatorData || SHA256(clientDataJSON))
     let client_hash = hash(client_data_json_raw);
     let mut message = Vec::with_capacity(authenticator_data_raw.len() + client_hash.as_ref().len());
     message.extend_from_slice(authenticator_data_raw);
     message.extend_from_slice(client_hash.as_ref());
 
-    // 4) parse the challenge from clientDataJSON
-    let json_str = core::str::from_utf8(client_data_json_raw)
-        .map_err(|_| crate::error::LazorKitError::ClientDataInvalidUtf8)?;
-    let parsed: serde_json::Value = serde_json::from_str(json_str)
-        .map_err(|_| crate::error::LazorKitError::ClientDataJsonParseError)?;
-    let challenge = parsed["challenge"]
-        .as_str()
-        .ok_or(crate::error::LazorKitError::ChallengeMissing)?;
+    // check relying party and user presence/verification from authenticatorData; assertions
+    // are rejected until a relying party is configured for the device or the program
+    let auth_data = AuthenticatorData::parse(authenticator_data_raw)?;
+    let expected_rp_id_hash = device
+        .rp_id_hash
+        .or(config.rp_id_hash)
+        .ok_or(crate::error::LazorKitError::RpIdHashNotConfigured)?;
+    require!(
+        auth_data.rp_id_hash == expected_rp_id_hash,
+        crate::error::LazorKitError::RpIdHashMismatch
+    );
+    require!(
+        auth_data.user_present(),
+        crate::error::LazorKitError::UserNotPresent
+    );
+    if config.require_user_verification || wallet.require_user_verification {
+        require!(
+            auth_data.user_verified(),
+            crate::error::LazorKitError::UserNotVerified
+        );
+    }
+    // BE is fixed at credential creation, so a mismatch means a different credential
+    require!(
+        !device.backup_eligibility_recorded
+            || auth_data.backup_eligible() == device.backup_eligible,
+        crate::error::LazorKitError::BackupEligibilityMismatch
+    );
+    require!(
+        auth_data.backup_eligible() || !auth_data.backup_state(),
+        crate::error::LazorKitError::InvalidBackupFlags
+    );
 
-    let challenge_clean = challenge.trim_matches(|c| c == '"' || c == '\'' || c == '/' || c == ' ');
-    let challenge_bytes = URL_SAFE_NO_PAD
-        .decode(challenge_clean)
+    // parse the challenge from clientDataJSON
+    let client_data = ClientData::parse(client_data_json_raw)?;
+
+    // only assertions (not registrations) from trusted origins are accepted; an empty list
+    // means no origin was configured, not that every origin is trusted
+    require!(
+        client_data.ty == Some(WEBAUTHN_GET_TYPE.as_bytes()),
+        crate::error::LazorKitError::ClientDataTypeMismatch
+    );
+    let origin = client_data
+        .origin
+        .ok_or(crate::error::LazorKitError::ClientDataOriginMissing)?;
+    let allowed_origins = wallet.effective_origin_hashes(config);
+    require!(
+        !allowed_origins.is_empty(),
+        crate::error::LazorKitError::AllowedOriginsNotConfigured
+    );
+    require!(
+        allowed_origins.contains(&hash(origin).to_bytes()),
+        crate::error::LazorKitError::ClientDataOriginNotAllowed
+    );
+    // assertions created inside a cross-origin iframe are signed for the embedding page's
+    // origin policy, not for the origins allowed above
+    require!(
+        !client_data.cross_origin,
+        crate::error::LazorKitError::ClientDataCrossOrigin
+    );
+
+    let challenge = client_data
+        .challenge
+        .ok_or(crate::error::LazorKitError::ChallengeMissing)?;
+    let challenge_len = URL_SAFE_NO_PAD
+        .decode_slice(challenge, challenge_buf)
         .map_err(|_| crate::error::LazorKitError::ChallengeBase64DecodeError)?;
 
-    verify_secp256r1_instruction(&secp_ix, device.passkey_pubkey, message, signature)?;
-    // Verify header and return the typed message
-    M::verify(challenge_bytes.clone(), last_nonce)?;
-    let t: M = AnchorDeserialize::deserialize(&mut &challenge_bytes[..])
-        .map_err(|_| crate::error::LazorKitError::ChallengeDeserializationError)?;
-    Ok(t)
+    // locate the secp256r1 verify instruction covering the signed message
+    locate_and_verify_precompile(
+        SignatureScheme::Secp256r1,
+        ix_sysvar,
+        verify_instruction_index,
+        &device.passkey_pubkey,
+        &message,
+        signature,
+    )?;
+
+    Ok((challenge_len, auth_data))
+}
+
+/// Check that `proof` is an assertion by the passkey of `device` over the challenge of its passkey
+/// index, proving the claimant holds the key. The signature counter is not updated.
+pub fn verify_passkey_index_proof(
+    ix_sysvar: &AccountInfo,
+    device: &crate::state::WalletDevice,
+    wallet: &crate::state::SmartWallet,
+    config: &crate::state::Config,
+    proof: &crate::instructions::DeviceAssertion,
+) -> Result<()> {
+    use crate::instructions::Args as _;
+
+    proof.validate()?;
+    require!(
+        proof.passkey_pubkey == device.passkey_pubkey,
+        crate::error::LazorKitError::PasskeyMismatch
+    );
+    let mut challenge_buf = [0u8; crate::security::MAX_CHALLENGE_SIZE];
+    let (challenge_len, _) = verify_passkey_assertion(
+        ix_sysvar,
+        device,
+        wallet,
+        config,
+        &proof.signature,
+        &proof.client_data_json_raw,
+        &proof.authenticator_data_raw,
+        proof.verify_instruction_index,
+        &mut challenge_buf,
+    )?;
+    let expected = crate::state::PasskeyIndex::claim_challenge(
+        config.deployment_tag,
+        &device.smart_wallet,
+        &device.passkey_pubkey,
+    );
+    require!(
+        challenge_buf[..challenge_len] == expected,
+        crate::error::LazorKitError::PasskeyIndexProofMismatch
+    );
+    Ok(())
+}
+
+/// Enforce a strictly increasing WebAuthn signature counter and store the new value.
+///
+/// Authenticators that always report 0 (e.g. synced passkeys) skip the check. A counter that
+/// fails to increase indicates a cloned credential and fails the instruction with
+/// `SignCountRegression`.
+///
+/// The critical `SIGN_COUNT_REGRESSION` security event is logged before failing, so it only
+/// appears in the logs of the failed transaction (or its simulation) and is never committed.
+/// Monitoring must watch failed transactions for the error code rather than rely on indexed
+/// events.
+fn update_sign_count(
+    device: &mut crate::state::WalletDevice,
+    smart_wallet_key: Pubkey,
+    sign_count: u32,
+) -> Result<()> {
+    check_sign_count(device, smart_wallet_key, sign_count)?;
+    device.sign_count = sign_count;
+    Ok(())
+}
+
+/// Check a WebAuthn signature counter against the stored one without updating it, emitting the
+/// `SIGN_COUNT_REGRESSION` security event when it did not increase
+fn check_sign_count(
+    device: &crate::state::WalletDevice,
+    smart_wallet_key: Pubkey,
+    sign_count: u32,
+) -> Result<()> {
+    if sign_count == 0 && device.sign_count == 0 {
+        return Ok(());
+    }
+    if sign_count <= device.sign_count {
+        crate::events::SecurityEvent::emit_critical(
+            Some(smart_wallet_key),
+            "SIGN_COUNT_REGRESSION",
+            &format!(
+                "stored sign_count {} >= received {}",
+                device.sign_count, sign_count
+            ),
+        )?;
+        return err!(crate::error::LazorKitError::SignCountRegression);
+    }
+    Ok(())
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
@@ -342,6 +1958,42 @@ impl HasHeader for UpdatePolicyMessage {
     }
 }
 
+impl HasHeader for UpdateWalletSettingsMessage {
+    fn header(&self) -> HeaderView {
+        HeaderView {
+            nonce: self.nonce,
+            current_timestamp: self.current_timestamp,
+        }
+    }
+}
+
+impl HasHeader for CancelIntentMessage {
+    fn header(&self) -> HeaderView {
+        HeaderView {
+            nonce: self.nonce,
+            current_timestamp: self.current_timestamp,
+        }
+    }
+}
+
+impl HasHeader for RemoveDeviceMessage {
+    fn header(&self) -> HeaderView {
+        HeaderView {
+            nonce: self.nonce,
+            current_timestamp: self.current_timestamp,
+        }
+    }
+}
+
+impl HasHeader for SyncDevicesMessage {
+    fn header(&self) -> HeaderView {
+        HeaderView {
+            nonce: self.nonce,
+            current_timestamp: self.current_timestamp,
+        }
+    }
+}
+
 /// Helper: Split remaining accounts into `(policy_accounts, cpi_accounts)` using `split_index` coming from `Message`.
 pub fn split_remaining_accounts<'a>(
     accounts: &'a [AccountInfo<'a>],
@@ -354,3 +2006,430 @@ pub fn split_remaining_accounts<'a>(
     );
     Ok(accounts.split_at(idx))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(deprecated)] // the solana-instruction crates are not direct dependencies
+    use anchor_lang::solana_program::sysvar::instructions::{
+        self, construct_instructions_data, BorrowedAccountMeta, BorrowedInstruction,
+    };
+
+    const PUBKEY: [u8; 33] = [2; 33];
+    const SIG: [u8; 64] = [7; 64];
+    const MSG: &[u8] = b"authenticator data || client data hash";
+
+    /// One offsets table entry: `(sig, pubkey, msg)` offsets, each with its instruction index
+    struct Entry {
+        sig: (u16, u16),
+        pubkey: (u16, u16),
+        msg: (u16, u16),
+        msg_len: u16,
+    }
+
+    /// Entry pointing at fields stored in the precompile instruction itself
+    fn inline_entry(sig: u16, pubkey: u16, msg: u16, msg_len: u16) -> Entry {
+        Entry {
+            sig: (sig, SECP_CURRENT_INSTRUCTION),
+            pubkey: (pubkey, SECP_CURRENT_INSTRUCTION),
+            msg: (msg, SECP_CURRENT_INSTRUCTION),
+            msg_len,
+        }
+    }
+
+    fn precompile_data(entries: &[Entry], payload: &[u8]) -> Vec<u8> {
+        let mut data = vec![entries.len() as u8, 0];
+        for entry in entries {
+            for value in [
+                entry.sig.0,
+                entry.sig.1,
+                entry.pubkey.0,
+                entry.pubkey.1,
+                entry.msg.0,
+                entry.msg_len,
+                entry.msg.1,
+            ] {
+                data.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+        data.extend_from_slice(payload);
+        data
+    }
+
+    /// Precompile instruction data with a single entry covering `(PUBKEY, SIG, MSG)`
+    fn single_signature_data() -> Vec<u8> {
+        let start = (SECP_OFFSETS_START + SECP_OFFSETS_SIZE) as u16;
+        let payload = [&PUBKEY[..], &SIG[..], MSG].concat();
+        precompile_data(
+            &[inline_entry(
+                start + 33,
+                start,
+                start + 97,
+                MSG.len() as u16,
+            )],
+            &payload,
+        )
+    }
+
+    /// Instructions sysvar data for a transaction made of `(program_id, data)` instructions
+    fn sysvar_data(ixs: &[(Pubkey, Vec<u8>)]) -> Vec<u8> {
+        let borrowed: Vec<BorrowedInstruction> = ixs
+            .iter()
+            .map(|(program_id, data)| BorrowedInstruction {
+                program_id,
+                accounts: Vec::<BorrowedAccountMeta>::new(),
+                data,
+            })
+            .collect();
+        construct_instructions_data(&borrowed)
+    }
+
+    fn verify(ixs: &[(Pubkey, Vec<u8>)], index: u8, msg: &[u8]) -> Result<()> {
+        let mut data = sysvar_data(ixs);
+        let mut lamports = 0;
+        let key = instructions::ID;
+        let owner = anchor_lang::solana_program::sysvar::ID;
+        let info = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &owner,
+            false,
+            0,
+        );
+        locate_and_verify_precompile(SignatureScheme::Secp256r1, &info, index, &PUBKEY, msg, &SIG)
+    }
+
+    fn memo() -> (Pubkey, Vec<u8>) {
+        (Pubkey::new_unique(), b"compute budget or memo".to_vec())
+    }
+
+    #[test]
+    fn explicit_index_must_point_at_the_precompile() {
+        let ixs = [memo(), (SECP256R1_ID, single_signature_data())];
+        assert!(verify(&ixs, 1, MSG).is_ok());
+        assert_eq!(
+            verify(&ixs, 0, MSG).unwrap_err(),
+            LazorKitError::Secp256r1InvalidLength.into()
+        );
+    }
+
+    #[test]
+    fn auto_index_finds_the_precompile_anywhere() {
+        let ixs = [memo(), memo(), (SECP256R1_ID, single_signature_data())];
+        assert!(verify(&ixs, AUTO_VERIFY_INSTRUCTION_INDEX, MSG).is_ok());
+        assert_eq!(
+            verify(&ixs, AUTO_VERIFY_INSTRUCTION_INDEX, b"some other message").unwrap_err(),
+            LazorKitError::Secp256r1InstructionNotFound.into()
+        );
+        assert_eq!(
+            verify(&[memo()], AUTO_VERIFY_INSTRUCTION_INDEX, MSG).unwrap_err(),
+            LazorKitError::Secp256r1InstructionNotFound.into()
+        );
+    }
+
+    #[test]
+    fn any_entry_of_a_multi_signature_table_matches() {
+        let start = (SECP_OFFSETS_START + 2 * SECP_OFFSETS_SIZE) as u16;
+        let other_msg = b"another signer's message";
+        let payload = [
+            &[3u8; 33][..],
+            &[9u8; 64][..],
+            other_msg,
+            &PUBKEY,
+            &SIG,
+            MSG,
+        ]
+        .concat();
+        let second = start + 33 + 64 + other_msg.len() as u16;
+        let data = precompile_data(
+            &[
+                inline_entry(start + 33, start, start + 97, other_msg.len() as u16),
+                inline_entry(second + 33, second, second + 97, MSG.len() as u16),
+            ],
+            &payload,
+        );
+        assert!(verify(&[(SECP256R1_ID, data)], 0, MSG).is_ok());
+    }
+
+    #[test]
+    fn entries_may_reference_other_instructions() {
+        // The message lives in instruction 0 at offset 4; key and signature are inline
+        let start = (SECP_OFFSETS_START + SECP_OFFSETS_SIZE) as u16;
+        let holder = (Pubkey::new_unique(), [&b"pad!"[..], MSG].concat());
+        let data = precompile_data(
+            &[Entry {
+                sig: (start + 33, SECP_CURRENT_INSTRUCTION),
+                pubkey: (start, SECP_CURRENT_INSTRUCTION),
+                msg: (4, 0),
+                msg_len: MSG.len() as u16,
+            }],
+            &[&PUBKEY[..], &SIG].concat(),
+        );
+        let ixs = [holder, (SECP256R1_ID, data)];
+        assert!(verify(&ixs, 1, MSG).is_ok());
+        assert!(verify(&ixs, AUTO_VERIFY_INSTRUCTION_INDEX, MSG).is_ok());
+    }
+
+    #[test]
+    fn mismatched_or_truncated_tables_are_rejected() {
+        let ixs = [(SECP256R1_ID, single_signature_data())];
+        assert_eq!(
+            verify(&ixs, 0, b"authenticator data || client data hasH").unwrap_err(),
+            LazorKitError::Secp256r1DataMismatch.into()
+        );
+
+        let mut truncated = single_signature_data();
+        truncated[0] = 20; // claims more entries than the data holds
+        assert_eq!(
+            verify(&[(SECP256R1_ID, truncated)], 0, MSG).unwrap_err(),
+            LazorKitError::Secp256r1InvalidLength.into()
+        );
+        assert_eq!(
+            verify(&[(SECP256R1_ID, vec![0, 0])], 0, MSG).unwrap_err(),
+            LazorKitError::Secp256r1HeaderMismatch.into()
+        );
+    }
+
+    /// `r || s` with `s` = n/2 + `delta`
+    fn signature_with_s(delta: u8) -> [u8; 64] {
+        let mut signature = [1u8; 64];
+        signature[32..].copy_from_slice(&SECP256R1_HALF_ORDER);
+        let mut carry = delta;
+        for byte in signature[32..].iter_mut().rev() {
+            let (sum, overflow) = byte.overflowing_add(carry);
+            *byte = sum;
+            carry = overflow as u8;
+            if carry == 0 {
+                break;
+            }
+        }
+        signature
+    }
+
+    #[test]
+    fn low_s_boundary() {
+        assert!(is_low_s(&signature_with_s(0)));
+        assert!(!is_low_s(&signature_with_s(1)));
+        assert!(is_low_s(&[0u8; 64]));
+        assert!(!is_low_s(&[0xff; 64]));
+        assert!(!is_low_s(&[0u8; 63]));
+    }
+
+    fn parse(json: &str) -> Result<ClientData<'_>> {
+        ClientData::parse(json.as_bytes())
+    }
+
+    #[test]
+    fn client_data_fields_are_extracted() {
+        let client_data = parse(
+            r#"{"type":"webauthn.get","challenge":"AAEC","origin":"https://lazor.sh","crossOrigin":false,"other_keys_can_be_added_here":"do not compare clientDataJSON against a template. See https://goo.gl/yabPex"}"#,
+        )
+        .unwrap();
+        assert_eq!(client_data.ty, Some(&b"webauthn.get"[..]));
+        assert_eq!(client_data.challenge, Some(&b"AAEC"[..]));
+        assert_eq!(client_data.origin, Some(&b"https://lazor.sh"[..]));
+        assert!(!client_data.cross_origin);
+
+        let client_data = parse(
+            " { \"crossOrigin\" : true ,\n\"tokenBinding\": {\"status\": \"present\", \"id\": \"x\\u00e9\"}, \"n\": [1, -0.5, 2e10, null, {}] } ",
+        )
+        .unwrap();
+        assert!(client_data.cross_origin);
+        assert_eq!(client_data.origin, None);
+        assert!(parse("{}").is_ok());
+    }
+
+    #[test]
+    fn malformed_client_data_is_rejected() {
+        let parse_error: Error = LazorKitError::ClientDataJsonParseError.into();
+        for json in [
+            "",
+            "[]",
+            "{",
+            r#"{"type":"webauthn.get",}"#,
+            r#"{"type":"webauthn.get"} x"#,
+            r#"{"type":"webauthn.get"}{}"#,
+            r#"{"type":"web\nauthn"}"#,
+            r#"{"origin":"https:\/\/lazor.sh"}"#,
+            r#"{"crossOrigin":"true"}"#,
+            r#"{"a":tru}"#,
+            r#"{"a":[[[[1]]]]}"#,
+        ] {
+            assert_eq!(parse(json).unwrap_err(), parse_error, "{json}");
+        }
+        assert_eq!(
+            parse(r#"{"origin":"a","origin":"b"}"#).unwrap_err(),
+            LazorKitError::ClientDataDuplicateKey.into()
+        );
+        assert_eq!(
+            parse(r#"{"a":"\x"}"#).unwrap_err(),
+            LazorKitError::ClientDataInvalidEscape.into()
+        );
+        assert_eq!(
+            parse(r#"{"a":"\u12g4"}"#).unwrap_err(),
+            LazorKitError::ClientDataInvalidEscape.into()
+        );
+    }
+
+    #[test]
+    fn numbers_follow_the_json_grammar() {
+        for number in [
+            "0", "-0", "7", "-12", "1.5", "0.25", "1e9", "1E+9", "-2.5e-3",
+        ] {
+            assert!(parse(&format!(r#"{{"a":{number}}}"#)).is_ok(), "{number}");
+        }
+        for number in [
+            "1-2e+", "-", "01", "1.", ".5", "1e", "1e+", "--1", "+1", "1.2.3", "0x10",
+        ] {
+            assert_eq!(
+                parse(&format!(r#"{{"a":{number}}}"#)).unwrap_err(),
+                LazorKitError::ClientDataJsonParseError.into(),
+                "{number}"
+            );
+        }
+    }
+
+    /// P-256 generator point, a valid ES256 public key
+    const GX: [u8; 32] = [
+        0x6b, 0x17, 0xd1, 0xf2, 0xe1, 0x2c, 0x42, 0x47, 0xf8, 0xbc, 0xe6, 0xe5, 0x63, 0xa4, 0x40,
+        0xf2, 0x77, 0x03, 0x7d, 0x81, 0x2d, 0xeb, 0x33, 0xa0, 0xf4, 0xa1, 0x39, 0x45, 0xd8, 0x98,
+        0xc2, 0x96,
+    ];
+    const GY: [u8; 32] = [
+        0x4f, 0xe3, 0x42, 0xe2, 0xfe, 0x1a, 0x7f, 0x9b, 0x8e, 0xe7, 0xeb, 0x4a, 0x7c, 0x0f, 0x9e,
+        0x16, 0x2b, 0xce, 0x33, 0x57, 0x6b, 0x31, 0x5e, 0xce, 0xcb, 0xb6, 0x40, 0x68, 0x37, 0xbf,
+        0x51, 0xf5,
+    ];
+    /// Map header, then kty: EC2, alg: ES256, crv: P-256 as authenticators encode them
+    const COSE_PREFIX: [u8; 7] = [0xa5, 0x01, 0x02, 0x03, 0x26, 0x20, 0x01];
+
+    fn cose_key(x: &[u8], y: &[u8]) -> Vec<u8> {
+        let mut key = COSE_PREFIX.to_vec();
+        key.extend_from_slice(&[0x21, 0x58, x.len() as u8]);
+        key.extend_from_slice(x);
+        key.extend_from_slice(&[0x22, 0x58, y.len() as u8]);
+        key.extend_from_slice(y);
+        key
+    }
+
+    fn compressed_g() -> [u8; PASSKEY_SIZE] {
+        let mut key = [0u8; PASSKEY_SIZE];
+        key[0] = 0x03; // GY is odd
+        key[1..].copy_from_slice(&GX);
+        key
+    }
+
+    #[test]
+    fn cose_key_is_decoded_and_compressed() {
+        assert_eq!(
+            cose_p256_public_key(&cose_key(&GX, &GY)).unwrap(),
+            compressed_g()
+        );
+
+        // entries may come in any order and unknown scalar labels are skipped
+        let mut key = cose_key(&GX, &GY);
+        key[0] = 0xa6;
+        key.extend_from_slice(&[0x38, 0x63, 0x43, 1, 2, 3]);
+        assert_eq!(cose_p256_public_key(&key).unwrap(), compressed_g());
+    }
+
+    #[test]
+    fn malformed_cose_keys_are_rejected() {
+        let invalid: Error = LazorKitError::InvalidCredentialPublicKey.into();
+        let mut wrong_alg = cose_key(&GX, &GY);
+        wrong_alg[4] = 0x38; // alg: -25
+        wrong_alg.insert(5, 0x18);
+        let mut duplicate = cose_key(&GX, &GY);
+        duplicate[0] = 0xa6;
+        duplicate.extend_from_slice(&[0x01, 0x02]);
+        let mut nested = cose_key(&GX, &GY);
+        nested[0] = 0xa6;
+        nested.extend_from_slice(&[0x04, 0xa0]);
+        let mut missing_y = cose_key(&GX, &GY);
+        missing_y[0] = 0xa4;
+        for key in [
+            wrong_alg,
+            duplicate,
+            nested,
+            missing_y,
+            cose_key(&GX[..31], &GY),
+            cose_key(&GX, &GY)[..60].to_vec(),
+            vec![0x80],
+        ] {
+            assert_eq!(cose_p256_public_key(&key).unwrap_err(), invalid);
+        }
+
+        let mut off_curve = GY;
+        off_curve[31] ^= 1;
+        assert_eq!(
+            cose_p256_public_key(&cose_key(&GX, &off_curve)).unwrap_err(),
+            LazorKitError::PasskeyNotOnCurve.into()
+        );
+    }
+
+    fn registration_data(credential_id: &[u8], public_key: &[u8]) -> Vec<u8> {
+        let mut data = [0x11u8; 32].to_vec();
+        data.push(AUTH_FLAG_USER_PRESENT | AUTH_FLAG_ATTESTED_CREDENTIAL_DATA);
+        data.extend_from_slice(&[0; 4]);
+        data.extend_from_slice(&[0xaa; AAGUID_SIZE]);
+        data.extend_from_slice(&(credential_id.len() as u16).to_be_bytes());
+        data.extend_from_slice(credential_id);
+        data.extend_from_slice(public_key);
+        data
+    }
+
+    #[test]
+    fn registration_must_attest_the_registered_key() {
+        let register = |passkey: &[u8; PASSKEY_SIZE], data: &[u8]| {
+            verify_device_registration(
+                &[[0xaa; AAGUID_SIZE]],
+                Some([0x11; 32]),
+                crate::state::DeviceKind::Passkey,
+                passkey,
+                b"credential",
+                false,
+                false,
+                Some(data),
+            )
+        };
+        let data = registration_data(b"credential", &cose_key(&GX, &GY));
+        assert_eq!(
+            register(&compressed_g(), &data).unwrap(),
+            Some([0xaa; AAGUID_SIZE])
+        );
+
+        let mut other_key = compressed_g();
+        other_key[0] = 0x02;
+        assert_eq!(
+            register(&other_key, &data).unwrap_err(),
+            LazorKitError::AttestationCredentialMismatch.into()
+        );
+    }
+
+    #[test]
+    fn policy_data_hash_covers_the_enrolled_device() {
+        let device = crate::instructions::NewWalletDeviceArgs {
+            device_kind: crate::state::DeviceKind::Passkey,
+            passkey_pubkey: compressed_g().to_vec(),
+            credential_id: b"credential".to_vec(),
+            rp_id_hash: None,
+            backup_eligible: false,
+            backup_state: false,
+            attestation_authenticator_data: None,
+            passkey_proof: None,
+        };
+        let plain = policy_data_hash(b"policy", None).unwrap();
+        assert_eq!(plain, hash(b"policy").to_bytes());
+
+        let enrolled = policy_data_hash(b"policy", Some(&device)).unwrap();
+        assert_ne!(enrolled, plain);
+        let other = crate::instructions::NewWalletDeviceArgs {
+            credential_id: b"other".to_vec(),
+            ..device
+        };
+        assert_ne!(policy_data_hash(b"policy", Some(&other)).unwrap(), enrolled);
+    }
+}
@@ -0,0 +1,246 @@
+import * as anchor from '@coral-xyz/anchor';
+import { expect } from 'chai';
+import { sendAndConfirmTransaction, Transaction } from '@solana/web3.js';
+import * as crypto from 'crypto';
+import * as dotenv from 'dotenv';
+import { sha256 } from 'js-sha256';
+import { base64, bs58 } from '@coral-xyz/anchor/dist/cjs/utils/bytes';
+import {
+  buildSignedMessage,
+  computeDomainSeparator,
+  LazorkitClient,
+} from '../contract-integration';
+import { buildSecp256r1VerifyIx } from '../contract-integration/webauthn/secp256r1';
+dotenv.config();
+
+const ORIGIN = 'https://lazor.sh';
+const RP_ID = 'lazor.sh';
+
+// Compute unit budget for a verify_signature call, including clientDataJSON parsing
+const VERIFY_SIGNATURE_CU_BUDGET = 60_000;
+// Compute unit budget for creating a wallet: the default limit of an instruction, so wallets
+// can be created without a compute budget instruction. Compressed passkeys are decompressed
+// on-chain to check they lie on the curve, which dominates the cost.
+const CREATE_SMART_WALLET_CU_BUDGET = 200_000;
+
+const SECP256R1_ORDER = new anchor.BN(
+  'ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551',
+  16
+);
+
+describe('Test passkey registration and clientDataJSON parsing compute usage', () => {
+  const connection = new anchor.web3.Connection(
+    process.env.RPC_URL || 'http://localhost:8899',
+    'confirmed'
+  );
+
+  const lazorkitProgram = new LazorkitClient(connection);
+
+  const payer = anchor.web3.Keypair.fromSecretKey(
+    bs58.decode(process.env.PRIVATE_KEY!)
+  );
+
+  const { privateKey, publicKey } = crypto.generateKeyPairSync('ec', {
+    namedCurve: 'prime256v1',
+  });
+  const jwk = publicKey.export({ format: 'jwk' });
+  const y = Buffer.from(jwk.y!, 'base64url');
+  const passkeyPubkey = Array.from(
+    Buffer.concat([
+      Buffer.from([y[31] & 1 ? 0x03 : 0x02]),
+      Buffer.from(jwk.x!, 'base64url'),
+    ])
+  );
+
+  const smartWalletId = lazorkitProgram.generateWalletId();
+  const smartWallet = lazorkitProgram.smartWalletPda(smartWalletId);
+  const walletDevice = lazorkitProgram.walletDevicePda(
+    smartWallet,
+    passkeyPubkey
+  );
+
+  /** Sign `authenticatorData || sha256(clientDataJSON)` as a passkey would, with low-S */
+  function signAssertion(authenticatorData: Buffer, clientDataJson: Buffer) {
+    const message = Buffer.concat([
+      authenticatorData,
+      Buffer.from(sha256.arrayBuffer(clientDataJson)),
+    ]);
+    const signature = crypto.sign('sha256', message, {
+      key: privateKey,
+      dsaEncoding: 'ieee-p1363',
+    });
+    const s = new anchor.BN(signature.subarray(32));
+    if (s.gt(SECP256R1_ORDER.shrn(1))) {
+      SECP256R1_ORDER.sub(s)
+        .toArrayLike(Buffer, 'be', 32)
+        .copy(signature, 32);
+    }
+    return { message, signature };
+  }
+
+  /** Simulate verify_signature for a clientDataJSON body and return the units consumed */
+  async function measure(clientDataFields: (challenge: string) => string) {
+    const config = await lazorkitProgram.getConfigData();
+    const messageHash = new Uint8Array(32).fill(7);
+    const challenge = buildSignedMessage(
+      messageHash,
+      computeDomainSeparator(
+        lazorkitProgram.programId,
+        config.deploymentTag,
+        smartWallet
+      )
+    );
+
+    const clientDataJson = Buffer.from(
+      clientDataFields(challenge.toString('base64url'))
+    );
+    const authenticatorData = Buffer.concat([
+      Buffer.from(sha256.arrayBuffer(RP_ID)),
+      Buffer.from([0x05]), // user present | user verified
+      Buffer.alloc(4), // authenticators without a signature counter report 0
+    ]);
+    const { message, signature } = signAssertion(
+      authenticatorData,
+      clientDataJson
+    );
+
+    const ix = await lazorkitProgram.program.methods
+      .verifySignature({
+        passkeyPubkey,
+        signature,
+        clientDataJsonRaw: clientDataJson,
+        authenticatorDataRaw: authenticatorData,
+        verifyInstructionIndex: 0,
+        messageHash: Array.from(messageHash),
+        coSigners: [],
+      })
+      .accountsPartial({
+        config: lazorkitProgram.configPda(),
+        smartWallet,
+        smartWalletData: lazorkitProgram.smartWalletDataPda(smartWallet),
+        walletDevice,
+        ixSysvar: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+        slotHashes: null,
+        payer: null,
+      })
+      .instruction();
+
+    const txn = new Transaction().add(
+      buildSecp256r1VerifyIx(message, passkeyPubkey, signature),
+      ix
+    );
+    txn.feePayer = payer.publicKey;
+    txn.recentBlockhash = (await connection.getLatestBlockhash()).blockhash;
+
+    const { value } = await connection.simulateTransaction(txn, [payer]);
+    expect(value.err).to.be.null;
+    expect(value.logs.join('\n')).to.contain(': true');
+    return value.unitsConsumed!;
+  }
+
+  before(async () => {
+    const programConfig = await connection.getAccountInfo(
+      lazorkitProgram.configPda()
+    );
+    if (programConfig === null) {
+      const ix = await lazorkitProgram.buildInitializeInstruction(
+        payer.publicKey,
+        new anchor.BN(1)
+      );
+      await sendAndConfirmTransaction(connection, new Transaction().add(ix), [
+        payer,
+      ]);
+    }
+
+    // assertions are rejected until the deployment trusts an origin and relying party
+    const configureTxn = new Transaction().add(
+      await lazorkitProgram.program.methods
+        .updateAllowedOrigins([Array.from(sha256.array(ORIGIN))])
+        .accountsPartial({
+          authority: payer.publicKey,
+          config: lazorkitProgram.configPda(),
+        })
+        .instruction(),
+      await lazorkitProgram.program.methods
+        .updateRpIdHash(Array.from(sha256.array(RP_ID)))
+        .accountsPartial({
+          authority: payer.publicKey,
+          config: lazorkitProgram.configPda(),
+        })
+        .instruction()
+    );
+    await sendAndConfirmTransaction(connection, configureTxn, [payer]);
+
+    const { transaction } = await lazorkitProgram.createSmartWalletTransaction(
+      {
+        payer: payer.publicKey,
+        passkeyPubkey,
+        credentialIdBase64: base64.encode(crypto.randomBytes(16)),
+        policyInstruction: null,
+        isPayForUser: true,
+        smartWalletId,
+      }
+    );
+    await sendAndConfirmTransaction(connection, transaction, [payer]);
+  });
+
+  /** Simulate creating a wallet for a fresh passkey and return the units consumed */
+  async function measureRegistration(compressed: boolean) {
+    const { publicKey } = crypto.generateKeyPairSync('ec', {
+      namedCurve: 'prime256v1',
+    });
+    const jwk = publicKey.export({ format: 'jwk' });
+    const x = Buffer.from(jwk.x!, 'base64url');
+    const y = Buffer.from(jwk.y!, 'base64url');
+    const key = compressed
+      ? Buffer.concat([Buffer.from([y[31] & 1 ? 0x03 : 0x02]), x])
+      : Buffer.concat([Buffer.from([0x04]), x, y]);
+
+    const { transaction } = await lazorkitProgram.createSmartWalletTransaction(
+      {
+        payer: payer.publicKey,
+        passkeyPubkey: Array.from(key),
+        credentialIdBase64: base64.encode(crypto.randomBytes(16)),
+        policyInstruction: null,
+        isPayForUser: true,
+      }
+    );
+    const { value } = await connection.simulateTransaction(transaction, [
+      payer,
+    ]);
+    expect(value.err).to.be.null;
+    return value.unitsConsumed!;
+  }
+
+  it('Registers a compressed passkey within the compute budget', async () => {
+    const units = await measureRegistration(true);
+    console.log('create_smart_wallet (compressed passkey) CU: ', units);
+    expect(units).to.be.lessThan(CREATE_SMART_WALLET_CU_BUDGET);
+  });
+
+  it('Registers an uncompressed passkey within the compute budget', async () => {
+    const units = await measureRegistration(false);
+    console.log('create_smart_wallet (uncompressed passkey) CU: ', units);
+    expect(units).to.be.lessThan(CREATE_SMART_WALLET_CU_BUDGET);
+  });
+
+  it('Verifies a minimal clientDataJSON within the compute budget', async () => {
+    const units = await measure(
+      (challenge) =>
+        `{"type":"webauthn.get","challenge":"${challenge}","origin":"${ORIGIN}"}`
+    );
+    console.log('verify_signature (minimal clientDataJSON) CU: ', units);
+    expect(units).to.be.lessThan(VERIFY_SIGNATURE_CU_BUDGET);
+  });
+
+  it('Verifies a browser clientDataJSON within the compute budget', async () => {
+    const units = await measure(
+      (challenge) =>
+        `{"type":"webauthn.get","challenge":"${challenge}","origin":"${ORIGIN}","crossOrigin":false,` +
+        `"tokenBinding":{"status":"supported"},` +
+        `"other_keys_can_be_added_here":"do not compare clientDataJSON against a template. See https://goo.gl/yabPex"}`
+    );
+    console.log('verify_signature (browser clientDataJSON) CU: ', units);
+    expect(units).to.be.lessThan(VERIFY_SIGNATURE_CU_BUDGET);
+  });
+});
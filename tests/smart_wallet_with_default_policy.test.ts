@@ -28,7 +28,8 @@ describe('Test smart wallet with default policy', () => {
 
     if (programConfig === null) {
       const ix = await lazorkitProgram.buildInitializeInstruction(
-        payer.publicKey
+        payer.publicKey,
+        new anchor.BN(1)
       );
       const txn = new Transaction().add(ix);
 